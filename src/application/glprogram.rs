@@ -280,6 +280,15 @@ impl UniformData for Vec4f {
     }
 }
 
+impl UniformData for Color {
+    fn set_uniform(&self, program: &Program, uniform: &str) {
+        let location = program.get_uniform_id(uniform);
+        unsafe {
+            gl::Uniform4f(location, self.r, self.g, self.b, self.a);
+        }
+    }
+}
+
 impl UniformData for Mat2f {
     fn set_uniform(&self, program: &Program, uniform: &str) {
         let location = program.get_uniform_id(uniform);
@@ -0,0 +1,551 @@
+/*
+    2D polygon algorithms.
+
+    Polygons are slices of `Vec2f` vertices, implicitly closed.
+    Counter-clockwise is the positive winding, with y pointing up.
+*/
+
+use crate::linalg::*;
+
+fn cross(o: Vec2f, a: Vec2f, b: Vec2f) -> f32 {
+    (a.x() - o.x()) * (b.y() - o.y()) - (a.y() - o.y()) * (b.x() - o.x())
+}
+
+fn perp_dot(a: Vec2f, b: Vec2f) -> f32 {
+    a.x() * b.y() - a.y() * b.x()
+}
+
+// Area & Winding
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Winding {
+    CounterClockwise,
+    Clockwise,
+}
+
+/// Shoelace formula, positive for counter-clockwise polygons.
+pub fn signed_area(polygon: &[Vec2f]) -> f32 {
+    let n = polygon.len();
+    (0..n).fold(0.0, |sum, i| sum + perp_dot(polygon[i], polygon[(i + 1) % n])) * 0.5
+}
+
+pub fn area(polygon: &[Vec2f]) -> f32 {
+    signed_area(polygon).abs()
+}
+
+pub fn winding(polygon: &[Vec2f]) -> Winding {
+    if signed_area(polygon) >= 0.0 {
+        Winding::CounterClockwise
+    } else {
+        Winding::Clockwise
+    }
+}
+
+/// Reverses `polygon` in place if needed so it has the given winding.
+pub fn set_winding(polygon: &mut [Vec2f], target: Winding) {
+    if winding(polygon) != target {
+        polygon.reverse();
+    }
+}
+
+// Point in Polygon
+
+/// Even-odd rule, so nested loops act as holes. Points exactly on an edge may go either way.
+pub fn contains_point(polygon: &[Vec2f], point: Vec2f) -> bool {
+    let n = polygon.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + n - 1) % n]);
+        if (a.y() > point.y()) != (b.y() > point.y()) {
+            let x = a.x() + (point.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+            if point.x() < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+// Convex Hull
+
+/// Andrew's monotone chain. Returns the hull counter-clockwise without collinear points.
+pub fn convex_hull(points: &[Vec2f]) -> Vec<Vec2f> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x().total_cmp(&b.x()).then(a.y().total_cmp(&b.y())));
+    sorted.dedup_by(|a, b| a.x() == b.x() && a.y() == b.y());
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let half = |points: &mut dyn Iterator<Item = &Vec2f>| {
+        let mut chain: Vec<Vec2f> = Vec::new();
+        for &p in points {
+            while chain.len() >= 2 && cross(chain[chain.len() - 2], chain[chain.len() - 1], p) <= 0.0 {
+                chain.pop();
+            }
+            chain.push(p);
+        }
+        // The last point starts the other half.
+        chain.pop();
+        chain
+    };
+    let mut hull = half(&mut sorted.iter());
+    hull.extend(half(&mut sorted.iter().rev()));
+    hull
+}
+
+// Segment Intersection
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentIntersection {
+    Point(Vec2f),
+    /// Collinear segments sharing the sub-segment between the two points.
+    Overlap(Vec2f, Vec2f),
+}
+
+/// Intersection of the closed segments `a0 a1` and `b0 b1`.
+pub fn segment_intersection(a0: Vec2f, a1: Vec2f, b0: Vec2f, b1: Vec2f) -> Option<SegmentIntersection> {
+    let r = a1 - a0;
+    let s = b1 - b0;
+    let denom = perp_dot(r, s);
+    let offset = b0 - a0;
+
+    let rr = r.length_squared();
+    if rr == 0.0 {
+        // `a` is a single point, it has to lie on `b`.
+        let on_line = perp_dot(offset, s).abs() <= f32::EPSILON * offset.length() * s.length();
+        let between = (a0 - b0).dot(&(a0 - b1)) <= 0.0;
+        return (on_line && between).then_some(SegmentIntersection::Point(a0));
+    }
+
+    if denom.abs() <= f32::EPSILON * r.length() * s.length() {
+        if perp_dot(offset, r).abs() > f32::EPSILON * offset.length() * r.length() {
+            return None;
+        }
+        // Collinear, project `b` onto `a` and clip to [0, 1].
+        let t0 = offset.dot(&r) / rr;
+        let t1 = (b1 - a0).dot(&r) / rr;
+        let (lo, hi) = (t0.min(t1).max(0.0), t0.max(t1).min(1.0));
+        return match lo.partial_cmp(&hi)? {
+            std::cmp::Ordering::Greater => None,
+            std::cmp::Ordering::Equal => Some(SegmentIntersection::Point(a0 + r * lo)),
+            std::cmp::Ordering::Less => Some(SegmentIntersection::Overlap(a0 + r * lo, a0 + r * hi)),
+        };
+    }
+
+    let t = perp_dot(offset, s) / denom;
+    let u = perp_dot(offset, r) / denom;
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some(SegmentIntersection::Point(a0 + r * t))
+    } else {
+        None
+    }
+}
+
+// Offsetting
+
+/// Moves every edge outwards by `distance`, inwards when negative, regardless of winding.\
+/// Corners whose miter would exceed `miter_limit * distance` are beveled, adding a vertex.
+/// Large inward offsets can produce self-intersecting output.
+pub fn offset_polygon(polygon: &[Vec2f], distance: f32, miter_limit: f32) -> Vec<Vec2f> {
+    // Zero-length edges have no normal, drop repeated vertices first.
+    let mut polygon = polygon.to_vec();
+    polygon.dedup();
+    while polygon.len() > 1 && polygon.first() == polygon.last() {
+        polygon.pop();
+    }
+    let n = polygon.len();
+    if n < 3 {
+        return polygon;
+    }
+    // Right-hand normals point outwards for counter-clockwise polygons.
+    let d = match winding(&polygon) {
+        Winding::CounterClockwise => distance,
+        Winding::Clockwise => -distance,
+    };
+    let normal = |a: Vec2f, b: Vec2f| {
+        let e = (b - a).normalize();
+        Vec2f::from([[e.y(), -e.x()]])
+    };
+
+    let mut out = Vec::with_capacity(n);
+    for i in 0..n {
+        let (prev, p, next) = (polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]);
+        let (n0, n1) = (normal(prev, p), normal(p, next));
+        let denom = 1.0 + n0.dot(&n1);
+        let miter = (n0 + n1) / denom;
+        if denom > f32::EPSILON && miter.length() <= miter_limit {
+            out.push(p + miter * d);
+        } else {
+            out.push(p + n0 * d);
+            out.push(p + n1 * d);
+        }
+    }
+    out
+}
+
+// Triangulation
+
+/// Ear-clipping triangulation of `outer` with `holes` cut out.\
+/// Returns triangle indices into the vertex list made of `outer` followed by each hole in order,
+/// as laid out by `flatten`. Windings of the inputs do not matter, triangles come out counter-clockwise.\
+/// Holes must lie inside `outer`. A hole with no edge of `outer` to its right cannot be bridged
+/// and is skipped, leaving its vertices unreferenced.
+pub fn triangulate(outer: &[Vec2f], holes: &[&[Vec2f]]) -> Vec<u32> {
+    let mut vertices = outer.to_vec();
+    let mut ring: Vec<u32> = (0..outer.len() as u32).collect();
+    if winding(outer) == Winding::Clockwise {
+        ring.reverse();
+    }
+
+    let mut loops: Vec<Vec<u32>> = Vec::with_capacity(holes.len());
+    for hole in holes {
+        let base = vertices.len() as u32;
+        vertices.extend_from_slice(hole);
+        let mut indices: Vec<u32> = (base..base + hole.len() as u32).collect();
+        if winding(hole) == Winding::CounterClockwise {
+            indices.reverse();
+        }
+        if indices.len() >= 3 {
+            loops.push(indices);
+        }
+    }
+
+    // Bridge holes from right to left so later bridges never cross earlier ones.
+    let rightmost = |l: &Vec<u32>| {
+        l.iter()
+            .map(|&i| vertices[i as usize])
+            .fold(f32::NEG_INFINITY, |m, v| m.max(v.x()))
+    };
+    loops.sort_by(|a, b| rightmost(b).total_cmp(&rightmost(a)));
+    for hole in loops {
+        bridge_hole(&vertices, &mut ring, &hole);
+    }
+
+    clip_ears(&vertices, ring)
+}
+
+/// Vertex positions as interleaved `x, y` pairs, matching the indices of `triangulate`.
+pub fn flatten(outer: &[Vec2f], holes: &[&[Vec2f]]) -> Vec<f32> {
+    std::iter::once(outer)
+        .chain(holes.iter().copied())
+        .flatten()
+        .flat_map(|v| [v.x(), v.y()])
+        .collect()
+}
+
+/// Splices a clockwise `hole` into the counter-clockwise `ring` through a mutually visible bridge.
+/// Leaves `ring` untouched when no ring edge lies to the right of the hole.
+fn bridge_hole(vertices: &[Vec2f], ring: &mut Vec<u32>, hole: &[u32]) {
+    let at = |i: u32| vertices[i as usize];
+    let (m_slot, _) = hole
+        .iter()
+        .enumerate()
+        .max_by(|(_, &a), (_, &b)| at(a).x().total_cmp(&at(b).x()).then(at(a).y().total_cmp(&at(b).y())))
+        .unwrap();
+    let m = at(hole[m_slot]);
+
+    // Cast a ray towards +x and find the closest edge it hits.
+    let n = ring.len();
+    let mut best: Option<(f32, usize)> = None;
+    for i in 0..n {
+        let (a, b) = (at(ring[i]), at(ring[(i + 1) % n]));
+        // Only upward edges face the hole from the right in a counter-clockwise ring.
+        if a.y() > m.y() || b.y() < m.y() || a.y() == b.y() {
+            continue;
+        }
+        let x = a.x() + (m.y() - a.y()) * (b.x() - a.x()) / (b.y() - a.y());
+        if x < m.x() || best.is_some_and(|(bx, _)| bx <= x) {
+            continue;
+        }
+        let slot = if a.x() > b.x() { i } else { (i + 1) % n };
+        best = Some((x, slot));
+    }
+    let Some((x, mut p_slot)) = best else {
+        return;
+    };
+    let intersection = Vec2f::from([[x, m.y()]]);
+    let p = at(ring[p_slot]);
+
+    // A reflex vertex inside triangle (m, intersection, p) would block the view of p,
+    // pick the one making the smallest angle with the ray instead.
+    if p.x() != intersection.x() || p.y() != intersection.y() {
+        let mut best_angle = f32::INFINITY;
+        for i in 0..n {
+            let v = at(ring[i]);
+            let (prev, next) = (at(ring[(i + n - 1) % n]), at(ring[(i + 1) % n]));
+            if cross(prev, v, next) >= 0.0 || !in_triangle(v, m, intersection, p) {
+                continue;
+            }
+            let d = v - m;
+            let angle = d.y().abs() / d.length();
+            if angle < best_angle {
+                best_angle = angle;
+                p_slot = i;
+            }
+        }
+    }
+
+    let mut spliced = Vec::with_capacity(ring.len() + hole.len() + 2);
+    spliced.extend_from_slice(&ring[..=p_slot]);
+    spliced.extend(hole[m_slot..].iter().chain(&hole[..=m_slot]));
+    spliced.extend_from_slice(&ring[p_slot..]);
+    *ring = spliced;
+}
+
+/// Inclusive point in counter-clockwise or clockwise triangle test.
+fn in_triangle(p: Vec2f, a: Vec2f, b: Vec2f, c: Vec2f) -> bool {
+    let (d0, d1, d2) = (cross(a, b, p), cross(b, c, p), cross(c, a, p));
+    let negative = d0 < 0.0 || d1 < 0.0 || d2 < 0.0;
+    let positive = d0 > 0.0 || d1 > 0.0 || d2 > 0.0;
+    !(negative && positive)
+}
+
+fn clip_ears(vertices: &[Vec2f], mut ring: Vec<u32>) -> Vec<u32> {
+    let at = |i: u32| vertices[i as usize];
+    let same = |a: Vec2f, b: Vec2f| a.x() == b.x() && a.y() == b.y();
+    let mut indices = Vec::with_capacity(ring.len().saturating_sub(2) * 3);
+    let mut i = 0;
+    let mut stalled = 0;
+
+    while ring.len() > 3 {
+        let n = ring.len();
+        i %= n;
+        let (ia, ib, ic) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+        let (a, b, c) = (at(ia), at(ib), at(ic));
+        let turn = cross(a, b, c);
+
+        if turn == 0.0 {
+            // Collinear or spike vertex, drop it without emitting a triangle.
+            ring.remove(i);
+            stalled = 0;
+            continue;
+        }
+        let ear = turn > 0.0
+            && !ring.iter().any(|&j| {
+                let v = at(j);
+                !same(v, a) && !same(v, b) && !same(v, c) && in_triangle(v, a, b, c)
+            });
+        // Numerically degenerate input can leave no ear, clip anyway to terminate.
+        // The forced triangle may be reflex, flip it to stay counter-clockwise.
+        if ear || stalled > n {
+            indices.extend_from_slice(&if turn > 0.0 { [ia, ib, ic] } else { [ia, ic, ib] });
+            ring.remove(i);
+            stalled = 0;
+        } else {
+            i += 1;
+            stalled += 1;
+        }
+    }
+    if let [ia, ib, ic] = ring[..] {
+        let turn = cross(at(ia), at(ib), at(ic));
+        if turn > 0.0 {
+            indices.extend_from_slice(&[ia, ib, ic]);
+        } else if turn < 0.0 {
+            indices.extend_from_slice(&[ia, ic, ib]);
+        }
+    }
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn vec2(x: f32, y: f32) -> Vec2f {
+        Vec2f::from([[x, y]])
+    }
+
+    fn square(center: Vec2f, half: f32) -> Vec<Vec2f> {
+        [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .map(|(x, y)| center + vec2(x, y) * half)
+            .to_vec()
+    }
+
+    fn star(rng: &mut StdRng, points: usize) -> Vec<Vec2f> {
+        (0..points)
+            .map(|i| {
+                let angle = i as f32 / points as f32 * std::f32::consts::TAU;
+                let radius = rng.gen_range(0.2..1.0);
+                vec2(angle.cos(), angle.sin()) * radius
+            })
+            .collect()
+    }
+
+    /// Every triangle is counter-clockwise and together they cover `expected_area`.
+    fn check_triangles(vertices: &[Vec2f], indices: &[u32], expected_area: f32) {
+        assert_eq!(indices.len() % 3, 0);
+        let mut total = 0.0;
+        for t in indices.chunks(3) {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| vertices[i as usize]);
+            let turn = cross(a, b, c);
+            assert!(turn >= 0.0, "clockwise triangle {t:?}");
+            total += turn * 0.5;
+        }
+        assert!((total - expected_area).abs() < 1e-3 * expected_area.max(1.0), "{total} != {expected_area}");
+    }
+
+    #[test]
+    fn triangles_are_counter_clockwise() {
+        let mut rng = StdRng::seed_from_u64(16);
+        for _ in 0..200 {
+            let points = rng.gen_range(3..40);
+            let mut polygon = star(&mut rng, points);
+            if rng.gen_bool(0.5) {
+                polygon.reverse();
+            }
+            let indices = triangulate(&polygon, &[]);
+            check_triangles(&polygon, &indices, area(&polygon));
+        }
+    }
+
+    #[test]
+    fn triangulate_with_holes() {
+        let outer = square(vec2(0.0, 0.0), 4.0);
+        let mut left = square(vec2(-2.0, 0.0), 1.0);
+        left.reverse();
+        let right = square(vec2(2.0, 1.0), 0.5);
+        let holes = [left.as_slice(), right.as_slice()];
+
+        let indices = triangulate(&outer, &holes);
+        let vertices: Vec<Vec2f> = flatten(&outer, &holes).chunks(2).map(|p| vec2(p[0], p[1])).collect();
+        check_triangles(&vertices, &indices, area(&outer) - area(&left) - area(&right));
+    }
+
+    #[test]
+    fn degenerate_rings_stay_counter_clockwise() {
+        // Collinear runs and repeated vertices leave rings without a clean ear.
+        let polygon = [
+            vec2(0.0, 0.0),
+            vec2(1.0, 0.0),
+            vec2(1.0, 0.0),
+            vec2(2.0, 0.0),
+            vec2(2.0, 2.0),
+            vec2(1.0, 1.0),
+            vec2(0.0, 2.0),
+            vec2(0.0, 2.0),
+        ];
+        for polygon in [polygon.to_vec(), polygon.iter().rev().copied().collect()] {
+            let indices = triangulate(&polygon, &[]);
+            check_triangles(&polygon, &indices, area(&polygon));
+        }
+    }
+
+    #[test]
+    fn offset_skips_zero_length_edges() {
+        let mut polygon = square(vec2(0.0, 0.0), 1.0);
+        polygon.insert(2, polygon[1]);
+        polygon.push(polygon[0]);
+        let out = offset_polygon(&polygon, 0.5, 4.0);
+        assert!(out.iter().all(|v| v.x().is_finite() && v.y().is_finite()), "{out:?}");
+        assert_eq!(out, offset_polygon(&square(vec2(0.0, 0.0), 1.0), 0.5, 4.0));
+        assert!((area(&out) - 9.0).abs() < 1e-4);
+
+        let collapsed = [vec2(1.0, 1.0); 4];
+        assert_eq!(offset_polygon(&collapsed, 0.5, 4.0), vec![vec2(1.0, 1.0)]);
+    }
+
+    #[test]
+    fn offset_winding_independent() {
+        let ccw = square(vec2(0.0, 0.0), 1.0);
+        let mut cw = ccw.clone();
+        cw.reverse();
+        for distance in [0.5, -0.5] {
+            let expected = (2.0 + 2.0 * distance) * (2.0 + 2.0 * distance);
+            assert!((area(&offset_polygon(&ccw, distance, 4.0)) - expected).abs() < 1e-4);
+            assert!((area(&offset_polygon(&cw, distance, 4.0)) - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn holes_outside_are_skipped() {
+        let outer = square(vec2(0.0, 0.0), 1.0);
+        let outside = square(vec2(5.0, 0.0), 1.0);
+        let indices = triangulate(&outer, &[&outside]);
+        assert!(indices.iter().all(|&i| (i as usize) < outer.len()), "{indices:?}");
+        check_triangles(&outer, &indices, area(&outer));
+    }
+
+    #[test]
+    fn contains_point_even_odd() {
+        let l_shape = [vec2(0.0, 0.0), vec2(2.0, 0.0), vec2(2.0, 1.0), vec2(1.0, 1.0), vec2(1.0, 2.0), vec2(0.0, 2.0)];
+        let reversed: Vec<Vec2f> = l_shape.iter().rev().copied().collect();
+        for polygon in [l_shape.as_slice(), &reversed] {
+            assert!(contains_point(polygon, vec2(0.5, 0.5)));
+            assert!(contains_point(polygon, vec2(1.5, 0.5)));
+            assert!(contains_point(polygon, vec2(0.5, 1.5)));
+            assert!(!contains_point(polygon, vec2(1.5, 1.5)));
+            assert!(!contains_point(polygon, vec2(-0.5, 0.5)));
+            assert!(!contains_point(polygon, vec2(0.5, 3.0)));
+        }
+        // A nested loop in the same list cancels out.
+        let mut nested = square(vec2(0.0, 0.0), 2.0);
+        nested.extend(square(vec2(0.0, 0.0), 1.0));
+        assert!(contains_point(&nested, vec2(1.5, 0.0)));
+        assert!(!contains_point(&nested, vec2(0.5, 0.0)));
+        assert!(!contains_point(&[], vec2(0.0, 0.0)));
+    }
+
+    #[test]
+    fn convex_hull_cases() {
+        assert!(convex_hull(&[]).is_empty());
+        assert_eq!(convex_hull(&[vec2(1.0, 2.0)]), vec![vec2(1.0, 2.0)]);
+        assert_eq!(convex_hull(&[vec2(3.0, 0.0), vec2(1.0, 2.0)]), vec![vec2(1.0, 2.0), vec2(3.0, 0.0)]);
+        assert_eq!(convex_hull(&[vec2(1.0, 2.0); 5]), vec![vec2(1.0, 2.0)]);
+
+        // Collinear points collapse to the two ends.
+        let line: Vec<Vec2f> = (0..5).map(|i| vec2(i as f32, 2.0 * i as f32)).rev().collect();
+        assert_eq!(convex_hull(&line), vec![vec2(0.0, 0.0), vec2(4.0, 8.0)]);
+
+        // Edge midpoints, duplicates and interior points are all dropped.
+        let mut points = square(vec2(0.0, 0.0), 1.0);
+        points.extend(square(vec2(0.0, 0.0), 1.0));
+        points.extend([vec2(0.0, -1.0), vec2(1.0, 0.0), vec2(0.0, 0.0), vec2(0.5, -0.2)]);
+        assert_eq!(convex_hull(&points), vec![vec2(-1.0, -1.0), vec2(1.0, -1.0), vec2(1.0, 1.0), vec2(-1.0, 1.0)]);
+
+        let mut rng = StdRng::seed_from_u64(16);
+        for _ in 0..100 {
+            let count = rng.gen_range(3..50);
+            let points: Vec<Vec2f> = (0..count).map(|_| vec2(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0))).collect();
+            let hull = convex_hull(&points);
+            let n = hull.len();
+            assert!(n >= 3 && winding(&hull) == Winding::CounterClockwise);
+            for i in 0..n {
+                let (a, b) = (hull[i], hull[(i + 1) % n]);
+                assert!(cross(a, b, hull[(i + 2) % n]) > 0.0, "not strictly convex {hull:?}");
+                assert!(points.iter().all(|&p| cross(a, b, p) >= -1e-6), "point outside {hull:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn segment_intersection_cases() {
+        use SegmentIntersection::*;
+        let p = |x0, y0, x1, y1| (vec2(x0, y0), vec2(x1, y1));
+        let intersect = |(a0, a1): (Vec2f, Vec2f), (b0, b1): (Vec2f, Vec2f)| segment_intersection(a0, a1, b0, b1);
+
+        assert_eq!(intersect(p(0.0, 0.0, 2.0, 2.0), p(0.0, 2.0, 2.0, 0.0)), Some(Point(vec2(1.0, 1.0))));
+        assert_eq!(intersect(p(0.0, 0.0, 1.0, 1.0), p(3.0, 0.0, 2.0, 1.0)), None);
+        assert_eq!(intersect(p(0.0, 0.0, 2.0, 0.0), p(0.0, 1.0, 2.0, 1.0)), None);
+
+        // Collinear, the overlap follows the direction of `a`.
+        assert_eq!(intersect(p(0.0, 0.0, 4.0, 0.0), p(5.0, 0.0, 2.0, 0.0)), Some(Overlap(vec2(2.0, 0.0), vec2(4.0, 0.0))));
+        assert_eq!(intersect(p(4.0, 0.0, 0.0, 0.0), p(1.0, 0.0, 2.0, 0.0)), Some(Overlap(vec2(2.0, 0.0), vec2(1.0, 0.0))));
+        assert_eq!(intersect(p(0.0, 0.0, 2.0, 0.0), p(3.0, 0.0, 4.0, 0.0)), None);
+        assert_eq!(intersect(p(0.0, 0.0, 2.0, 0.0), p(2.0, 0.0, 4.0, 0.0)), Some(Point(vec2(2.0, 0.0))));
+
+        // Touching at an endpoint.
+        assert_eq!(intersect(p(0.0, 0.0, 2.0, 0.0), p(1.0, 0.0, 1.0, 3.0)), Some(Point(vec2(1.0, 0.0))));
+        assert_eq!(intersect(p(0.0, 0.0, 2.0, 0.0), p(2.0, 0.0, 3.0, 3.0)), Some(Point(vec2(2.0, 0.0))));
+
+        // Degenerate segments.
+        assert_eq!(intersect(p(1.0, 1.0, 1.0, 1.0), p(0.0, 0.0, 2.0, 2.0)), Some(Point(vec2(1.0, 1.0))));
+        assert_eq!(intersect(p(1.0, 0.0, 1.0, 0.0), p(0.0, 0.0, 2.0, 2.0)), None);
+        assert_eq!(intersect(p(3.0, 3.0, 3.0, 3.0), p(0.0, 0.0, 2.0, 2.0)), None);
+        assert_eq!(intersect(p(0.0, 0.0, 2.0, 2.0), p(1.0, 1.0, 1.0, 1.0)), Some(Point(vec2(1.0, 1.0))));
+        assert_eq!(intersect(p(0.0, 0.0, 2.0, 2.0), p(3.0, 3.0, 3.0, 3.0)), None);
+        assert_eq!(intersect(p(1.0, 1.0, 1.0, 1.0), p(1.0, 1.0, 1.0, 1.0)), Some(Point(vec2(1.0, 1.0))));
+        assert_eq!(intersect(p(1.0, 1.0, 1.0, 1.0), p(2.0, 1.0, 2.0, 1.0)), None);
+    }
+}
@@ -1,6 +1,216 @@
 use crate::linalg::*;
 
+#[derive(Debug, Clone, Copy)]
+pub enum Projection {
+    /// `fov` is the vertical field of view.
+    Perspective { fov: Rad<f32>, near: f32, far: f32 },
+    /// `height` is the vertical extent of the view volume, the width follows the aspect ratio.
+    Orthographic { height: f32, near: f32, far: f32 },
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct Camera {
-    pub lookat : Vec4f,
-    pub up : Vec4f,
+    pub position: Vec3f,
+    pub target: Vec3f,
+    pub up: Vec3f,
+    pub projection: Projection,
+    pub aspect: f32,
+}
+
+impl Camera {
+    pub fn new(position: Vec3f, target: Vec3f, up: Vec3f, projection: Projection) -> Self {
+        Self {
+            position,
+            target,
+            up,
+            projection,
+            aspect: 1.0,
+        }
+    }
+
+    pub fn perspective(fov: impl Into<Rad<f32>>, aspect: f32, near: f32, far: f32) -> Self {
+        Self {
+            position: Vec3f::from([[0.0, 0.0, 1.0]]),
+            target: Vec3f::from([[0.0, 0.0, 0.0]]),
+            up: Vec3f::from([[0.0, 1.0, 0.0]]),
+            projection: Projection::Perspective { fov: fov.into(), near, far },
+            aspect,
+        }
+    }
+
+    pub fn orthographic(height: f32, aspect: f32, near: f32, far: f32) -> Self {
+        Self {
+            position: Vec3f::from([[0.0, 0.0, 1.0]]),
+            target: Vec3f::from([[0.0, 0.0, 0.0]]),
+            up: Vec3f::from([[0.0, 1.0, 0.0]]),
+            projection: Projection::Orthographic { height, near, far },
+            aspect,
+        }
+    }
+
+    /// Unit vector towards `target`, `-Z` when `position` and `target` coincide.
+    pub fn forward(&self) -> Vec3f {
+        let offset = self.target - self.position;
+        if offset.length_squared() > 0.0 {
+            offset.normalize()
+        } else {
+            Vec3f::from([[0.0, 0.0, -1.0]])
+        }
+    }
+
+    /// When looking along `up`, `-Z` stands in for it, or `Y` when looking along Z.
+    pub fn right(&self) -> Vec3f {
+        let forward = self.forward();
+        let right = forward.cross(&self.up);
+        if right.length_squared() > 1e-12 * self.up.length_squared() {
+            return right.normalize();
+        }
+        let up = if forward.z().abs() < 0.9 {
+            Vec3f::from([[0.0, 0.0, -1.0]])
+        } else {
+            Vec3f::from([[0.0, 1.0, 0.0]])
+        };
+        forward.cross(&up).normalize()
+    }
+
+    /// Up vector orthogonal to `forward` and `right`.
+    pub fn true_up(&self) -> Vec3f {
+        self.right().cross(&self.forward())
+    }
+
+    pub fn distance(&self) -> f32 {
+        self.position.distance_to(&self.target)
+    }
+
+    pub fn look_at(&mut self, target: Vec3f) {
+        self.target = target;
+    }
+
+    /// Moves both position and target, keeping the orientation.
+    pub fn translate(&mut self, offset: Vec3f) {
+        self.position += offset;
+        self.target += offset;
+    }
+
+    pub fn set_aspect(&mut self, aspect: f32) {
+        self.aspect = aspect;
+    }
+
+    /// Call on `glfw::WindowEvent::FramebufferSize`.
+    pub fn resize(&mut self, width: i32, height: i32) {
+        if width > 0 && height > 0 {
+            self.aspect = width as f32 / height as f32;
+        }
+    }
+
+    /// Built from `forward` and `true_up`, so it stays finite in the cases they guard.
+    pub fn view_matrix(&self) -> Mat4f {
+        Mat4f::look_at(self.position, self.position + self.forward(), self.true_up())
+    }
+
+    pub fn projection_matrix(&self) -> Mat4f {
+        match self.projection {
+            Projection::Perspective { fov, near, far } => {
+                Mat4f::perspective(fov, self.aspect, near, far)
+            }
+            Projection::Orthographic { height, near, far } => {
+                let half_h = height * 0.5;
+                let half_w = half_h * self.aspect;
+                Mat4f::ortho(-half_w, half_w, -half_h, half_h, near, far)
+            }
+        }
+    }
+
+    pub fn view_projection(&self) -> Mat4f {
+        self.projection_matrix() * self.view_matrix()
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::perspective(Deg(45.0), 1.0, 0.1, 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+        Vec3f::from([[x, y, z]])
+    }
+
+    fn assert_finite(m: &Mat4f) {
+        assert!((0..4).all(|i| (0..4).all(|j| m[i][j].is_finite())), "{m:?}");
+    }
+
+    fn assert_vec3_eq(a: Vec3f, b: Vec3f) {
+        assert!((a - b).length() < 1e-5, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn projection_follows_the_variant() {
+        let mut camera = Camera::perspective(Deg(60.0), 2.0, 0.5, 50.0);
+        let expected = Mat4f::perspective(Deg(60.0), 2.0, 0.5, 50.0);
+        assert_eq!(camera.projection_matrix(), expected);
+
+        camera.projection = Projection::Orthographic { height: 4.0, near: 0.5, far: 50.0 };
+        assert_eq!(camera.projection_matrix(), Mat4f::ortho(-4.0, 4.0, -2.0, 2.0, 0.5, 50.0));
+        camera.resize(300, 100);
+        assert_eq!(camera.projection_matrix(), Mat4f::ortho(-6.0, 6.0, -2.0, 2.0, 0.5, 50.0));
+        camera.resize(0, 100);
+        assert_eq!(camera.aspect, 3.0);
+
+        let camera = Camera::orthographic(4.0, 0.5, 0.1, 10.0);
+        assert!(matches!(camera.projection, Projection::Orthographic { height, .. } if height == 4.0));
+        assert_eq!(camera.projection_matrix(), Mat4f::ortho(-1.0, 1.0, -2.0, 2.0, 0.1, 10.0));
+    }
+
+    #[test]
+    fn view_matches_look_at() {
+        let camera = Camera {
+            position: vec3(3.0, 2.0, 5.0),
+            target: vec3(-1.0, 0.5, 0.0),
+            ..Default::default()
+        };
+        let expected = Mat4f::look_at(camera.position, camera.target, camera.up);
+        let view = camera.view_matrix();
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((view[i][j] - expected[i][j]).abs() < 1e-5, "{view:?} != {expected:?}");
+            }
+        }
+        let basis = [camera.forward(), camera.right(), camera.true_up()];
+        assert!(basis.iter().all(|v| (v.length() - 1.0).abs() < 1e-5));
+        assert!(basis[0].dot(&basis[1]).abs() < 1e-5 && basis[1].dot(&basis[2]).abs() < 1e-5);
+    }
+
+    #[test]
+    fn degenerate_placements_stay_finite() {
+        let mut camera = Camera::default();
+        camera.target = camera.position;
+        assert_vec3_eq(camera.forward(), vec3(0.0, 0.0, -1.0));
+        assert_vec3_eq(camera.right(), vec3(1.0, 0.0, 0.0));
+        assert_finite(&camera.view_matrix());
+
+        for (target, up) in [
+            (vec3(0.0, -5.0, 1.0), vec3(0.0, 1.0, 0.0)),
+            (vec3(0.0, 5.0, 1.0), vec3(0.0, 2.0, 0.0)),
+            (vec3(0.0, 0.0, -5.0), vec3(0.0, 0.0, 1.0)),
+            (vec3(1.0, 0.0, 1.0), vec3(3.0, 0.0, 0.0)),
+            (vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 0.0)),
+        ] {
+            camera.target = target;
+            camera.up = up;
+            let (forward, right, up) = (camera.forward(), camera.right(), camera.true_up());
+            assert!((right.length() - 1.0).abs() < 1e-5 && (up.length() - 1.0).abs() < 1e-5, "{right:?} {up:?}");
+            assert!(forward.dot(&right).abs() < 1e-5 && forward.dot(&up).abs() < 1e-5);
+            assert_finite(&camera.view_matrix());
+            assert_finite(&camera.view_projection());
+        }
+        // Looking straight down with Y up keeps X to the right.
+        camera.target = vec3(0.0, -5.0, 1.0);
+        camera.up = vec3(0.0, 1.0, 0.0);
+        assert_vec3_eq(camera.right(), vec3(1.0, 0.0, 0.0));
+    }
 }
@@ -0,0 +1,447 @@
+/*
+    Camera controllers.
+
+    A controller consumes glfw window events through `handle_event` and
+    writes its state into a `Camera` through `update`, which should be
+    called once per frame with the elapsed time in seconds.
+*/
+
+use std::f32::consts::FRAC_PI_2;
+
+use glfw::{Action, Key, MouseButton, WindowEvent};
+
+use super::camera::*;
+use crate::linalg::*;
+
+const PITCH_LIMIT: f32 = FRAC_PI_2 - 0.01;
+
+/// Tracks cursor movement while a mouse button is held.
+#[derive(Debug, Default, Clone, Copy)]
+struct Drag {
+    held: bool,
+    cursor: Option<(f64, f64)>,
+}
+
+impl Drag {
+    fn button(&mut self, action: Action) {
+        match action {
+            Action::Press => self.held = true,
+            Action::Release => self.held = false,
+            Action::Repeat => (),
+        }
+    }
+
+    /// Returns the cursor delta since the last event if the button is held.
+    fn cursor(&mut self, x: f64, y: f64) -> Option<(f32, f32)> {
+        let delta = self
+            .cursor
+            .map(|(lx, ly)| ((x - lx) as f32, (y - ly) as f32));
+        self.cursor = Some((x, y));
+        if self.held {
+            delta
+        } else {
+            None
+        }
+    }
+}
+
+fn direction(yaw: f32, pitch: f32) -> Vec3f {
+    Vec3f::from([[
+        yaw.sin() * pitch.cos(),
+        pitch.sin(),
+        yaw.cos() * pitch.cos(),
+    ]])
+}
+
+// Orbit
+
+/// Rotates around `target` by dragging with the left mouse button, zooms with the scroll wheel.
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitController {
+    pub target: Vec3f,
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    pub rotate_speed: f32,
+    pub zoom_speed: f32,
+    drag: Drag,
+}
+
+impl OrbitController {
+    pub fn new(target: Vec3f, distance: f32) -> Self {
+        Self {
+            target,
+            distance,
+            yaw: 0.0,
+            pitch: 0.0,
+            min_distance: 0.1,
+            max_distance: 1000.0,
+            rotate_speed: 0.005,
+            zoom_speed: 0.1,
+            drag: Drag::default(),
+        }
+    }
+
+    /// Picks up yaw, pitch and distance from the current camera placement.
+    pub fn from_camera(camera: &Camera) -> Self {
+        let offset = camera.position - camera.target;
+        let distance = offset.length();
+        let mut controller = Self::new(camera.target, distance);
+        if distance > 0.0 {
+            controller.yaw = offset.x().atan2(offset.z());
+            controller.pitch = (offset.y() / distance).clamp(-1.0, 1.0).asin();
+        }
+        controller
+    }
+
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::MouseButton(MouseButton::Button1, action, _) => self.drag.button(action),
+            WindowEvent::CursorPos(x, y) => {
+                if let Some((dx, dy)) = self.drag.cursor(x, y) {
+                    self.yaw -= dx * self.rotate_speed;
+                    self.pitch = (self.pitch + dy * self.rotate_speed).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+                }
+            }
+            WindowEvent::Scroll(_, y) => {
+                let factor = 1.0 - y as f32 * self.zoom_speed;
+                self.distance = (self.distance * factor).clamp(self.min_distance, self.max_distance);
+            }
+            _ => (),
+        }
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, _delta: f32) {
+        camera.target = self.target;
+        camera.position = self.target + direction(self.yaw, self.pitch) * self.distance;
+    }
+}
+
+// Fly
+
+/// First-person controls: WASD to move, Space / Left Shift to rise and sink,
+/// mouse look while the right mouse button is held.
+#[derive(Debug, Clone, Copy)]
+pub struct FlyController {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub speed: f32,
+    pub look_speed: f32,
+    forward: bool,
+    backward: bool,
+    left: bool,
+    right: bool,
+    rise: bool,
+    sink: bool,
+    drag: Drag,
+}
+
+impl FlyController {
+    pub fn new(speed: f32) -> Self {
+        Self {
+            yaw: 0.0,
+            pitch: 0.0,
+            speed,
+            look_speed: 0.003,
+            forward: false,
+            backward: false,
+            left: false,
+            right: false,
+            rise: false,
+            sink: false,
+            drag: Drag::default(),
+        }
+    }
+
+    /// Picks up yaw and pitch from the current camera orientation.
+    pub fn from_camera(camera: &Camera, speed: f32) -> Self {
+        let forward = camera.forward();
+        let mut controller = Self::new(speed);
+        controller.yaw = forward.x().atan2(-forward.z());
+        controller.pitch = forward.y().clamp(-1.0, 1.0).asin();
+        controller
+    }
+
+    pub fn forward(&self) -> Vec3f {
+        let d = direction(self.yaw, self.pitch);
+        Vec3f::from([[d.x(), d.y(), -d.z()]])
+    }
+
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::Key(key, _, action, _) => {
+                let pressed = action != Action::Release;
+                match key {
+                    Key::W => self.forward = pressed,
+                    Key::S => self.backward = pressed,
+                    Key::A => self.left = pressed,
+                    Key::D => self.right = pressed,
+                    Key::Space => self.rise = pressed,
+                    Key::LeftShift => self.sink = pressed,
+                    _ => (),
+                }
+            }
+            WindowEvent::MouseButton(MouseButton::Button2, action, _) => self.drag.button(action),
+            WindowEvent::CursorPos(x, y) => {
+                if let Some((dx, dy)) = self.drag.cursor(x, y) {
+                    self.yaw += dx * self.look_speed;
+                    self.pitch = (self.pitch - dy * self.look_speed).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, delta: f32) {
+        let forward = self.forward();
+        let right = forward.cross(&camera.up).normalize();
+        let axis = |positive: bool, negative: bool| {
+            (positive as i32 - negative as i32) as f32
+        };
+
+        let motion = forward * axis(self.forward, self.backward)
+            + right * axis(self.right, self.left)
+            + camera.up * axis(self.rise, self.sink);
+        if motion.length_squared() > 0.0 {
+            camera.position += motion.normalize() * (self.speed * delta);
+        }
+        camera.target = camera.position + forward;
+    }
+}
+
+// Pan & Zoom
+
+/// Pans in the view plane by dragging with the middle mouse button, zooms with the scroll wheel.\
+/// Zooming shrinks the view volume of orthographic cameras and dollies perspective cameras.
+#[derive(Debug, Clone, Copy)]
+pub struct PanZoomController {
+    pub pan_speed: f32,
+    pub zoom_speed: f32,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    pan: (f32, f32),
+    zoom: f32,
+    drag: Drag,
+}
+
+impl PanZoomController {
+    pub fn new() -> Self {
+        Self {
+            pan_speed: 0.002,
+            zoom_speed: 0.1,
+            min_zoom: 0.01,
+            max_zoom: 1000.0,
+            pan: (0.0, 0.0),
+            zoom: 0.0,
+            drag: Drag::default(),
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::MouseButton(MouseButton::Button3, action, _) => self.drag.button(action),
+            WindowEvent::CursorPos(x, y) => {
+                if let Some((dx, dy)) = self.drag.cursor(x, y) {
+                    self.pan.0 += dx;
+                    self.pan.1 += dy;
+                }
+            }
+            WindowEvent::Scroll(_, y) => self.zoom += y as f32,
+            _ => (),
+        }
+    }
+
+    pub fn update(&mut self, camera: &mut Camera, _delta: f32) {
+        let factor = 1.0 - self.zoom * self.zoom_speed;
+        let scale = match &mut camera.projection {
+            Projection::Orthographic { height, .. } => {
+                *height = (*height * factor).clamp(self.min_zoom, self.max_zoom);
+                *height
+            }
+            Projection::Perspective { .. } => {
+                let distance = camera.distance();
+                let zoomed = (distance * factor).clamp(self.min_zoom, self.max_zoom);
+                camera.position = camera.target - camera.forward() * zoomed;
+                zoomed
+            }
+        };
+
+        let (dx, dy) = self.pan;
+        let offset = camera.right() * (-dx * self.pan_speed * scale)
+            + camera.true_up() * (dy * self.pan_speed * scale);
+        camera.translate(offset);
+
+        self.pan = (0.0, 0.0);
+        self.zoom = 0.0;
+    }
+}
+
+impl Default for PanZoomController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glfw::Modifiers;
+
+    fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+        Vec3f::from([[x, y, z]])
+    }
+
+    fn assert_vec3_eq(a: Vec3f, b: Vec3f) {
+        assert!((a - b).length() < 1e-4, "{a:?} != {b:?}");
+    }
+
+    fn button(button: MouseButton, action: Action) -> WindowEvent {
+        WindowEvent::MouseButton(button, action, Modifiers::empty())
+    }
+
+    fn key(key: Key, action: Action) -> WindowEvent {
+        WindowEvent::Key(key, 0, action, Modifiers::empty())
+    }
+
+    /// Presses `held`, moves the cursor by `(dx, dy)` and releases it again.
+    fn drag(handle: &mut impl FnMut(&WindowEvent), held: MouseButton, dx: f64, dy: f64) {
+        handle(&WindowEvent::CursorPos(100.0, 100.0));
+        handle(&button(held, Action::Press));
+        handle(&WindowEvent::CursorPos(100.0 + dx, 100.0 + dy));
+        handle(&button(held, Action::Release));
+    }
+
+    #[test]
+    fn orbit_keeps_its_radius() {
+        let mut camera = Camera {
+            position: vec3(3.0, 4.0, 5.0),
+            target: vec3(1.0, 1.0, 1.0),
+            ..Default::default()
+        };
+        let mut orbit = OrbitController::from_camera(&camera);
+        let radius = camera.distance();
+        assert!((orbit.distance - radius).abs() < 1e-5);
+        let position = camera.position;
+        orbit.update(&mut camera, 0.016);
+        assert_vec3_eq(camera.position, position);
+
+        for (dx, dy) in [(40.0, 0.0), (0.0, -25.0), (-300.0, 120.0), (0.0, 10_000.0)] {
+            drag(&mut |e| orbit.handle_event(e), MouseButton::Button1, dx, dy);
+            orbit.update(&mut camera, 0.016);
+            assert!((camera.distance() - radius).abs() < 1e-4, "{} != {radius}", camera.distance());
+            assert_eq!(camera.target, vec3(1.0, 1.0, 1.0));
+        }
+        assert_eq!(orbit.pitch, PITCH_LIMIT);
+
+        // Without the button held the cursor only gets tracked.
+        let (yaw, pitch) = (orbit.yaw, orbit.pitch);
+        orbit.handle_event(&WindowEvent::CursorPos(0.0, 0.0));
+        orbit.handle_event(&WindowEvent::CursorPos(50.0, 50.0));
+        assert_eq!((orbit.yaw, orbit.pitch), (yaw, pitch));
+    }
+
+    #[test]
+    fn orbit_zoom_is_clamped() {
+        let mut orbit = OrbitController::new(vec3(0.0, 0.0, 0.0), 10.0);
+        orbit.handle_event(&WindowEvent::Scroll(0.0, 1.0));
+        assert!((orbit.distance - 9.0).abs() < 1e-5);
+        orbit.handle_event(&WindowEvent::Scroll(0.0, -2.0));
+        assert!((orbit.distance - 10.8).abs() < 1e-5);
+        for _ in 0..200 {
+            orbit.handle_event(&WindowEvent::Scroll(0.0, 5.0));
+        }
+        assert_eq!(orbit.distance, orbit.min_distance);
+        for _ in 0..200 {
+            orbit.handle_event(&WindowEvent::Scroll(0.0, -5.0));
+        }
+        assert_eq!(orbit.distance, orbit.max_distance);
+    }
+
+    #[test]
+    fn fly_moves_and_looks() {
+        let mut camera = Camera::default();
+        let mut fly = FlyController::from_camera(&camera, 2.0);
+        assert_vec3_eq(fly.forward(), camera.forward());
+
+        fly.handle_event(&key(Key::W, Action::Press));
+        fly.update(&mut camera, 0.5);
+        assert_vec3_eq(camera.position, vec3(0.0, 0.0, 0.0));
+        assert_vec3_eq(camera.target, vec3(0.0, 0.0, -1.0));
+
+        // Diagonal motion is not faster.
+        fly.handle_event(&key(Key::D, Action::Press));
+        fly.update(&mut camera, 0.5);
+        assert!((camera.position.length() - 1.0).abs() < 1e-5);
+        fly.handle_event(&key(Key::W, Action::Release));
+        fly.handle_event(&key(Key::D, Action::Release));
+        let position = camera.position;
+        fly.update(&mut camera, 0.5);
+        assert_eq!(camera.position, position);
+
+        fly.handle_event(&key(Key::Space, Action::Press));
+        fly.update(&mut camera, 0.25);
+        assert_vec3_eq(camera.position, position + vec3(0.0, 0.5, 0.0));
+        fly.handle_event(&key(Key::Space, Action::Release));
+
+        drag(&mut |e| fly.handle_event(e), MouseButton::Button2, 100.0, -100_000.0);
+        assert!((fly.yaw - 0.3).abs() < 1e-5);
+        assert_eq!(fly.pitch, PITCH_LIMIT);
+        fly.update(&mut camera, 0.1);
+        assert_vec3_eq(camera.target - camera.position, fly.forward());
+        assert!(fly.forward().dot(&camera.forward()) > 1.0 - 1e-5);
+    }
+
+    #[test]
+    fn pan_zoom_orthographic() {
+        let mut camera = Camera::orthographic(10.0, 1.0, 0.1, 100.0);
+        let mut pan_zoom = PanZoomController::new();
+        pan_zoom.handle_event(&WindowEvent::Scroll(0.0, 2.0));
+        pan_zoom.update(&mut camera, 0.016);
+        assert!(matches!(camera.projection, Projection::Orthographic { height, .. } if (height - 8.0).abs() < 1e-5));
+
+        // The accumulated zoom resets after each update.
+        pan_zoom.update(&mut camera, 0.016);
+        assert!(matches!(camera.projection, Projection::Orthographic { height, .. } if (height - 8.0).abs() < 1e-5));
+
+        drag(&mut |e| pan_zoom.handle_event(e), MouseButton::Button3, 100.0, 50.0);
+        let (position, target) = (camera.position, camera.target);
+        pan_zoom.update(&mut camera, 0.016);
+        let offset = vec3(-100.0 * 0.002 * 8.0, 50.0 * 0.002 * 8.0, 0.0);
+        assert_vec3_eq(camera.position, position + offset);
+        assert_vec3_eq(camera.target, target + offset);
+
+        pan_zoom.handle_event(&WindowEvent::Scroll(0.0, 50.0));
+        pan_zoom.update(&mut camera, 0.016);
+        assert!(matches!(camera.projection, Projection::Orthographic { height, .. } if height == pan_zoom.min_zoom));
+        pan_zoom.handle_event(&WindowEvent::Scroll(0.0, -1e6));
+        pan_zoom.update(&mut camera, 0.016);
+        assert!(matches!(camera.projection, Projection::Orthographic { height, .. } if height == pan_zoom.max_zoom));
+    }
+
+    #[test]
+    fn pan_zoom_perspective_dollies() {
+        let mut camera = Camera {
+            position: vec3(0.0, 0.0, 10.0),
+            ..Default::default()
+        };
+        let mut pan_zoom = PanZoomController::new();
+        pan_zoom.handle_event(&WindowEvent::Scroll(0.0, 5.0));
+        pan_zoom.update(&mut camera, 0.016);
+        assert_vec3_eq(camera.position, vec3(0.0, 0.0, 5.0));
+        assert_eq!(camera.target, vec3(0.0, 0.0, 0.0));
+
+        pan_zoom.handle_event(&WindowEvent::Scroll(0.0, 20.0));
+        pan_zoom.update(&mut camera, 0.016);
+        assert!((camera.distance() - pan_zoom.min_zoom).abs() < 1e-6);
+        assert!(camera.forward().dot(&vec3(0.0, 0.0, -1.0)) > 1.0 - 1e-5);
+
+        // A camera sitting on its target still dollies back along a finite direction.
+        camera.position = camera.target;
+        pan_zoom.handle_event(&WindowEvent::Scroll(0.0, -1.0));
+        pan_zoom.update(&mut camera, 0.016);
+        assert!((camera.distance() - pan_zoom.min_zoom).abs() < 1e-6);
+        assert!(camera.position.length().is_finite());
+    }
+}
@@ -0,0 +1,557 @@
+/*
+    Implementation of procedural mesh generators.
+    Every generator returns counter-clockwise triangles facing outward,
+    with positions, normals, UVs, tangents and `u32` indices.
+
+    The attribute arrays are kept separate so they can be handed to
+    `compose_data` as is, `attributes` gives the matching layout for `Program::bind_buffer`.
+*/
+
+use crate::application::compose_data;
+use crate::linalg::*;
+use gl::types::*;
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    /// 3 floats per vertex.
+    pub positions: Vec<f32>,
+    /// 3 floats per vertex, unit length.
+    pub normals: Vec<f32>,
+    /// 2 floats per vertex.
+    pub uvs: Vec<f32>,
+    /// 4 floats per vertex, `w` is the handedness of the bitangent `cross(normal, tangent) * w`.
+    pub tangents: Vec<f32>,
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len() / 3
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Input for `compose_data`, in the order position, normal, uv, tangent.
+    pub fn buffers(&self) -> Vec<(&Vec<f32>, usize)> {
+        vec![
+            (&self.positions, 3),
+            (&self.normals, 3),
+            (&self.uvs, 2),
+            (&self.tangents, 4),
+        ]
+    }
+
+    /// Interleaved vertex data, ready for `Program::bind_buffer`.
+    pub fn vertex_data(&self) -> Vec<f32> {
+        compose_data(&self.buffers())
+    }
+
+    /// Layout of `vertex_data`, locations 0 to 3 hold position, normal, uv and tangent.
+    pub fn attributes() -> Vec<(GLuint, GLint, GLenum, GLboolean, GLuint)> {
+        vec![
+            (0, 3, gl::FLOAT, gl::FALSE, 0),
+            (1, 3, gl::FLOAT, gl::FALSE, 3),
+            (2, 2, gl::FLOAT, gl::FALSE, 6),
+            (3, 4, gl::FLOAT, gl::FALSE, 8),
+        ]
+    }
+
+    // Generators
+
+    /// Plane in XZ facing +Y, centered on the origin.
+    pub fn plane(width: f32, depth: f32, subdivisions_x: u32, subdivisions_z: u32) -> Self {
+        let mut mesh = Self::new();
+        mesh.grid(
+            vec3(-0.5 * width, 0.0, 0.5 * depth),
+            vec3(width, 0.0, 0.0),
+            vec3(0.0, 0.0, -depth),
+            subdivisions_x.max(1),
+            subdivisions_z.max(1),
+        );
+        mesh.compute_tangents();
+        mesh
+    }
+
+    /// Single quad in XY facing +Z, centered on the origin.
+    pub fn quad(width: f32, height: f32) -> Self {
+        let mut mesh = Self::new();
+        mesh.grid(
+            vec3(-0.5 * width, -0.5 * height, 0.0),
+            vec3(width, 0.0, 0.0),
+            vec3(0.0, height, 0.0),
+            1,
+            1,
+        );
+        mesh.compute_tangents();
+        mesh
+    }
+
+    /// Axis-aligned cube centered on the origin, each face split into `subdivisions²` quads.\
+    /// Faces do not share vertices, so normals stay flat.
+    pub fn cube(size: f32, subdivisions: u32) -> Self {
+        let s = subdivisions.max(1);
+        let half = 0.5 * size;
+        let faces = [
+            (vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0)),
+            (vec3(-1.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0), vec3(0.0, 1.0, 0.0)),
+            (vec3(0.0, 1.0, 0.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, -1.0)),
+            (vec3(0.0, -1.0, 0.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0)),
+            (vec3(0.0, 0.0, 1.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+            (vec3(0.0, 0.0, -1.0), vec3(-1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+        ];
+        let mut mesh = Self::new();
+        for (normal, u, v) in faces {
+            mesh.grid((normal - u - v) * half, u * size, v * size, s, s);
+        }
+        mesh.compute_tangents();
+        mesh
+    }
+
+    /// Latitude-longitude sphere, `segments` around the Y axis and `rings` from pole to pole.\
+    /// The seam and the poles are duplicated so every vertex has a single UV.
+    pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Self {
+        let rings = rings.max(2);
+        let profile: Vec<_> = (0..=rings)
+            .map(|j| {
+                let v = j as f32 / rings as f32;
+                let (sin, cos) = (PI * (1.0 - v)).sin_cos();
+                // Snap the poles so `revolve` closes them.
+                let sin = if j == 0 || j == rings { 0.0 } else { sin };
+                (radius * sin, radius * cos, sin, cos, v)
+            })
+            .collect();
+        let mut mesh = Self::new();
+        mesh.revolve(&profile, segments.max(3));
+        mesh.compute_tangents();
+        mesh
+    }
+
+    /// Subdivided icosahedron, `subdivisions` splits each triangle into 4, `4^subdivisions * 20` triangles in total.\
+    /// UVs are spherical, vertices along the seam and at the poles are duplicated.
+    pub fn icosphere(radius: f32, subdivisions: u32) -> Self {
+        let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+        let mut points: Vec<Vec3f> = [
+            (-1.0, t, 0.0),
+            (1.0, t, 0.0),
+            (-1.0, -t, 0.0),
+            (1.0, -t, 0.0),
+            (0.0, -1.0, t),
+            (0.0, 1.0, t),
+            (0.0, -1.0, -t),
+            (0.0, 1.0, -t),
+            (t, 0.0, -1.0),
+            (t, 0.0, 1.0),
+            (-t, 0.0, -1.0),
+            (-t, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|&(x, y, z)| vec3(x, y, z).normalize())
+        .collect();
+        let mut triangles: Vec<[u32; 3]> = vec![
+            [0, 11, 5],
+            [0, 5, 1],
+            [0, 1, 7],
+            [0, 7, 10],
+            [0, 10, 11],
+            [1, 5, 9],
+            [5, 11, 4],
+            [11, 10, 2],
+            [10, 7, 6],
+            [7, 1, 8],
+            [3, 9, 4],
+            [3, 4, 2],
+            [3, 2, 6],
+            [3, 6, 8],
+            [3, 8, 9],
+            [4, 9, 5],
+            [2, 4, 11],
+            [6, 2, 10],
+            [8, 6, 7],
+            [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions {
+            let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+            let mut midpoint = |a: u32, b: u32, points: &mut Vec<Vec3f>| {
+                *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    points.push((points[a as usize] + points[b as usize]).normalize());
+                    (points.len() - 1) as u32
+                })
+            };
+            triangles = triangles
+                .iter()
+                .flat_map(|&[a, b, c]| {
+                    let ab = midpoint(a, b, &mut points);
+                    let bc = midpoint(b, c, &mut points);
+                    let ca = midpoint(c, a, &mut points);
+                    [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        // Triangles straddling the seam take a copy of their low-u vertices shifted by 1,
+        // pole vertices get a copy per triangle centered between its other two corners.
+        let uv_of = |p: Vec3f| {
+            (
+                0.5 + p.x().atan2(p.z()) / TAU,
+                0.5 + p.y().clamp(-1.0, 1.0).asin() / PI,
+            )
+        };
+        let is_pole = |p: Vec3f| p.x().abs() < 1e-6 && p.z().abs() < 1e-6;
+        let mut mesh = Self::new();
+        let mut emitted: HashMap<(u32, bool), u32> = HashMap::new();
+        for triangle in triangles {
+            let base = triangle.map(|i| uv_of(points[i as usize]).0);
+            let poles = triangle.map(|i| is_pole(points[i as usize]));
+            let spread = |us: &[f32; 3]| {
+                let (lo, hi) = (0..3)
+                    .filter(|&k| !poles[k])
+                    .fold((f32::MAX, f32::MIN), |(lo, hi), k| (lo.min(us[k]), hi.max(us[k])));
+                hi - lo
+            };
+            let mut wrapped = base;
+            for (k, u) in wrapped.iter_mut().enumerate() {
+                if !poles[k] && *u < 0.5 {
+                    *u += 1.0;
+                }
+            }
+            let us = if spread(&wrapped) < spread(&base) { wrapped } else { base };
+            for k in 0..3 {
+                let i = triangle[k];
+                let p = points[i as usize];
+                let v = uv_of(p).1;
+                let index = if poles[k] {
+                    let u = 0.5 * (us[(k + 1) % 3] + us[(k + 2) % 3]);
+                    mesh.push_vertex(p * radius, p, [u, v])
+                } else {
+                    let u = us[k];
+                    *emitted
+                        .entry((i, u - base[k] > 0.5))
+                        .or_insert_with(|| mesh.push_vertex(p * radius, p, [u, v]))
+                };
+                mesh.indices.push(index);
+            }
+        }
+        mesh.compute_tangents();
+        mesh
+    }
+
+    /// Capped cylinder around the Y axis, centered on the origin.\
+    /// `stacks` splits the side along its height.
+    pub fn cylinder(radius: f32, height: f32, segments: u32, stacks: u32) -> Self {
+        let segments = segments.max(3);
+        let stacks = stacks.max(1);
+        let half = 0.5 * height;
+        let profile: Vec<_> = (0..=stacks)
+            .map(|j| {
+                let v = j as f32 / stacks as f32;
+                (radius, -half + v * height, 1.0, 0.0, v)
+            })
+            .collect();
+        let mut mesh = Self::new();
+        mesh.revolve(&profile, segments);
+        mesh.disk(vec3(0.0, half, 0.0), radius, segments, true);
+        mesh.disk(vec3(0.0, -half, 0.0), radius, segments, false);
+        mesh.compute_tangents();
+        mesh
+    }
+
+    /// Cone around the Y axis with its base at `-height / 2` and its apex at `height / 2`.
+    pub fn cone(radius: f32, height: f32, segments: u32, stacks: u32) -> Self {
+        let segments = segments.max(3);
+        let stacks = stacks.max(1);
+        let half = 0.5 * height;
+        let slant = (radius * radius + height * height).sqrt();
+        let (normal_r, normal_y) = (height / slant, radius / slant);
+        let profile: Vec<_> = (0..=stacks)
+            .map(|j| {
+                let v = j as f32 / stacks as f32;
+                (radius * (1.0 - v), -half + v * height, normal_r, normal_y, v)
+            })
+            .collect();
+        let mut mesh = Self::new();
+        mesh.revolve(&profile, segments);
+        mesh.disk(vec3(0.0, -half, 0.0), radius, segments, false);
+        mesh.compute_tangents();
+        mesh
+    }
+
+    /// Torus around the Y axis, `radius` from the center to the middle of the tube.\
+    /// `segments` runs around the Y axis and `sides` around the tube.
+    pub fn torus(radius: f32, tube_radius: f32, segments: u32, sides: u32) -> Self {
+        let sides = sides.max(3);
+        let profile: Vec<_> = (0..=sides)
+            .map(|j| {
+                let v = j as f32 / sides as f32;
+                let (sin, cos) = (TAU * v).sin_cos();
+                (radius + tube_radius * cos, tube_radius * sin, cos, sin, v)
+            })
+            .collect();
+        let mut mesh = Self::new();
+        mesh.revolve(&profile, segments.max(3));
+        mesh.compute_tangents();
+        mesh
+    }
+
+    /// Capsule around the Y axis, a cylinder of `height` between two hemispheres of `radius`.\
+    /// `rings` splits each hemisphere from its pole to the equator.
+    pub fn capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Self {
+        let rings = rings.max(1);
+        let half = 0.5 * height;
+        // V follows the length of the profile so texels keep their aspect across the seam.
+        let total = PI * radius + height;
+        let arc = FRAC_PI_2 * radius;
+        let mut profile = Vec::new();
+        for j in 0..=rings {
+            let angle = FRAC_PI_2 * j as f32 / rings as f32;
+            let (sin, cos) = angle.sin_cos();
+            profile.push((radius * sin, -half - radius * cos, sin, -cos, arc * j as f32 / rings as f32 / total));
+        }
+        for j in 0..=rings {
+            let angle = FRAC_PI_2 * j as f32 / rings as f32;
+            let (sin, cos) = angle.sin_cos();
+            let cos = if j == rings { 0.0 } else { cos };
+            let v = (arc + height + arc * j as f32 / rings as f32) / total;
+            profile.push((radius * cos, half + radius * sin, cos, sin, v));
+        }
+        let mut mesh = Self::new();
+        mesh.revolve(&profile, segments.max(3));
+        mesh.compute_tangents();
+        mesh
+    }
+
+    // Building Blocks
+
+    fn push_vertex(&mut self, position: Vec3f, normal: Vec3f, uv: [f32; 2]) -> u32 {
+        self.positions.extend(position.data[0]);
+        self.normals.extend(normal.data[0]);
+        self.uvs.extend(uv);
+        (self.vertex_count() - 1) as u32
+    }
+
+    /// Flat grid spanning `axis_u × axis_v` from `origin`, facing `cross(axis_u, axis_v)`.
+    fn grid(&mut self, origin: Vec3f, axis_u: Vec3f, axis_v: Vec3f, columns: u32, rows: u32) {
+        let normal = axis_u.cross(&axis_v).normalize();
+        let base = self.vertex_count() as u32;
+        for j in 0..=rows {
+            for i in 0..=columns {
+                let (u, v) = (i as f32 / columns as f32, j as f32 / rows as f32);
+                self.push_vertex(origin + axis_u * u + axis_v * v, normal, [u, v]);
+            }
+        }
+        let stride = columns + 1;
+        for j in 0..rows {
+            for i in 0..columns {
+                let a = base + j * stride + i;
+                let (b, c, d) = (a + 1, a + stride + 1, a + stride);
+                self.indices.extend([a, b, c, a, c, d]);
+            }
+        }
+    }
+
+    /// Surface of revolution around the Y axis.\
+    /// Each profile row is `(radius, y, normal radial, normal y, v)`, listed with the outside on the right
+    /// when walking along it from bottom to top. Rows with a zero radius close into a pole.
+    fn revolve(&mut self, profile: &[(f32, f32, f32, f32, f32)], segments: u32) {
+        let base = self.vertex_count() as u32;
+        for &(radius, y, normal_r, normal_y, v) in profile {
+            for i in 0..=segments {
+                let u = i as f32 / segments as f32;
+                let (sin, cos) = (TAU * u).sin_cos();
+                self.push_vertex(
+                    vec3(radius * sin, y, radius * cos),
+                    vec3(normal_r * sin, normal_y, normal_r * cos).normalize(),
+                    [u, v],
+                );
+            }
+        }
+        let stride = segments + 1;
+        for (j, rows) in profile.windows(2).enumerate() {
+            let (bottom_pole, top_pole) = (rows[0].0 == 0.0, rows[1].0 == 0.0);
+            for i in 0..segments {
+                let a = base + j as u32 * stride + i;
+                let (b, c, d) = (a + 1, a + stride + 1, a + stride);
+                if !bottom_pole {
+                    self.indices.extend([a, b, c]);
+                }
+                if !top_pole {
+                    self.indices.extend([a, c, d]);
+                }
+            }
+        }
+    }
+
+    /// Triangle fan closing a revolved surface, facing +Y if `up` and -Y otherwise.
+    fn disk(&mut self, center: Vec3f, radius: f32, segments: u32, up: bool) {
+        let (normal, facing) = if up {
+            (vec3(0.0, 1.0, 0.0), -1.0)
+        } else {
+            (vec3(0.0, -1.0, 0.0), 1.0)
+        };
+        let middle = self.push_vertex(center, normal, [0.5, 0.5]);
+        for i in 0..segments {
+            let (sin, cos) = (TAU * i as f32 / segments as f32).sin_cos();
+            self.push_vertex(
+                center + vec3(radius * sin, 0.0, radius * cos),
+                normal,
+                [0.5 + 0.5 * sin, 0.5 + 0.5 * facing * cos],
+            );
+        }
+        for i in 0..segments {
+            let (a, b) = (middle + 1 + i, middle + 1 + (i + 1) % segments);
+            if up {
+                self.indices.extend([middle, a, b]);
+            } else {
+                self.indices.extend([middle, b, a]);
+            }
+        }
+    }
+
+    /// Recomputes `tangents` from positions, normals and UVs (Lengyel's method).\
+    /// Vertices without a usable UV gradient get an arbitrary tangent orthogonal to their normal.
+    pub fn compute_tangents(&mut self) {
+        let n = self.vertex_count();
+        let position = |i: usize| vec3(self.positions[3 * i], self.positions[3 * i + 1], self.positions[3 * i + 2]);
+        let uv = |i: usize| (self.uvs[2 * i], self.uvs[2 * i + 1]);
+
+        let mut tangents = vec![Vec3f::default(); n];
+        let mut bitangents = vec![Vec3f::default(); n];
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| triangle[k] as usize);
+            let (e1, e2) = (position(b) - position(a), position(c) - position(a));
+            let ((u0, v0), (u1, v1), (u2, v2)) = (uv(a), uv(b), uv(c));
+            let (du1, dv1, du2, dv2) = (u1 - u0, v1 - v0, u2 - u0, v2 - v0);
+            let det = du1 * dv2 - du2 * dv1;
+            if det.abs() < 1e-12 {
+                continue;
+            }
+            let r = 1.0 / det;
+            let t = (e1 * dv2 - e2 * dv1) * r;
+            let s = (e2 * du1 - e1 * du2) * r;
+            for i in [a, b, c] {
+                tangents[i] += t;
+                bitangents[i] += s;
+            }
+        }
+
+        self.tangents = Vec::with_capacity(4 * n);
+        for i in 0..n {
+            let normal = vec3(self.normals[3 * i], self.normals[3 * i + 1], self.normals[3 * i + 2]);
+            let mut t = tangents[i] - normal * normal.dot(&tangents[i]);
+            if t.length_squared() < 1e-12 {
+                let axis = if normal.x().abs() < 0.9 {
+                    vec3(1.0, 0.0, 0.0)
+                } else {
+                    vec3(0.0, 1.0, 0.0)
+                };
+                t = axis - normal * normal.dot(&axis);
+            }
+            let t = t.normalize();
+            let w = if normal.cross(&t).dot(&bitangents[i]) < 0.0 { -1.0 } else { 1.0 };
+            self.tangents.extend([t.x(), t.y(), t.z(), w]);
+        }
+    }
+}
+
+fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+    Vec3f::from([[x, y, z]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Expected counts, indices in range, matching attribute lengths and unit normals and tangents.
+    fn check(mesh: &Mesh, vertices: usize, triangles: usize) {
+        assert_eq!(mesh.vertex_count(), vertices);
+        assert_eq!(mesh.triangle_count(), triangles);
+        assert_eq!(mesh.indices.len(), 3 * triangles);
+        assert_eq!(mesh.normals.len(), 3 * vertices);
+        assert_eq!(mesh.uvs.len(), 2 * vertices);
+        assert_eq!(mesh.tangents.len(), 4 * vertices);
+        assert!(mesh.indices.iter().all(|&i| (i as usize) < vertices));
+        for normal in mesh.normals.chunks(3) {
+            let length = vec3(normal[0], normal[1], normal[2]).length();
+            assert!((length - 1.0).abs() < 1e-5, "normal {normal:?}");
+        }
+        for tangent in mesh.tangents.chunks(4) {
+            let length = vec3(tangent[0], tangent[1], tangent[2]).length();
+            assert!((length - 1.0).abs() < 1e-5, "tangent {tangent:?}");
+            assert!(tangent[3].abs() == 1.0);
+        }
+    }
+
+    #[test]
+    fn plane_and_quad() {
+        for (x, z) in [(1, 1), (3, 2), (8, 8)] {
+            check(&Mesh::plane(2.0, 3.0, x, z), ((x + 1) * (z + 1)) as usize, (2 * x * z) as usize);
+        }
+        check(&Mesh::quad(1.0, 2.0), 4, 2);
+    }
+
+    #[test]
+    fn cube() {
+        for s in [1, 2, 5] {
+            check(&Mesh::cube(2.0, s), (6 * (s + 1) * (s + 1)) as usize, (12 * s * s) as usize);
+        }
+    }
+
+    #[test]
+    fn uv_sphere() {
+        for (segments, rings) in [(3, 2), (16, 8), (32, 17)] {
+            let mesh = Mesh::uv_sphere(1.5, segments, rings);
+            let vertices = (segments + 1) * (rings + 1);
+            check(&mesh, vertices as usize, (2 * segments * (rings - 1)) as usize);
+        }
+    }
+
+    #[test]
+    fn icosphere() {
+        for s in 0..4 {
+            let mesh = Mesh::icosphere(2.0, s);
+            // Seam and pole duplicates come on top of the shared vertices.
+            let shared = 10 * 4usize.pow(s) + 2;
+            let vertices = mesh.vertex_count();
+            assert!(vertices >= shared, "{vertices} < {shared}");
+            check(&mesh, vertices, 20 * 4usize.pow(s));
+            for p in mesh.positions.chunks(3) {
+                assert!((vec3(p[0], p[1], p[2]).length() - 2.0).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn cylinder_and_cone() {
+        for (segments, stacks) in [(3, 1), (12, 4)] {
+            let (s, k) = (segments as usize, stacks as usize);
+            let side = (s + 1) * (k + 1);
+            check(&Mesh::cylinder(1.0, 2.0, segments, stacks), side + 2 * (s + 1), 2 * s * k + 2 * s);
+            // The apex row closes into a pole, its quads become single triangles.
+            check(&Mesh::cone(1.0, 2.0, segments, stacks), side + s + 1, (2 * s * k - s) + s);
+        }
+    }
+
+    #[test]
+    fn torus() {
+        for (segments, sides) in [(3, 3), (24, 12)] {
+            let (s, k) = (segments as usize, sides as usize);
+            check(&Mesh::torus(2.0, 0.5, segments, sides), (s + 1) * (k + 1), 2 * s * k);
+        }
+    }
+
+    #[test]
+    fn capsule() {
+        for (segments, rings) in [(3, 1), (16, 6)] {
+            let (s, k) = (segments as usize, rings as usize);
+            check(&Mesh::capsule(0.5, 1.0, segments, rings), 2 * (k + 1) * (s + 1), 4 * s * k);
+        }
+    }
+}
@@ -1 +1,6 @@
-pub mod camera;
\ No newline at end of file
+pub mod camera;
+pub use camera::*;
+pub mod controller;
+pub use controller::*;
+pub mod mesh;
+pub use mesh::*;
@@ -0,0 +1,562 @@
+/*
+    Implementation of rectangle packing for texture atlases.
+    `MaxRectsBin` and `SkylineBin` pack a single page online, `AtlasPacker` sorts
+    a whole batch and spreads it over as many pages as needed.
+
+    Pixel rects use buffer rows, row 0 being the first row handed to `TexImage2D`,
+    so UVs are simply pixel coordinates divided by the page size.
+*/
+
+use crate::linalg::*;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn right(&self) -> u32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> u32 {
+        self.y + self.height
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    pub fn contains(&self, other: &Rect) -> bool {
+        other.x >= self.x && other.y >= self.y && other.right() <= self.right() && other.bottom() <= self.bottom()
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right() && other.x < self.right() && self.y < other.bottom() && other.y < self.bottom()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    /// MaxRects, smallest leftover on the short side of the free rect.
+    MaxRectsBestShortSide,
+    /// MaxRects, smallest leftover on the long side of the free rect.
+    MaxRectsBestLongSide,
+    /// MaxRects, smallest free rect.
+    MaxRectsBestArea,
+    /// MaxRects, lowest then leftmost position (Tetris-like).
+    MaxRectsBottomLeft,
+    /// MaxRects, most edge contact with placed rects and the page border.
+    MaxRectsContactPoint,
+    /// Skyline, lowest then leftmost position.
+    SkylineBottomLeft,
+    /// Skyline, least area wasted under the placed rect.
+    SkylineMinWaste,
+}
+
+/// A single page that places rects as they come.
+pub trait Bin {
+    /// Position of a `width × height` rect, `true` if it was placed rotated by 90°.
+    fn insert(&mut self, width: u32, height: u32, allow_rotation: bool) -> Option<(Rect, bool)>;
+    /// Fraction of the page covered by placed rects.
+    fn occupancy(&self) -> f32;
+}
+
+// MaxRects
+
+/// Jylänki's maximal rectangles packer, keeps every maximal free rect.
+#[derive(Debug, Clone)]
+pub struct MaxRectsBin {
+    pub width: u32,
+    pub height: u32,
+    pub heuristic: Heuristic,
+    free: Vec<Rect>,
+    used: Vec<Rect>,
+}
+
+impl MaxRectsBin {
+    /// `heuristic` must be one of the `MaxRects*` variants.
+    pub fn new(width: u32, height: u32, heuristic: Heuristic) -> Self {
+        assert!(
+            !matches!(heuristic, Heuristic::SkylineBottomLeft | Heuristic::SkylineMinWaste),
+            "MaxRectsBin needs a MaxRects heuristic"
+        );
+        Self {
+            width,
+            height,
+            heuristic,
+            free: vec![Rect::new(0, 0, width, height)],
+            used: Vec::new(),
+        }
+    }
+
+    /// Lower is better, compared as a pair.
+    fn score(&self, free: &Rect, width: u32, height: u32) -> (i64, i64) {
+        let leftover_x = (free.width - width) as i64;
+        let leftover_y = (free.height - height) as i64;
+        let (short, long) = (leftover_x.min(leftover_y), leftover_x.max(leftover_y));
+        match self.heuristic {
+            Heuristic::MaxRectsBestShortSide => (short, long),
+            Heuristic::MaxRectsBestLongSide => (long, short),
+            Heuristic::MaxRectsBestArea => ((free.area() - width as u64 * height as u64) as i64, short),
+            Heuristic::MaxRectsBottomLeft => ((free.y + height) as i64, free.x as i64),
+            Heuristic::MaxRectsContactPoint => (-(self.contact(free.x, free.y, width, height) as i64), 0),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Length of the edges shared with the page border and placed rects.
+    fn contact(&self, x: u32, y: u32, width: u32, height: u32) -> u32 {
+        let overlap = |a0: u32, a1: u32, b0: u32, b1: u32| a1.min(b1).saturating_sub(a0.max(b0));
+        let mut contact = 0;
+        if x == 0 || x + width == self.width {
+            contact += height;
+        }
+        if y == 0 || y + height == self.height {
+            contact += width;
+        }
+        for r in &self.used {
+            if r.x == x + width || r.right() == x {
+                contact += overlap(r.y, r.bottom(), y, y + height);
+            }
+            if r.y == y + height || r.bottom() == y {
+                contact += overlap(r.x, r.right(), x, x + width);
+            }
+        }
+        contact
+    }
+
+    fn place(&mut self, placed: Rect) {
+        let mut split = Vec::new();
+        self.free.retain(|free| {
+            if !free.intersects(&placed) {
+                return true;
+            }
+            if placed.x > free.x {
+                split.push(Rect::new(free.x, free.y, placed.x - free.x, free.height));
+            }
+            if placed.right() < free.right() {
+                split.push(Rect::new(placed.right(), free.y, free.right() - placed.right(), free.height));
+            }
+            if placed.y > free.y {
+                split.push(Rect::new(free.x, free.y, free.width, placed.y - free.y));
+            }
+            if placed.bottom() < free.bottom() {
+                split.push(Rect::new(free.x, placed.bottom(), free.width, free.bottom() - placed.bottom()));
+            }
+            false
+        });
+        self.free.extend(split);
+
+        // Drop free rects contained in another, keeping one of any duplicates.
+        let mut i = 0;
+        while i < self.free.len() {
+            let rect = self.free[i];
+            let redundant = self
+                .free
+                .iter()
+                .enumerate()
+                .any(|(j, other)| j != i && other.contains(&rect) && (other != &rect || j < i));
+            if redundant {
+                self.free.swap_remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        self.used.push(placed);
+    }
+}
+
+impl Bin for MaxRectsBin {
+    fn insert(&mut self, width: u32, height: u32, allow_rotation: bool) -> Option<(Rect, bool)> {
+        let mut best: Option<((i64, i64), Rect, bool)> = None;
+        for free in &self.free {
+            let orientations = [(width, height, false), (height, width, true)];
+            for &(w, h, rotated) in &orientations[..if allow_rotation && width != height { 2 } else { 1 }] {
+                if w > free.width || h > free.height {
+                    continue;
+                }
+                let score = self.score(free, w, h);
+                if best.is_none_or(|(b, _, _)| score < b) {
+                    best = Some((score, Rect::new(free.x, free.y, w, h), rotated));
+                }
+            }
+        }
+        let (_, rect, rotated) = best?;
+        self.place(rect);
+        Some((rect, rotated))
+    }
+
+    fn occupancy(&self) -> f32 {
+        let used: u64 = self.used.iter().map(Rect::area).sum();
+        used as f32 / (self.width as u64 * self.height as u64).max(1) as f32
+    }
+}
+
+// Skyline
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+/// Skyline packer, tracks the top edge of the placed rects.\
+/// Cheaper than MaxRects but cannot fill the space left under overhangs.
+#[derive(Debug, Clone)]
+pub struct SkylineBin {
+    pub width: u32,
+    pub height: u32,
+    pub heuristic: Heuristic,
+    skyline: Vec<Segment>,
+    used_area: u64,
+}
+
+impl SkylineBin {
+    /// `heuristic` must be one of the `Skyline*` variants.
+    pub fn new(width: u32, height: u32, heuristic: Heuristic) -> Self {
+        assert!(
+            matches!(heuristic, Heuristic::SkylineBottomLeft | Heuristic::SkylineMinWaste),
+            "SkylineBin needs a Skyline heuristic"
+        );
+        Self {
+            width,
+            height,
+            heuristic,
+            skyline: vec![Segment { x: 0, y: 0, width }],
+            used_area: 0,
+        }
+    }
+
+    /// Resting height and wasted area of a rect whose left edge sits on segment `i`.
+    fn fit(&self, i: usize, width: u32, height: u32) -> Option<(u32, u64)> {
+        let x = self.skyline[i].x;
+        if x + width > self.width {
+            return None;
+        }
+        let covered: Vec<&Segment> = self.skyline[i..].iter().take_while(|s| s.x < x + width).collect();
+        let y = covered.iter().map(|s| s.y).max()?;
+        if y + height > self.height {
+            return None;
+        }
+        let waste = covered
+            .iter()
+            .map(|s| (y - s.y) as u64 * ((s.x + s.width).min(x + width) - s.x) as u64)
+            .sum();
+        Some((y, waste))
+    }
+
+    fn place(&mut self, i: usize, rect: Rect) {
+        self.skyline.insert(
+            i,
+            Segment {
+                x: rect.x,
+                y: rect.bottom(),
+                width: rect.width,
+            },
+        );
+        // Trim the segments now under the new one.
+        let end = rect.right();
+        while i + 1 < self.skyline.len() && self.skyline[i + 1].x < end {
+            let next = &mut self.skyline[i + 1];
+            let next_end = next.x + next.width;
+            if next_end <= end {
+                self.skyline.remove(i + 1);
+            } else {
+                next.width = next_end - end;
+                next.x = end;
+                break;
+            }
+        }
+        // Merge neighbors at the same height.
+        let mut j = 0;
+        while j + 1 < self.skyline.len() {
+            if self.skyline[j].y == self.skyline[j + 1].y {
+                self.skyline[j].width += self.skyline[j + 1].width;
+                self.skyline.remove(j + 1);
+            } else {
+                j += 1;
+            }
+        }
+        self.used_area += rect.area();
+    }
+}
+
+impl Bin for SkylineBin {
+    fn insert(&mut self, width: u32, height: u32, allow_rotation: bool) -> Option<(Rect, bool)> {
+        let mut best: Option<((u64, u64), usize, Rect, bool)> = None;
+        let orientations = [(width, height, false), (height, width, true)];
+        for &(w, h, rotated) in &orientations[..if allow_rotation && width != height { 2 } else { 1 }] {
+            for i in 0..self.skyline.len() {
+                let Some((y, waste)) = self.fit(i, w, h) else {
+                    continue;
+                };
+                let top = (y + h) as u64;
+                let score = match self.heuristic {
+                    Heuristic::SkylineMinWaste => (waste, top),
+                    _ => (top, self.skyline[i].width as u64),
+                };
+                if best.is_none_or(|(b, ..)| score < b) {
+                    best = Some((score, i, Rect::new(self.skyline[i].x, y, w, h), rotated));
+                }
+            }
+        }
+        let (_, i, rect, rotated) = best?;
+        self.place(i, rect);
+        Some((rect, rotated))
+    }
+
+    fn occupancy(&self) -> f32 {
+        self.used_area as f32 / (self.width as u64 * self.height as u64).max(1) as f32
+    }
+}
+
+// Atlas
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub page: usize,
+    /// Pixels of the image on its page, excluding extrusion. Width and height are swapped when rotated.
+    pub rect: Rect,
+    /// Rotated by 90°, source pixel `(0, 0)` lands on pixel `(x, bottom - 1)` of `rect`.
+    pub rotated: bool,
+    /// `(u0, v0, u1, v1)` bounds of `rect` on its page.
+    pub uv: Vec4f,
+}
+
+impl Placement {
+    /// UVs of the source image's corners, in the order
+    /// `(0, 0)`, `(width, 0)`, `(width, height)`, `(0, height)` of its own pixel space.
+    pub fn uv_corners(&self) -> [Vec2f; 4] {
+        let [u0, v0, u1, v1] = self.uv.data[0];
+        let uv = |u: f32, v: f32| Vec2f::from([[u, v]]);
+        if self.rotated {
+            [uv(u0, v1), uv(u0, v0), uv(u1, v0), uv(u1, v1)]
+        } else {
+            [uv(u0, v0), uv(u1, v0), uv(u1, v1), uv(u0, v1)]
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Atlas {
+    pub page_width: u32,
+    pub page_height: u32,
+    pub pages: usize,
+    /// Edge pixels repeated around each image by `compose`, as packed.
+    pub extrude: u32,
+    /// One entry per input size, in input order. `None` if the rect cannot fit on an empty page.
+    pub placements: Vec<Option<Placement>>,
+}
+
+impl Atlas {
+    /// RGBA8 pages with every image copied to its placement, ready for `Texture::new`.\
+    /// `images` holds `(width, height, rgba)` in the order given to `pack`.
+    pub fn compose(&self, images: &[(u32, u32, &[u8])]) -> Vec<Vec<u8>> {
+        let (page_width, page_height) = (self.page_width as i64, self.page_height as i64);
+        let mut pages = vec![vec![0u8; (page_width * page_height * 4) as usize]; self.pages];
+        for (placement, &(width, height, rgba)) in self.placements.iter().zip(images) {
+            debug_assert_eq!(rgba.len(), (width * height * 4) as usize);
+            let Some(placement) = placement else {
+                continue;
+            };
+            let Rect { x, y, width: w, height: h } = placement.rect;
+            let page = &mut pages[placement.page];
+            let e = self.extrude as i64;
+            for dy in -e..h as i64 + e {
+                for dx in -e..w as i64 + e {
+                    let (px, py) = (x as i64 + dx, y as i64 + dy);
+                    if px < 0 || py < 0 || px >= page_width || py >= page_height {
+                        continue;
+                    }
+                    // Extruded pixels repeat the nearest edge pixel.
+                    let cx = dx.clamp(0, w as i64 - 1) as u32;
+                    let cy = dy.clamp(0, h as i64 - 1) as u32;
+                    let (sx, sy) = if placement.rotated { (width - 1 - cy, cx) } else { (cx, cy) };
+                    let src = ((sy * width + sx) * 4) as usize;
+                    let dst = ((py * page_width + px) * 4) as usize;
+                    page[dst..dst + 4].copy_from_slice(&rgba[src..src + 4]);
+                }
+            }
+        }
+        pages
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AtlasPacker {
+    pub page_width: u32,
+    pub page_height: u32,
+    pub heuristic: Heuristic,
+    pub allow_rotation: bool,
+    /// Empty pixels between neighboring images, not applied along the page border.
+    pub padding: u32,
+    /// Pixels reserved around each image for its repeated edge, see `Atlas::compose`.
+    pub extrude: u32,
+}
+
+impl AtlasPacker {
+    pub fn new(page_width: u32, page_height: u32, heuristic: Heuristic) -> Self {
+        Self {
+            page_width,
+            page_height,
+            heuristic,
+            allow_rotation: false,
+            padding: 0,
+            extrude: 0,
+        }
+    }
+
+    fn new_bin(&self) -> Box<dyn Bin> {
+        // The page grows by the padding so the padding after the last image may hang off the border.
+        let (width, height) = (self.page_width + self.padding, self.page_height + self.padding);
+        match self.heuristic {
+            Heuristic::SkylineBottomLeft | Heuristic::SkylineMinWaste => {
+                Box::new(SkylineBin::new(width, height, self.heuristic))
+            }
+            _ => Box::new(MaxRectsBin::new(width, height, self.heuristic)),
+        }
+    }
+
+    /// Packs `(width, height)` sizes, largest first, opening pages as needed.
+    pub fn pack(&self, sizes: &[(u32, u32)]) -> Atlas {
+        let margin = 2 * self.extrude + self.padding;
+        let mut order: Vec<usize> = (0..sizes.len()).collect();
+        order.sort_by_key(|&i| {
+            let (w, h) = sizes[i];
+            std::cmp::Reverse((w.max(h), w.min(h)))
+        });
+
+        let mut bins: Vec<Box<dyn Bin>> = Vec::new();
+        let mut placements = vec![None; sizes.len()];
+        for i in order {
+            let (w, h) = sizes[i];
+            if w == 0 || h == 0 {
+                continue;
+            }
+            let (footprint_w, footprint_h) = (w + margin, h + margin);
+            let mut found = bins
+                .iter_mut()
+                .enumerate()
+                .find_map(|(page, bin)| Some((page, bin.insert(footprint_w, footprint_h, self.allow_rotation)?)));
+            if found.is_none() {
+                let mut bin = self.new_bin();
+                if let Some(placed) = bin.insert(footprint_w, footprint_h, self.allow_rotation) {
+                    bins.push(bin);
+                    found = Some((bins.len() - 1, placed));
+                }
+            }
+            let Some((page, (footprint, rotated))) = found else {
+                continue;
+            };
+            let (w, h) = if rotated { (h, w) } else { (w, h) };
+            let rect = Rect::new(footprint.x + self.extrude, footprint.y + self.extrude, w, h);
+            let uv = Vec4f::from([[
+                rect.x as f32 / self.page_width as f32,
+                rect.y as f32 / self.page_height as f32,
+                rect.right() as f32 / self.page_width as f32,
+                rect.bottom() as f32 / self.page_height as f32,
+            ]]);
+            placements[i] = Some(Placement {
+                page,
+                rect,
+                rotated,
+                uv,
+            });
+        }
+
+        Atlas {
+            page_width: self.page_width,
+            page_height: self.page_height,
+            pages: bins.len(),
+            extrude: self.extrude,
+            placements,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const HEURISTICS: [Heuristic; 7] = [
+        Heuristic::MaxRectsBestShortSide,
+        Heuristic::MaxRectsBestLongSide,
+        Heuristic::MaxRectsBestArea,
+        Heuristic::MaxRectsBottomLeft,
+        Heuristic::MaxRectsContactPoint,
+        Heuristic::SkylineBottomLeft,
+        Heuristic::SkylineMinWaste,
+    ];
+
+    #[test]
+    fn packs_without_overlap() {
+        let mut rng = StdRng::seed_from_u64(19);
+        let sizes: Vec<(u32, u32)> = (0..200).map(|_| (rng.gen_range(1..64), rng.gen_range(1..64))).collect();
+        for heuristic in HEURISTICS {
+            let mut packer = AtlasPacker::new(256, 256, heuristic);
+            packer.allow_rotation = true;
+            packer.padding = 2;
+            packer.extrude = 1;
+            let atlas = packer.pack(&sizes);
+            assert_eq!(atlas.extrude, 1);
+
+            // Extruded rects plus the padding after them, which must not overlap.
+            let mut footprints: Vec<(usize, Rect)> = Vec::new();
+            for (placement, &(w, h)) in atlas.placements.iter().zip(&sizes) {
+                let placement = placement.expect("every size fits on an empty page");
+                let Rect { x, y, width, height } = placement.rect;
+                let expected = if placement.rotated { (h, w) } else { (w, h) };
+                assert_eq!((width, height), expected, "{heuristic:?}");
+                assert!(placement.page < atlas.pages);
+                assert!(x >= 1 && y >= 1, "{heuristic:?} {:?}", placement.rect);
+                assert!(placement.rect.right() < 256 && placement.rect.bottom() < 256, "{heuristic:?} {:?}", placement.rect);
+                footprints.push((placement.page, Rect::new(x - 1, y - 1, width + 4, height + 4)));
+            }
+            for (i, (page, a)) in footprints.iter().enumerate() {
+                for (other, b) in &footprints[i + 1..] {
+                    assert!(page != other || !a.intersects(b), "{heuristic:?} {a:?} {b:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compose_rotated_matches_uv_corners() {
+        let (width, height) = (3, 2);
+        let image: Vec<u8> = (0..width * height).flat_map(|i| [i as u8, 10, 20, 255]).collect();
+        // Only the rotated footprint fits the page.
+        let mut packer = AtlasPacker::new(4, 5, Heuristic::MaxRectsBestShortSide);
+        packer.allow_rotation = true;
+        packer.extrude = 1;
+        let atlas = packer.pack(&[(width, height)]);
+        let placement = atlas.placements[0].unwrap();
+        assert!(placement.rotated);
+
+        let pages = atlas.compose(&[(width, height, &image)]);
+        assert_eq!(pages.len(), 1);
+        let [c0, c1, _, c3] = placement.uv_corners();
+        for sy in 0..height {
+            for sx in 0..width {
+                // Pixel centers map affinely through the corners.
+                let (s, t) = ((sx as f32 + 0.5) / width as f32, (sy as f32 + 0.5) / height as f32);
+                let uv = c0 + (c1 - c0) * s + (c3 - c0) * t;
+                let (px, py) = ((uv.x() * 4.0) as usize, (uv.y() * 5.0) as usize);
+                let src = ((sy * width + sx) * 4) as usize;
+                let dst = (py * 4 + px) * 4;
+                assert_eq!(pages[0][dst..dst + 4], image[src..src + 4], "source ({sx}, {sy})");
+            }
+        }
+        // The extruded border repeats the edge, so the whole page is covered.
+        assert!(pages[0].chunks(4).all(|p| p[3] == 255));
+    }
+}
@@ -1,3 +1,4 @@
 #![allow(unused)]
 
-pub mod image;
\ No newline at end of file
+pub mod image;
+pub mod atlas;
\ No newline at end of file
@@ -0,0 +1,218 @@
+/*
+    Implementation of angle units.
+    `Rad<T>` and `Deg<T>` wrap a scalar so that radians and degrees cannot be mixed up,
+    rotation and projection builders take `impl Into<Rad<T>>` and accept either.
+
+    Bare scalars do not convert, write `Rad(x)` when a value already is in radians.
+*/
+
+use num_traits::Float;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Rad<T>(pub T);
+
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Deg<T>(pub T);
+
+impl<T: Float> From<Deg<T>> for Rad<T> {
+    fn from(deg: Deg<T>) -> Self {
+        Rad(deg.0.to_radians())
+    }
+}
+
+impl<T: Float> From<Rad<T>> for Deg<T> {
+    fn from(rad: Rad<T>) -> Self {
+        Deg(rad.0.to_degrees())
+    }
+}
+
+impl<T: Float> Rad<T> {
+    pub fn turn() -> Self {
+        Rad(T::from(std::f64::consts::TAU).unwrap())
+    }
+
+    pub fn half_turn() -> Self {
+        Rad(T::from(std::f64::consts::PI).unwrap())
+    }
+}
+
+impl<T: Float> Deg<T> {
+    pub fn turn() -> Self {
+        Deg(T::from(360).unwrap())
+    }
+
+    pub fn half_turn() -> Self {
+        Deg(T::from(180).unwrap())
+    }
+}
+
+macro_rules! impl_angle {
+    ($angle:ident) => {
+        impl<T: Float> $angle<T> {
+            pub fn sin(self) -> T {
+                Rad::from(self).0.sin()
+            }
+
+            pub fn cos(self) -> T {
+                Rad::from(self).0.cos()
+            }
+
+            pub fn tan(self) -> T {
+                Rad::from(self).0.tan()
+            }
+
+            /// Returns `(sin, cos)`.
+            pub fn sin_cos(self) -> (T, T) {
+                Rad::from(self).0.sin_cos()
+            }
+
+            pub fn asin(x: T) -> Self {
+                Rad(x.asin()).into()
+            }
+
+            pub fn acos(x: T) -> Self {
+                Rad(x.acos()).into()
+            }
+
+            pub fn atan(x: T) -> Self {
+                Rad(x.atan()).into()
+            }
+
+            pub fn atan2(y: T, x: T) -> Self {
+                Rad(y.atan2(x)).into()
+            }
+
+            /// Wraps into [0, turn).
+            pub fn normalize(self) -> Self {
+                let turn = Self::turn().0;
+                let wrapped = self.0 % turn;
+                let wrapped = if wrapped < T::zero() { wrapped + turn } else { wrapped };
+                // A tiny negative angle plus a turn can round up to the turn itself.
+                if wrapped >= turn {
+                    $angle(T::zero())
+                } else {
+                    $angle(wrapped)
+                }
+            }
+
+            /// Wraps into (-half turn, half turn].
+            pub fn normalize_signed(self) -> Self {
+                let wrapped = self.normalize();
+                if wrapped > Self::half_turn() {
+                    wrapped - Self::turn()
+                } else {
+                    wrapped
+                }
+            }
+        }
+
+        impl<T: Float> Add for $angle<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $angle(self.0 + rhs.0)
+            }
+        }
+
+        impl<T: Float> Sub for $angle<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $angle(self.0 - rhs.0)
+            }
+        }
+
+        impl<T: Float> Neg for $angle<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $angle(-self.0)
+            }
+        }
+
+        impl<T: Float> Mul<T> for $angle<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $angle(self.0 * rhs)
+            }
+        }
+
+        impl<T: Float> Div<T> for $angle<T> {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self {
+                $angle(self.0 / rhs)
+            }
+        }
+
+        /// Ratio of two angles.
+        impl<T: Float> Div for $angle<T> {
+            type Output = T;
+
+            fn div(self, rhs: Self) -> T {
+                self.0 / rhs.0
+            }
+        }
+
+        impl<T: Float> AddAssign for $angle<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 = self.0 + rhs.0;
+            }
+        }
+
+        impl<T: Float> SubAssign for $angle<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 = self.0 - rhs.0;
+            }
+        }
+    };
+}
+
+impl_angle!(Rad);
+impl_angle!(Deg);
+
+impl<T: std::fmt::Display> std::fmt::Display for Rad<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} rad", self.0)
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Deg<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}°", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let rad: Rad<f64> = Deg(180.0).into();
+        assert!((rad.0 - std::f64::consts::PI).abs() < 1e-12);
+        let deg: Deg<f64> = Rad::half_turn().into();
+        assert!((deg.0 - 180.0).abs() < 1e-12);
+        assert!((Deg(30.0_f64).sin() - 0.5).abs() < 1e-12);
+        assert!((Deg::<f64>::atan2(1.0, 1.0).0 - 45.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn normalize_stays_below_a_turn() {
+        for angle in [Rad(-1e-9_f32), Rad(-f32::EPSILON), Rad(-1e-30)] {
+            let wrapped = angle.normalize();
+            assert!(wrapped >= Rad(0.0) && wrapped < Rad::turn(), "{wrapped:?}");
+        }
+        assert_eq!(Rad(-1e-9_f32).normalize(), Rad(0.0));
+        assert_eq!(Deg(-1e-6_f32).normalize(), Deg(0.0));
+        assert_eq!(Deg(-90.0_f64).normalize(), Deg(270.0));
+        assert_eq!(Deg(720.0_f64).normalize(), Deg(0.0));
+        assert_eq!(Deg(540.0_f64).normalize_signed(), Deg(180.0));
+        assert_eq!(Deg(-180.0_f64).normalize_signed(), Deg(180.0));
+        assert_eq!(Deg(-90.0_f64).normalize_signed(), Deg(-90.0));
+    }
+}
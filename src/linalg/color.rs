@@ -0,0 +1,569 @@
+/*
+    Implementation of RGBA colors.
+    Channels are f32 with straight (non-premultiplied) alpha, in the sRGB encoding
+    used by hex codes and color pickers unless converted with `to_linear`.
+
+    Hue is in degrees for HSV, HSL and OKLCH, every other component lies in [0, 1].
+    OKLab is Björn Ottosson's perceptual space, L in [0, 1] and a, b roughly in [-0.4, 0.4].
+*/
+
+use super::*;
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub const TRANSPARENT: Self = Self::new(0.0, 0.0, 0.0, 0.0);
+    pub const BLACK: Self = Self::rgb(0.0, 0.0, 0.0);
+    pub const WHITE: Self = Self::rgb(1.0, 1.0, 1.0);
+    pub const RED: Self = Self::rgb(1.0, 0.0, 0.0);
+    pub const GREEN: Self = Self::rgb(0.0, 1.0, 0.0);
+    pub const BLUE: Self = Self::rgb(0.0, 0.0, 1.0);
+    pub const YELLOW: Self = Self::rgb(1.0, 1.0, 0.0);
+    pub const CYAN: Self = Self::rgb(0.0, 1.0, 1.0);
+    pub const MAGENTA: Self = Self::rgb(1.0, 0.0, 1.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Opaque color.
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self::new(r, g, b, 1.0)
+    }
+
+    pub fn with_alpha(self, a: f32) -> Self {
+        Self { a, ..self }
+    }
+
+    pub fn to_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    pub fn clamp(self) -> Self {
+        Self::new(
+            self.r.clamp(0.0, 1.0),
+            self.g.clamp(0.0, 1.0),
+            self.b.clamp(0.0, 1.0),
+            self.a.clamp(0.0, 1.0),
+        )
+    }
+
+    /// Flattens colors into 4 floats each, ready for `compose_data`.
+    pub fn flatten(colors: &[Color]) -> Vec<f32> {
+        colors.iter().flat_map(|c| c.to_array()).collect()
+    }
+
+    // Packed
+
+    pub fn from_rgba8(bytes: [u8; 4]) -> Self {
+        let [r, g, b, a] = bytes.map(|x| x as f32 / 255.0);
+        Self::new(r, g, b, a)
+    }
+
+    /// Channels clamped to [0, 1] and rounded to the nearest byte.
+    pub fn to_rgba8(self) -> [u8; 4] {
+        self.clamp().to_array().map(|x| (x * 255.0).round() as u8)
+    }
+
+    /// From `0xRRGGBBAA`, the byte order of hex codes.
+    pub fn from_u32(packed: u32) -> Self {
+        Self::from_rgba8(packed.to_be_bytes())
+    }
+
+    /// To `0xRRGGBBAA`, the byte order of hex codes.
+    pub fn to_u32(self) -> u32 {
+        u32::from_be_bytes(self.to_rgba8())
+    }
+
+    /// Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, the `#` being optional.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        // `from_str_radix` accepts a leading sign, which is not a hex digit.
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let nibble = |i: usize| u8::from_str_radix(&digits[i..i + 1], 16).ok();
+        let byte = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+        let bytes = match digits.len() {
+            3 | 4 => {
+                let mut bytes = [255; 4];
+                for (i, b) in bytes.iter_mut().take(digits.len()).enumerate() {
+                    *b = nibble(i)? * 17;
+                }
+                bytes
+            }
+            6 | 8 => {
+                let mut bytes = [255; 4];
+                for (i, b) in bytes.iter_mut().take(digits.len() / 2).enumerate() {
+                    *b = byte(2 * i)?;
+                }
+                bytes
+            }
+            _ => return None,
+        };
+        Some(Self::from_rgba8(bytes))
+    }
+
+    /// Formats as `#rrggbbaa`.
+    pub fn to_hex(self) -> String {
+        format!("#{:08x}", self.to_u32())
+    }
+
+    // sRGB
+
+    /// Decodes sRGB channels to linear light, alpha is unchanged.
+    pub fn to_linear(self) -> Self {
+        Self::new(
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+            self.a,
+        )
+    }
+
+    /// Encodes linear channels to sRGB, alpha is unchanged.
+    pub fn to_srgb(self) -> Self {
+        Self::new(
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+            self.a,
+        )
+    }
+
+    // Premultiplied Alpha
+
+    pub fn premultiply(self) -> Self {
+        Self::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// Inverse of `premultiply`, fully transparent colors become transparent black.
+    pub fn unpremultiply(self) -> Self {
+        if self.a == 0.0 {
+            return Self::TRANSPARENT;
+        }
+        Self::new(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
+    // HSV & HSL
+
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Self {
+        let chroma = value * saturation;
+        let (r, g, b) = hue_to_rgb(hue, chroma);
+        let m = value - chroma;
+        Self::new(r + m, g + m, b + m, alpha)
+    }
+
+    /// `(hue, saturation, value)`.
+    pub fn to_hsv(self) -> Vec3f {
+        let (hue, max, chroma) = self.hue_max_chroma();
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+        Vec3f::from([[hue, saturation, max]])
+    }
+
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let (r, g, b) = hue_to_rgb(hue, chroma);
+        let m = lightness - 0.5 * chroma;
+        Self::new(r + m, g + m, b + m, alpha)
+    }
+
+    /// `(hue, saturation, lightness)`.
+    pub fn to_hsl(self) -> Vec3f {
+        let (hue, max, chroma) = self.hue_max_chroma();
+        let lightness = max - 0.5 * chroma;
+        let saturation = if lightness > 0.0 && lightness < 1.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        Vec3f::from([[hue, saturation, lightness]])
+    }
+
+    fn hue_max_chroma(self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+        let sector = if chroma == 0.0 {
+            0.0
+        } else if max == self.r {
+            (self.g - self.b) / chroma
+        } else if max == self.g {
+            (self.b - self.r) / chroma + 2.0
+        } else {
+            (self.r - self.g) / chroma + 4.0
+        };
+        (wrap_hue(60.0 * sector), max, chroma)
+    }
+
+    // OKLab & OKLCH
+
+    /// `(L, a, b)` of this sRGB color.
+    pub fn to_oklab(self) -> Vec3f {
+        let c = self.to_linear();
+        let l = (0.41222147 * c.r + 0.53633254 * c.g + 0.051445993 * c.b).cbrt();
+        let m = (0.2119035 * c.r + 0.6806995 * c.g + 0.10739696 * c.b).cbrt();
+        let s = (0.08830246 * c.r + 0.28171884 * c.g + 0.6299787 * c.b).cbrt();
+        Vec3f::from([[
+            0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+            1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+            0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+        ]])
+    }
+
+    /// sRGB color from `(L, a, b)`, out of gamut results are not clamped.
+    pub fn from_oklab(lab: Vec3f, alpha: f32) -> Self {
+        let l = lab.x() + 0.39633778 * lab.y() + 0.21580376 * lab.z();
+        let m = lab.x() - 0.105561346 * lab.y() - 0.06385417 * lab.z();
+        let s = lab.x() - 0.08948418 * lab.y() - 1.2914855 * lab.z();
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+        Self::new(
+            4.0767417 * l - 3.3077116 * m + 0.23096993 * s,
+            -1.268438 * l + 2.6097574 * m - 0.34131938 * s,
+            -0.0041960863 * l - 0.7034186 * m + 1.7076147 * s,
+            alpha,
+        )
+        .to_srgb()
+    }
+
+    /// `(L, chroma, hue)` of this sRGB color.
+    pub fn to_oklch(self) -> Vec3f {
+        let lab = self.to_oklab();
+        let hue = wrap_hue(lab.z().atan2(lab.y()).to_degrees());
+        Vec3f::from([[lab.x(), lab.y().hypot(lab.z()), hue]])
+    }
+
+    /// sRGB color from `(L, chroma, hue)`, out of gamut results are not clamped.
+    pub fn from_oklch(lch: Vec3f, alpha: f32) -> Self {
+        let (sin, cos) = lch.z().to_radians().sin_cos();
+        Self::from_oklab(Vec3f::from([[lch.x(), lch.y() * cos, lch.y() * sin]]), alpha)
+    }
+
+    // Interpolation
+
+    /// Componentwise interpolation in the stored encoding.
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+
+    /// Perceptual interpolation through OKLab, free of the dark band
+    /// that mixing sRGB channels leaves between saturated colors.
+    pub fn mix(self, other: Self, t: f32) -> Self {
+        let lab = self.to_oklab().lerp(&other.to_oklab(), t);
+        Self::from_oklab(lab, self.a + (other.a - self.a) * t)
+    }
+
+    /// Perceptual interpolation through OKLCH, the hue taking the shorter way around.\
+    /// Keeps saturation between two saturated colors where `mix` passes closer to gray.
+    pub fn mix_oklch(self, other: Self, t: f32) -> Self {
+        let (from, to) = (self.to_oklch(), other.to_oklch());
+        let turn = (to.z() - from.z() + 180.0).rem_euclid(360.0) - 180.0;
+        let lch = Vec3f::from([[
+            from.x() + (to.x() - from.x()) * t,
+            from.y() + (to.y() - from.y()) * t,
+            from.z() + turn * t,
+        ]]);
+        Self::from_oklch(lch, self.a + (other.a - self.a) * t)
+    }
+}
+
+/// sRGB transfer function, decoding to linear light.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    let x = c.abs();
+    let linear = if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    };
+    linear.copysign(c)
+}
+
+/// Inverse sRGB transfer function, encoding linear light.
+pub fn linear_to_srgb(c: f32) -> f32 {
+    let x = c.abs();
+    let encoded = if x <= 0.0031308 {
+        12.92 * x
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    };
+    encoded.copysign(c)
+}
+
+/// Hue in [0, 360), `rem_euclid` alone rounds tiny negative angles up to 360.
+fn wrap_hue(hue: f32) -> f32 {
+    let hue = hue.rem_euclid(360.0);
+    if hue < 360.0 {
+        hue
+    } else {
+        0.0
+    }
+}
+
+/// RGB with the given hue and chroma before the lightness offset.
+fn hue_to_rgb(hue: f32, chroma: f32) -> (f32, f32, f32) {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+    match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    }
+}
+
+// Operators
+
+impl std::ops::Add for Color {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.r + rhs.r, self.g + rhs.g, self.b + rhs.b, self.a + rhs.a)
+    }
+}
+
+impl std::ops::Sub for Color {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.r - rhs.r, self.g - rhs.g, self.b - rhs.b, self.a - rhs.a)
+    }
+}
+
+/// Modulation, channel by channel.
+impl std::ops::Mul for Color {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.r * rhs.r, self.g * rhs.g, self.b * rhs.b, self.a * rhs.a)
+    }
+}
+
+impl std::ops::Mul<f32> for Color {
+    type Output = Self;
+    fn mul(self, rhs: f32) -> Self {
+        Self::new(self.r * rhs, self.g * rhs, self.b * rhs, self.a * rhs)
+    }
+}
+
+// Conversions
+
+impl From<Vec4f> for Color {
+    fn from(v: Vec4f) -> Self {
+        Self::new(v.x(), v.y(), v.z(), v.w())
+    }
+}
+
+impl From<Color> for Vec4f {
+    fn from(c: Color) -> Self {
+        Vec4f::from([c.to_array()])
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from([r, g, b, a]: [f32; 4]) -> Self {
+        Self::new(r, g, b, a)
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(c: Color) -> Self {
+        c.to_array()
+    }
+}
+
+impl From<[u8; 4]> for Color {
+    fn from(bytes: [u8; 4]) -> Self {
+        Self::from_rgba8(bytes)
+    }
+}
+
+impl std::str::FromStr for Color {
+    type Err = ParseColorError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s).ok_or(ParseColorError)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseColorError;
+
+impl std::fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected a hex color such as #rgb, #rrggbb or #rrggbbaa")
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+impl std::fmt::Display for Color {
+    /// Formats as `#rrggbbaa`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_forms() {
+        let expected = Color::from_rgba8([0x11, 0x22, 0x33, 0xff]);
+        assert_eq!(Color::from_hex("#123"), Some(expected));
+        assert_eq!(Color::from_hex("112233"), Some(expected));
+        assert_eq!(Color::from_hex("#112233ff"), Some(expected));
+        assert_eq!(Color::from_hex("#1234"), Some(Color::from_rgba8([0x11, 0x22, 0x33, 0x44])));
+        assert_eq!(Color::from_hex("#AbCdEf"), Some(Color::from_rgba8([0xab, 0xcd, 0xef, 0xff])));
+    }
+
+    #[test]
+    fn hex_rejects_non_digits() {
+        for hex in ["", "#", "#12", "#12345", "#+f+f+f", "+fff", "#-1-1-1", "#12g", "#1122 3", "#ｆｆｆ", "##fff"] {
+            assert_eq!(Color::from_hex(hex), None, "{hex}");
+        }
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        for packed in [0x00000000, 0xffffffff, 0x12345678, 0xdeadbeef] {
+            let color = Color::from_u32(packed);
+            assert_eq!(color.to_u32(), packed);
+            assert_eq!(Color::from_hex(&color.to_hex()), Some(color));
+        }
+    }
+
+    fn assert_color(a: Color, b: Color, tolerance: f32) {
+        let (a, b) = (a.to_array(), b.to_array());
+        assert!(a.iter().zip(&b).all(|(x, y)| (x - y).abs() <= tolerance), "{a:?} != {b:?}");
+    }
+
+    fn assert_vec3(a: Vec3f, b: [f32; 3], tolerance: f32) {
+        assert!((a - Vec3f::from([b])).length() <= tolerance, "{a:?} != {b:?}");
+    }
+
+    fn samples() -> Vec<Color> {
+        let steps = [0.0, 0.1, 0.35, 0.5, 0.8, 1.0];
+        let mut colors = Vec::new();
+        for r in steps {
+            for g in steps {
+                for b in steps {
+                    colors.push(Color::new(r, g, b, 0.75));
+                }
+            }
+        }
+        colors
+    }
+
+    #[test]
+    fn hsv_hsl_known_values() {
+        assert_vec3(Color::RED.to_hsv(), [0.0, 1.0, 1.0], 0.0);
+        assert_vec3(Color::CYAN.to_hsv(), [180.0, 1.0, 1.0], 0.0);
+        assert_vec3(Color::rgb(0.5, 0.25, 1.0).to_hsv(), [260.0, 0.75, 1.0], 1e-4);
+        assert_vec3(Color::rgb(0.5, 0.25, 1.0).to_hsl(), [260.0, 1.0, 0.625], 1e-4);
+        assert_vec3(Color::BLUE.to_hsl(), [240.0, 1.0, 0.5], 0.0);
+        assert_color(Color::from_hsv(120.0, 0.5, 0.8, 1.0), Color::rgb(0.4, 0.8, 0.4), 1e-6);
+        assert_color(Color::from_hsl(300.0, 0.5, 0.25, 1.0), Color::rgb(0.375, 0.125, 0.375), 1e-6);
+
+        // Gray has no chroma, the hue is reported as zero and ignored on the way back.
+        for gray in [Color::BLACK, Color::rgb(0.5, 0.5, 0.5), Color::WHITE] {
+            assert_vec3(gray.to_hsv(), [0.0, 0.0, gray.r], 0.0);
+            assert_vec3(gray.to_hsl(), [0.0, 0.0, gray.r], 0.0);
+            for hue in [0.0, 90.0, 275.0] {
+                assert_color(Color::from_hsv(hue, 0.0, gray.r, 1.0), gray, 0.0);
+                assert_color(Color::from_hsl(hue, 0.0, gray.r, 1.0), gray, 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn hue_wraps_at_360() {
+        for hue in [0.0, 45.0, 200.0, 359.0] {
+            for turns in [-2.0, -1.0, 1.0, 3.0] {
+                let wrapped = hue + 360.0 * turns;
+                assert_color(Color::from_hsv(wrapped, 0.7, 0.9, 1.0), Color::from_hsv(hue, 0.7, 0.9, 1.0), 1e-4);
+                assert_color(Color::from_hsl(wrapped, 0.7, 0.4, 1.0), Color::from_hsl(hue, 0.7, 0.4, 1.0), 1e-4);
+            }
+        }
+        // Just short of red on the magenta side.
+        for color in [Color::rgb(1.0, 0.0, 1e-3), Color::rgb(1.0, 0.0, 1e-9)] {
+            for hue in [color.to_hsv().x(), color.to_hsl().x()] {
+                assert!((0.0..360.0).contains(&hue), "{hue}");
+            }
+        }
+    }
+
+    #[test]
+    fn hsv_hsl_round_trip() {
+        for color in samples() {
+            let hsv = color.to_hsv();
+            assert_color(Color::from_hsv(hsv.x(), hsv.y(), hsv.z(), color.a), color, 1e-5);
+            let hsl = color.to_hsl();
+            assert_color(Color::from_hsl(hsl.x(), hsl.y(), hsl.z(), color.a), color, 1e-5);
+        }
+    }
+
+    #[test]
+    fn srgb_transfer() {
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert_eq!(srgb_to_linear(1.0), 1.0);
+        assert!((srgb_to_linear(0.5) - 0.21404114).abs() < 1e-6);
+        assert!((linear_to_srgb(0.5) - 0.7353569).abs() < 1e-6);
+        assert!((srgb_to_linear(0.04045) - 0.0031308).abs() < 1e-6);
+        assert_eq!(srgb_to_linear(-0.5), -srgb_to_linear(0.5));
+        for color in samples() {
+            let linear = color.to_linear();
+            assert_eq!(linear.a, color.a);
+            assert!(linear.r <= color.r && linear.g <= color.g && linear.b <= color.b);
+            assert_color(linear.to_srgb(), color, 1e-6);
+        }
+    }
+
+    #[test]
+    fn oklab_reference_values() {
+        // Ottosson's reference: white is L = 1 with no chroma, black is the origin.
+        assert_vec3(Color::WHITE.to_oklab(), [1.0, 0.0, 0.0], 1e-4);
+        assert_vec3(Color::BLACK.to_oklab(), [0.0, 0.0, 0.0], 0.0);
+        assert_vec3(Color::RED.to_oklab(), [0.62796, 0.22486, 0.12585], 1e-4);
+        assert_vec3(Color::GREEN.to_oklab(), [0.86644, -0.23389, 0.17950], 1e-4);
+        assert_vec3(Color::BLUE.to_oklab(), [0.45201, -0.03246, -0.31153], 1e-4);
+        for color in samples() {
+            assert_color(Color::from_oklab(color.to_oklab(), color.a), color, 1e-4);
+            let lch = color.to_oklch();
+            assert!((0.0..360.0).contains(&lch.z()));
+            assert_color(Color::from_oklch(lch, color.a), color, 1e-4);
+        }
+    }
+
+    #[test]
+    fn premultiplied_alpha() {
+        let color = Color::new(0.5, 1.0, 0.25, 0.5);
+        assert_eq!(color.premultiply(), Color::new(0.25, 0.5, 0.125, 0.5));
+        assert_eq!(color.premultiply().unpremultiply(), color);
+        assert_eq!(Color::RED.premultiply(), Color::RED);
+        assert_eq!(Color::new(0.3, 0.6, 0.9, 0.0).premultiply().unpremultiply(), Color::TRANSPARENT);
+        for color in samples() {
+            assert_color(color.premultiply().unpremultiply(), color, 1e-6);
+        }
+    }
+
+    #[test]
+    fn mix_oklch_takes_the_short_hue_path() {
+        for (from, to, middle) in [(350.0, 10.0, 0.0), (10.0, 350.0, 0.0), (300.0, 60.0, 0.0), (100.0, 200.0, 150.0)] {
+            let a = Color::from_oklch(Vec3f::from([[0.7, 0.1, from]]), 1.0);
+            let b = Color::from_oklch(Vec3f::from([[0.7, 0.1, to]]), 0.0);
+            let mixed = a.mix_oklch(b, 0.5);
+            let hue = mixed.to_oklch().z();
+            let off = (hue - middle + 180.0).rem_euclid(360.0) - 180.0;
+            assert!(off.abs() < 0.5, "{from} -> {to}: {hue}");
+            assert!((mixed.a - 0.5).abs() < 1e-6);
+            assert_color(a.mix_oklch(b, 0.0), a, 1e-4);
+            assert_color(a.mix_oklch(b, 1.0), b, 1e-4);
+        }
+    }
+}
@@ -4,8 +4,9 @@
 */
 
 use super::*;
+use angle::Rad;
 use crate::cmp_from;
-use quaternion::Quaternion;
+use quaternion::{Quaternion, SLERP_THRESHOLD};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Complex<T> {
@@ -64,10 +65,143 @@ where
         Complex::new_from(theta.cos(), theta.sin()) * c.exp()
     }
 
+    /// Principal branch, the imaginary part lies in (-π, π].
+    pub fn ln(&self) -> Self {
+        Self::new_from(self.abs().ln(), self.arg().0)
+    }
+
+    /// A zero base follows `f32::powf`: `0^0` is one, negative powers are infinite.
+    pub fn powf(&self, n: T) -> Self {
+        if self.is_zero() {
+            return Self::new_from(T::zero().powf(n), T::zero());
+        }
+        let (r, theta) = self.to_polar();
+        Self::from_polar(r.powf(n), theta * n)
+    }
+
+    /// A zero base follows `powf` on the real part of `n`.
+    /// With a purely imaginary `n` the result has no limit and is NaN.
+    pub fn powc(&self, n: Self) -> Self {
+        if self.is_zero() {
+            if n.x == T::zero() && n.y != T::zero() {
+                return Self::new_from(T::nan(), T::nan());
+            }
+            return self.powf(n.x);
+        }
+        (n * self.ln()).exp()
+    }
+
+    /// Principal square root, the real part is non-negative.
+    pub fn sqrt(&self) -> Self {
+        if self.is_zero() {
+            return Self::new();
+        }
+        let two = T::one() + T::one();
+        let t = ((self.abs() + self.x.abs()) / two).sqrt();
+        if self.x >= T::zero() {
+            Self::new_from(t, self.y / (two * t))
+        } else {
+            Self::new_from(self.y.abs() / (two * t), t.copysign(self.y))
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.x == T::zero() && self.y == T::zero()
+    }
+}
+
+// Trigonometric & Hyperbolic
+
+impl<T> Complex<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    pub fn sin(&self) -> Self {
+        Self::new_from(self.x.sin() * self.y.cosh(), self.x.cos() * self.y.sinh())
+    }
+
+    pub fn cos(&self) -> Self {
+        Self::new_from(self.x.cos() * self.y.cosh(), -self.x.sin() * self.y.sinh())
+    }
+
+    pub fn tan(&self) -> Self {
+        let (x, y) = (self.x + self.x, self.y + self.y);
+        Self::new_from(x.sin(), y.sinh()) / (x.cos() + y.cosh())
+    }
+
+    pub fn sinh(&self) -> Self {
+        Self::new_from(self.x.sinh() * self.y.cos(), self.x.cosh() * self.y.sin())
+    }
+
+    pub fn cosh(&self) -> Self {
+        Self::new_from(self.x.cosh() * self.y.cos(), self.x.sinh() * self.y.sin())
+    }
+
+    pub fn tanh(&self) -> Self {
+        let (x, y) = (self.x + self.x, self.y + self.y);
+        Self::new_from(x.sinh(), y.sin()) / (x.cosh() + y.cos())
+    }
+}
+
+// Polar & Rotation
+
+impl<T> Complex<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    pub fn from_polar(r: T, theta: impl Into<Rad<T>>) -> Self {
+        let (sin, cos) = theta.into().sin_cos();
+        Self::new_from(r * cos, r * sin)
+    }
+
+    /// Unit complex number rotating counter-clockwise by `angle`.
+    pub fn from_angle(angle: impl Into<Rad<T>>) -> Self {
+        Self::from_polar(T::one(), angle)
+    }
+
+    /// Angle in (-π, π].
+    pub fn arg(&self) -> Rad<T> {
+        Rad(self.y.atan2(self.x))
+    }
+
+    /// Returns `(abs, arg)`.
+    pub fn to_polar(self) -> (T, Rad<T>) {
+        (self.abs(), self.arg())
+    }
+
+    /// Rotates and scales `v` as the product `self * (v.x + v.y i)`.
+    pub fn rotate_vec2(&self, v: Vec2<T>) -> Vec2<T> {
+        Vec2::from([[self.x * v.x() - self.y * v.y(), self.y * v.x() + self.x * v.y()]])
+    }
+
+    pub fn to_mat2(self) -> Mat2<T> {
+        Mat2::from([[self.x, self.y], [-self.y, self.x]])
+    }
+
+    /// Homogeneous 2D transform for use with `Vec3` of the form `(x, y, 1)`.
+    pub fn to_mat3(self) -> Mat3<T> {
+        let (o, l) = (T::zero(), T::one());
+        Mat3::from([[self.x, self.y, o], [-self.y, self.x, o], [o, o, l]])
+    }
+
+    /// Spherical interpolation of unit complex numbers.\
+    /// Falls back to `nlerp` when the inputs are nearly parallel,
+    /// and turns counter-clockwise when they are opposite.
     pub fn slerp(&self, other: &Self, t: T) -> Self {
-        let theta = self.dot(&other).acos();
-        let alpha = ((T::one() - t) * theta).sin() / theta.sin();
-        let beta = (t * theta).sin() / theta.sin();
+        let threshold = T::from(SLERP_THRESHOLD).unwrap();
+        let cos_theta = self.dot(other).max(-T::one()).min(T::one());
+        if cos_theta > T::one() - threshold {
+            return self.nlerp(other, t);
+        }
+        if cos_theta < threshold - T::one() {
+            let half_turn = T::from(std::f64::consts::PI).unwrap() * t;
+            let perpendicular = Self::new_from(-self.y, self.x);
+            return *self * half_turn.cos() + perpendicular * half_turn.sin();
+        }
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let alpha = ((T::one() - t) * theta).sin() / sin_theta;
+        let beta = (t * theta).sin() / sin_theta;
         *self * alpha + *other * beta
     }
 }
@@ -244,7 +378,7 @@ where
     fn div(self, rhs: Self) -> Self::Output {
         let (a, b) = (self.x, self.y);
         let (c, d) = (rhs.x, rhs.y);
-        Self::new_from(a * c - b * d, a * d + b * c) / (c * c + d * d)
+        Self::new_from(a * c + b * d, b * c - a * d) / (c * c + d * d)
     }
 }
 
@@ -255,4 +389,161 @@ where
     fn div_assign(&mut self, rhs: Self) {
         *self = *self / rhs;
     }
-}
\ No newline at end of file
+}
+
+impl<T> std::ops::Mul<Vec2<T>> for Complex<T>
+where
+    T: num_traits::Float + Copy + Default,
+{
+    type Output = Vec2<T>;
+    fn mul(self, rhs: Vec2<T>) -> Self::Output {
+        self.rotate_vec2(rhs)
+    }
+}
+
+impl<T> From<Complex<T>> for Mat2<T>
+where
+    T: num_traits::Float + Copy + Default,
+{
+    fn from(value: Complex<T>) -> Self {
+        value.to_mat2()
+    }
+}
+
+impl<T> From<Complex<T>> for Mat3<T>
+where
+    T: num_traits::Float + Copy + Default,
+{
+    fn from(value: Complex<T>) -> Self {
+        value.to_mat3()
+    }
+}
+
+impl<T> std::fmt::Display for Complex<T>
+where
+    T: num_traits::Float + std::fmt::Display,
+{
+    /// Formats as `x + yi`, honoring the precision flag.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.y.is_sign_negative() { '-' } else { '+' };
+        match f.precision() {
+            Some(p) => write!(f, "{:.*} {} {:.*}i", p, self.x, sign, p, self.y.abs()),
+            None => write!(f, "{} {} {}i", self.x, sign, self.y.abs()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use std::f64::consts::PI;
+
+    type C = Complex<f64>;
+
+    fn assert_close(a: C, b: C) {
+        assert!((a - b).abs() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    fn samples() -> Vec<C> {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut zs: Vec<C> = (0..100)
+            .map(|_| C::new_from(rng.gen_range(-5.0..5.0), rng.gen_range(-5.0..5.0)))
+            .collect();
+        // Axes and the branch cut of `ln` and `sqrt`.
+        let axes = [(1.0, 0.0), (-1.0, 0.0), (0.0, 2.0), (0.0, -2.0), (-3.0, 0.0)];
+        zs.extend(axes.map(|(x, y)| C::new_from(x, y)));
+        zs
+    }
+
+    #[test]
+    fn euler_identity() {
+        let z = C::new_from(0.0, PI).exp() + C::new_from(1.0, 0.0);
+        assert!(z.abs() < 1e-12, "{:?}", z);
+    }
+
+    #[test]
+    fn exp_inverts_ln() {
+        for z in samples() {
+            assert_close(z.ln().exp(), z);
+            let ln = z.ln();
+            assert!(ln.y > -PI && ln.y <= PI);
+        }
+    }
+
+    #[test]
+    fn sqrt_squares_back() {
+        for z in samples() {
+            let root = z.sqrt();
+            assert_close(root * root, z);
+            assert!(root.x >= 0.0);
+        }
+        assert_close(C::new_from(-4.0, 0.0).sqrt(), C::new_from(0.0, 2.0));
+    }
+
+    #[test]
+    fn abs_is_multiplicative() {
+        let zs = samples();
+        for (z, w) in zs.iter().zip(zs.iter().rev()) {
+            assert!(((*z * *w).abs() - z.abs() * w.abs()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn de_moivre() {
+        for theta in [-2.0, 0.3, 1.0, 3.0] {
+            let z = C::from_angle(Rad(theta));
+            let mut product = C::new_from(1.0, 0.0);
+            for n in 1..=6 {
+                product *= z;
+                let expected = C::new_from((theta * n as f64).cos(), (theta * n as f64).sin());
+                assert_close(product, expected);
+                assert_close(z.powf(n as f64), expected);
+                assert_close(z.powc(C::new_from(n as f64, 0.0)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn polar_round_trip() {
+        for z in samples() {
+            let (r, theta) = z.to_polar();
+            assert_eq!(theta, z.arg());
+            assert!(theta.0 > -PI && theta.0 <= PI);
+            assert_close(C::from_polar(r, theta), z);
+        }
+        assert_eq!(C::new_from(0.0, 1.0).arg(), Rad(PI / 2.0));
+    }
+
+    #[test]
+    fn zero_powers() {
+        let zero = C::new();
+        let one = C::new_from(1.0, 0.0);
+        assert_close(zero.powf(0.0), one);
+        assert_close(zero.powc(C::new()), one);
+        assert_close(zero.powf(2.5), zero);
+        assert_close(zero.powc(C::new_from(2.0, 1.0)), zero);
+        assert_close(zero.sqrt(), zero);
+
+        let infinite = |z: C| z.x == f64::INFINITY && z.y == 0.0;
+        assert!(infinite(zero.powf(-1.0)));
+        assert!(infinite(zero.powf(-0.5)));
+        assert!(infinite(zero.powc(C::new_from(-1.0, 0.0))));
+        assert!(infinite(zero.powc(C::new_from(-2.0, 3.0))));
+        let nan = |z: C| z.x.is_nan() && z.y.is_nan();
+        assert!(zero.powf(f64::NAN).x.is_nan());
+        assert!(nan(zero.powc(C::new_from(0.0, 1.0))));
+    }
+
+    #[test]
+    fn trig_identities() {
+        for z in samples().into_iter().map(|z| z * 0.3) {
+            let (s, c) = (z.sin(), z.cos());
+            assert_close(s * s + c * c, C::new_from(1.0, 0.0));
+            assert_close(z.tan(), s / c);
+            let (sh, ch) = (z.sinh(), z.cosh());
+            assert_close(ch * ch - sh * sh, C::new_from(1.0, 0.0));
+            assert_close(z.tanh(), sh / ch);
+        }
+    }
+}
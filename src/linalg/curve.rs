@@ -0,0 +1,807 @@
+/*
+    Implementation of parametric curves.
+    Every curve is parameterized over t in [0, 1]. Splines are evaluated
+    segment by segment, each segment taking an equal share of the parameter range.
+    `ArcLength` wraps any curve and reparameterizes it by distance for constant-speed motion.
+*/
+
+use super::*;
+use num_traits::Float;
+use std::ops::{AddAssign, DivAssign};
+
+fn c<T: Float>(x: f64) -> T {
+    T::from(x).unwrap()
+}
+
+pub trait Curve<T, const L: usize>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    fn point(&self, t: T) -> TVec<T, L>;
+
+    /// First derivative with respect to `t`.
+    fn derivative(&self, t: T) -> TVec<T, L>;
+
+    /// Second derivative with respect to `t`.
+    fn second_derivative(&self, t: T) -> TVec<T, L>;
+
+    /// Length between parameters `a` and `b`, by Gauss-Legendre quadrature of the speed.
+    fn length_between(&self, a: T, b: T) -> T {
+        const NODES: [(f64, f64); 5] = [
+            (0.0, 0.568_888_888_888_888_9),
+            (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+            (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+            (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+            (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+        ];
+        let half = (b - a) * c::<T>(0.5);
+        let mid = (a + b) * c::<T>(0.5);
+        NODES.iter().fold(T::zero(), |sum, &(x, w)| {
+            sum + self.derivative(mid + half * c(x)).length() * c(w)
+        }) * half
+    }
+
+    fn length(&self) -> T {
+        let steps = 16;
+        let step = T::one() / T::from(steps).unwrap();
+        (0..steps).fold(T::zero(), |sum, i| {
+            let a = step * T::from(i).unwrap();
+            sum + self.length_between(a, a + step)
+        })
+    }
+}
+
+/// Component-wise `(min, max)` over a set of points.
+fn bounds<T, const L: usize>(points: impl IntoIterator<Item = TVec<T, L>>) -> (TVec<T, L>, TVec<T, L>)
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    let mut min = TVec::from([[T::infinity(); L]]);
+    let mut max = TVec::from([[T::neg_infinity(); L]]);
+    for p in points {
+        min = min.min(&p);
+        max = max.max(&p);
+    }
+    (min, max)
+}
+
+/// Roots of `a t² + b t + c` inside (0, 1).
+fn unit_quadratic_roots<T: Float>(a: T, b: T, c: T) -> [Option<T>; 2] {
+    let inside = |t: T| if t > T::zero() && t < T::one() { Some(t) } else { None };
+    if a.abs() <= T::epsilon() {
+        if b.abs() <= T::epsilon() {
+            return [None, None];
+        }
+        return [inside(-c / b), None];
+    }
+    let discriminant = b * b - (a + a) * (c + c);
+    if discriminant < T::zero() {
+        return [None, None];
+    }
+    let root = discriminant.sqrt();
+    [inside((-b + root) / (a + a)), inside((-b - root) / (a + a))]
+}
+
+/// Maps a global parameter onto `(segment, local t)`, `None` if there are no segments.
+fn locate<T: Float>(t: T, segments: usize) -> Option<(usize, T)> {
+    if segments == 0 {
+        return None;
+    }
+    let count = T::from(segments).unwrap();
+    let s = t.max(T::zero()).min(T::one()) * count;
+    let i = s.floor().to_usize().unwrap().min(segments - 1);
+    Some((i, s - T::from(i).unwrap()))
+}
+
+// Quadratic Bézier
+
+#[derive(Debug, Clone, Copy)]
+pub struct QuadraticBezier<T, const L: usize> {
+    pub p0: TVec<T, L>,
+    pub p1: TVec<T, L>,
+    pub p2: TVec<T, L>,
+}
+
+impl<T, const L: usize> QuadraticBezier<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    pub fn new(p0: TVec<T, L>, p1: TVec<T, L>, p2: TVec<T, L>) -> Self {
+        Self { p0, p1, p2 }
+    }
+
+    /// De Casteljau subdivision at `t`.
+    pub fn split(&self, t: T) -> (Self, Self) {
+        let a = self.p0.lerp(&self.p1, t);
+        let b = self.p1.lerp(&self.p2, t);
+        let m = a.lerp(&b, t);
+        (Self::new(self.p0, a, m), Self::new(m, b, self.p2))
+    }
+
+    /// Exact bounding box as `(min, max)`.
+    pub fn bounding_box(&self) -> (TVec<T, L>, TVec<T, L>) {
+        let extrema = (0..L).filter_map(|i| {
+            let denom = self.p0[0][i] - self.p1[0][i] * c::<T>(2.0) + self.p2[0][i];
+            if denom.abs() <= T::epsilon() {
+                return None;
+            }
+            let t = (self.p0[0][i] - self.p1[0][i]) / denom;
+            (t > T::zero() && t < T::one()).then(|| self.point(t))
+        });
+        bounds([self.p0, self.p2].into_iter().chain(extrema))
+    }
+
+    /// The same curve as a cubic.
+    pub fn elevate(&self) -> CubicBezier<T, L> {
+        let third = T::one() / c::<T>(3.0);
+        CubicBezier::new(
+            self.p0,
+            self.p0 + (self.p1 - self.p0) * (third + third),
+            self.p2 + (self.p1 - self.p2) * (third + third),
+            self.p2,
+        )
+    }
+}
+
+impl<T, const L: usize> Curve<T, L> for QuadraticBezier<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    fn point(&self, t: T) -> TVec<T, L> {
+        let mt = T::one() - t;
+        self.p0 * (mt * mt) + self.p1 * (mt * t * c::<T>(2.0)) + self.p2 * (t * t)
+    }
+
+    fn derivative(&self, t: T) -> TVec<T, L> {
+        ((self.p1 - self.p0) * (T::one() - t) + (self.p2 - self.p1) * t) * c::<T>(2.0)
+    }
+
+    fn second_derivative(&self, _t: T) -> TVec<T, L> {
+        (self.p2 - self.p1 * c::<T>(2.0) + self.p0) * c::<T>(2.0)
+    }
+}
+
+// Cubic Bézier
+
+#[derive(Debug, Clone, Copy)]
+pub struct CubicBezier<T, const L: usize> {
+    pub p0: TVec<T, L>,
+    pub p1: TVec<T, L>,
+    pub p2: TVec<T, L>,
+    pub p3: TVec<T, L>,
+}
+
+impl<T, const L: usize> CubicBezier<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    pub fn new(p0: TVec<T, L>, p1: TVec<T, L>, p2: TVec<T, L>, p3: TVec<T, L>) -> Self {
+        Self { p0, p1, p2, p3 }
+    }
+
+    /// De Casteljau subdivision at `t`.
+    pub fn split(&self, t: T) -> (Self, Self) {
+        let a = self.p0.lerp(&self.p1, t);
+        let b = self.p1.lerp(&self.p2, t);
+        let c = self.p2.lerp(&self.p3, t);
+        let ab = a.lerp(&b, t);
+        let bc = b.lerp(&c, t);
+        let m = ab.lerp(&bc, t);
+        (Self::new(self.p0, a, ab, m), Self::new(m, bc, c, self.p3))
+    }
+
+    /// Exact bounding box as `(min, max)`.
+    pub fn bounding_box(&self) -> (TVec<T, L>, TVec<T, L>) {
+        let extrema = (0..L).flat_map(|i| {
+            let a = self.p1[0][i] - self.p0[0][i];
+            let b = self.p2[0][i] - self.p1[0][i];
+            let c = self.p3[0][i] - self.p2[0][i];
+            unit_quadratic_roots(a - b - b + c, (b - a) * (T::one() + T::one()), a)
+        });
+        let extrema: Vec<_> = extrema.flatten().map(|t| self.point(t)).collect();
+        bounds([self.p0, self.p3].into_iter().chain(extrema))
+    }
+}
+
+impl<T, const L: usize> Curve<T, L> for CubicBezier<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    fn point(&self, t: T) -> TVec<T, L> {
+        let mt = T::one() - t;
+        self.p0 * (mt * mt * mt)
+            + self.p1 * (mt * mt * t * c::<T>(3.0))
+            + self.p2 * (mt * t * t * c::<T>(3.0))
+            + self.p3 * (t * t * t)
+    }
+
+    fn derivative(&self, t: T) -> TVec<T, L> {
+        let mt = T::one() - t;
+        ((self.p1 - self.p0) * (mt * mt)
+            + (self.p2 - self.p1) * (mt * t * c::<T>(2.0))
+            + (self.p3 - self.p2) * (t * t))
+            * c::<T>(3.0)
+    }
+
+    fn second_derivative(&self, t: T) -> TVec<T, L> {
+        let two = c::<T>(2.0);
+        ((self.p2 - self.p1 * two + self.p0) * (T::one() - t) + (self.p3 - self.p2 * two + self.p1) * t)
+            * c::<T>(6.0)
+    }
+}
+
+// Hermite
+
+/// Cubic between `p0` and `p1` leaving with tangent `m0` and arriving with tangent `m1`.
+#[derive(Debug, Clone, Copy)]
+pub struct Hermite<T, const L: usize> {
+    pub p0: TVec<T, L>,
+    pub m0: TVec<T, L>,
+    pub p1: TVec<T, L>,
+    pub m1: TVec<T, L>,
+}
+
+impl<T, const L: usize> Hermite<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    pub fn new(p0: TVec<T, L>, m0: TVec<T, L>, p1: TVec<T, L>, m1: TVec<T, L>) -> Self {
+        Self { p0, m0, p1, m1 }
+    }
+
+    pub fn to_bezier(self) -> CubicBezier<T, L> {
+        let third = T::one() / c::<T>(3.0);
+        CubicBezier::new(self.p0, self.p0 + self.m0 * third, self.p1 - self.m1 * third, self.p1)
+    }
+
+    pub fn split(&self, t: T) -> (CubicBezier<T, L>, CubicBezier<T, L>) {
+        self.to_bezier().split(t)
+    }
+
+    pub fn bounding_box(&self) -> (TVec<T, L>, TVec<T, L>) {
+        self.to_bezier().bounding_box()
+    }
+}
+
+impl<T, const L: usize> Curve<T, L> for Hermite<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    fn point(&self, t: T) -> TVec<T, L> {
+        self.to_bezier().point(t)
+    }
+
+    fn derivative(&self, t: T) -> TVec<T, L> {
+        self.to_bezier().derivative(t)
+    }
+
+    fn second_derivative(&self, t: T) -> TVec<T, L> {
+        self.to_bezier().second_derivative(t)
+    }
+}
+
+// Bézier Path
+
+/// Chain of cubic Bézier segments, the common form all splines convert to.\
+/// An empty path stays at the origin.
+#[derive(Debug, Clone)]
+pub struct BezierPath<T, const L: usize> {
+    pub segments: Vec<CubicBezier<T, L>>,
+}
+
+impl<T, const L: usize> BezierPath<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    pub fn new(segments: Vec<CubicBezier<T, L>>) -> Self {
+        Self { segments }
+    }
+
+    /// Splits at the global parameter `t`, each half covering its own [0, 1] range.
+    pub fn split(&self, t: T) -> (Self, Self) {
+        let Some((i, local)) = locate(t, self.segments.len()) else {
+            return (self.clone(), self.clone());
+        };
+        let (a, b) = self.segments[i].split(local);
+        let mut head = self.segments[..i].to_vec();
+        head.push(a);
+        let mut tail = vec![b];
+        tail.extend_from_slice(&self.segments[i + 1..]);
+        (Self::new(head), Self::new(tail))
+    }
+
+    pub fn bounding_box(&self) -> (TVec<T, L>, TVec<T, L>) {
+        bounds(self.segments.iter().flat_map(|s| {
+            let (min, max) = s.bounding_box();
+            [min, max]
+        }))
+    }
+}
+
+impl<T, const L: usize> Curve<T, L> for BezierPath<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    fn point(&self, t: T) -> TVec<T, L> {
+        let Some((i, local)) = locate(t, self.segments.len()) else {
+            return TVec::default();
+        };
+        self.segments[i].point(local)
+    }
+
+    fn derivative(&self, t: T) -> TVec<T, L> {
+        let Some((i, local)) = locate(t, self.segments.len()) else {
+            return TVec::default();
+        };
+        self.segments[i].derivative(local) * T::from(self.segments.len()).unwrap()
+    }
+
+    fn second_derivative(&self, t: T) -> TVec<T, L> {
+        let Some((i, local)) = locate(t, self.segments.len()) else {
+            return TVec::default();
+        };
+        let n = T::from(self.segments.len()).unwrap();
+        self.segments[i].second_derivative(local) * (n * n)
+    }
+}
+
+// Catmull-Rom
+
+/// Interpolating spline through every point of `points`.\
+/// `alpha` selects the knot spacing: 0 is uniform, 0.5 centripetal, 1 chordal.
+/// Centripetal splines never form cusps or self-intersections within a segment.\
+/// The end tangents are taken from mirrored phantom points.\
+/// With fewer than two points the curve stays at the first point, or at the origin when empty.
+#[derive(Debug, Clone)]
+pub struct CatmullRom<T, const L: usize> {
+    pub points: Vec<TVec<T, L>>,
+    pub alpha: T,
+}
+
+impl<T, const L: usize> CatmullRom<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    pub fn new(points: Vec<TVec<T, L>>, alpha: T) -> Self {
+        Self { points, alpha }
+    }
+
+    pub fn uniform(points: Vec<TVec<T, L>>) -> Self {
+        Self::new(points, T::zero())
+    }
+
+    pub fn centripetal(points: Vec<TVec<T, L>>) -> Self {
+        Self::new(points, c::<T>(0.5))
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.points.len().saturating_sub(1)
+    }
+
+    /// Segment `i`, running from `points[i]` to `points[i + 1]`.
+    pub fn segment(&self, i: usize) -> Hermite<T, L> {
+        let n = self.points.len();
+        let p1 = self.points[i];
+        let p2 = self.points[i + 1];
+        let p0 = if i > 0 { self.points[i - 1] } else { p1 * c::<T>(2.0) - p2 };
+        let p3 = if i + 2 < n { self.points[i + 2] } else { p2 * c::<T>(2.0) - p1 };
+
+        // Knot intervals, coincident points fall back to uniform spacing.
+        let interval = |a: TVec<T, L>, b: TVec<T, L>| {
+            let d = a.distance_to(&b).powf(self.alpha);
+            if d > T::epsilon() {
+                d
+            } else {
+                T::one()
+            }
+        };
+        let (d0, d1, d2) = (interval(p0, p1), interval(p1, p2), interval(p2, p3));
+
+        let m1 = ((p1 - p0) / d0 - (p2 - p0) / (d0 + d1) + (p2 - p1) / d1) * d1;
+        let m2 = ((p2 - p1) / d1 - (p3 - p1) / (d1 + d2) + (p3 - p2) / d2) * d1;
+        Hermite::new(p1, m1, p2, m2)
+    }
+
+    pub fn to_path(&self) -> BezierPath<T, L> {
+        BezierPath::new((0..self.segment_count()).map(|i| self.segment(i).to_bezier()).collect())
+    }
+
+    pub fn split(&self, t: T) -> (BezierPath<T, L>, BezierPath<T, L>) {
+        self.to_path().split(t)
+    }
+
+    pub fn bounding_box(&self) -> (TVec<T, L>, TVec<T, L>) {
+        self.to_path().bounding_box()
+    }
+}
+
+impl<T, const L: usize> Curve<T, L> for CatmullRom<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    fn point(&self, t: T) -> TVec<T, L> {
+        let Some((i, local)) = locate(t, self.segment_count()) else {
+            return self.points.first().copied().unwrap_or_default();
+        };
+        self.segment(i).point(local)
+    }
+
+    fn derivative(&self, t: T) -> TVec<T, L> {
+        let Some((i, local)) = locate(t, self.segment_count()) else {
+            return TVec::default();
+        };
+        self.segment(i).derivative(local) * T::from(self.segment_count()).unwrap()
+    }
+
+    fn second_derivative(&self, t: T) -> TVec<T, L> {
+        let Some((i, local)) = locate(t, self.segment_count()) else {
+            return TVec::default();
+        };
+        let n = T::from(self.segment_count()).unwrap();
+        self.segment(i).second_derivative(local) * (n * n)
+    }
+}
+
+// B-Spline
+
+/// Uniform cubic B-spline. Approximates the control points with C2 continuity,
+/// passing through none of them in general.\
+/// With fewer than four points the curve stays at the first point, or at the origin when empty.
+#[derive(Debug, Clone)]
+pub struct BSpline<T, const L: usize> {
+    pub points: Vec<TVec<T, L>>,
+}
+
+impl<T, const L: usize> BSpline<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    pub fn new(points: Vec<TVec<T, L>>) -> Self {
+        Self { points }
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.points.len().saturating_sub(3)
+    }
+
+    /// Segment `i`, controlled by `points[i..i + 4]`.
+    pub fn segment(&self, i: usize) -> CubicBezier<T, L> {
+        let [p0, p1, p2, p3] = [0, 1, 2, 3].map(|k| self.points[i + k]);
+        let sixth = T::one() / c::<T>(6.0);
+        let third = T::one() / c::<T>(3.0);
+        CubicBezier::new(
+            (p0 + p1 * c::<T>(4.0) + p2) * sixth,
+            (p1 * c::<T>(2.0) + p2) * third,
+            (p1 + p2 * c::<T>(2.0)) * third,
+            (p1 + p2 * c::<T>(4.0) + p3) * sixth,
+        )
+    }
+
+    pub fn to_path(&self) -> BezierPath<T, L> {
+        BezierPath::new((0..self.segment_count()).map(|i| self.segment(i)).collect())
+    }
+
+    pub fn split(&self, t: T) -> (BezierPath<T, L>, BezierPath<T, L>) {
+        self.to_path().split(t)
+    }
+
+    pub fn bounding_box(&self) -> (TVec<T, L>, TVec<T, L>) {
+        self.to_path().bounding_box()
+    }
+}
+
+impl<T, const L: usize> Curve<T, L> for BSpline<T, L>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    fn point(&self, t: T) -> TVec<T, L> {
+        let Some((i, local)) = locate(t, self.segment_count()) else {
+            return self.points.first().copied().unwrap_or_default();
+        };
+        self.segment(i).point(local)
+    }
+
+    fn derivative(&self, t: T) -> TVec<T, L> {
+        let Some((i, local)) = locate(t, self.segment_count()) else {
+            return TVec::default();
+        };
+        self.segment(i).derivative(local) * T::from(self.segment_count()).unwrap()
+    }
+
+    fn second_derivative(&self, t: T) -> TVec<T, L> {
+        let Some((i, local)) = locate(t, self.segment_count()) else {
+            return TVec::default();
+        };
+        let n = T::from(self.segment_count()).unwrap();
+        self.segment(i).second_derivative(local) * (n * n)
+    }
+}
+
+// Arc Length
+
+/// Reparameterizes `curve` by normalized arc length,
+/// so that `point(u)` moves at constant speed as `u` goes from 0 to 1.
+#[derive(Debug, Clone)]
+pub struct ArcLength<T, C> {
+    pub curve: C,
+    params: Vec<T>,
+    lengths: Vec<T>,
+}
+
+impl<T, C> ArcLength<T, C>
+where
+    T: Float + Default + AddAssign + DivAssign,
+{
+    /// Tabulates the length of `curve` over `samples` intervals.
+    pub fn new<const L: usize>(curve: C, samples: usize) -> Self
+    where
+        C: Curve<T, L>,
+    {
+        let samples = samples.max(1);
+        let step = T::one() / T::from(samples).unwrap();
+        let params: Vec<T> = (0..=samples).map(|i| T::from(i).unwrap() * step).collect();
+        let mut lengths = Vec::with_capacity(params.len());
+        let mut total = T::zero();
+        lengths.push(total);
+        for pair in params.windows(2) {
+            total += curve.length_between(pair[0], pair[1]);
+            lengths.push(total);
+        }
+        Self { curve, params, lengths }
+    }
+
+    pub fn total_length(&self) -> T {
+        *self.lengths.last().unwrap()
+    }
+
+    /// Curve parameter at `distance` along the curve, refined with one Newton step.
+    pub fn parameter<const L: usize>(&self, distance: T) -> T
+    where
+        C: Curve<T, L>,
+    {
+        let distance = distance.max(T::zero()).min(self.total_length());
+        let i = self.lengths.partition_point(|&l| l < distance).clamp(1, self.lengths.len() - 1);
+        let (l0, l1) = (self.lengths[i - 1], self.lengths[i]);
+        let (t0, t1) = (self.params[i - 1], self.params[i]);
+        let f = if l1 > l0 { (distance - l0) / (l1 - l0) } else { T::zero() };
+        let t = t0 + (t1 - t0) * f;
+
+        let speed = self.curve.derivative(t).length();
+        if speed <= T::epsilon() {
+            return t;
+        }
+        let error = l0 + self.curve.length_between(t0, t) - distance;
+        (t - error / speed).max(t0).min(t1)
+    }
+}
+
+impl<T, C, const L: usize> Curve<T, L> for ArcLength<T, C>
+where
+    T: Float + Default + AddAssign + DivAssign,
+    C: Curve<T, L>,
+{
+    fn point(&self, u: T) -> TVec<T, L> {
+        self.curve.point(self.parameter::<L>(u * self.total_length()))
+    }
+
+    fn derivative(&self, u: T) -> TVec<T, L> {
+        let d = self.curve.derivative(self.parameter::<L>(u * self.total_length()));
+        let speed = d.length();
+        if speed <= T::epsilon() {
+            return d;
+        }
+        d * (self.total_length() / speed)
+    }
+
+    fn second_derivative(&self, u: T) -> TVec<T, L> {
+        let t = self.parameter::<L>(u * self.total_length());
+        let d1 = self.curve.derivative(t);
+        let d2 = self.curve.second_derivative(t);
+        let speed_squared = d1.length_squared();
+        if speed_squared <= T::epsilon() {
+            return d2;
+        }
+        // Only the component of d2 normal to the motion survives at constant speed.
+        let length = self.total_length();
+        (d2 - d1 * (d1.dot(&d2) / speed_squared)) * (length * length / speed_squared)
+    }
+
+    fn length(&self) -> T {
+        self.total_length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec2(x: f64, y: f64) -> Vec2d {
+        Vec2d::from([[x, y]])
+    }
+
+    fn assert_near(a: Vec2d, b: Vec2d, tolerance: f64) {
+        assert!((a - b).length() <= tolerance * (1.0 + b.length()), "{a:?} != {b:?}");
+    }
+
+    fn points() -> Vec<Vec2d> {
+        vec![vec2(0.0, 0.0), vec2(1.0, 3.0), vec2(4.0, 3.5), vec2(5.0, -1.0), vec2(8.0, 0.5)]
+    }
+
+    fn cubic() -> CubicBezier<f64, 2> {
+        CubicBezier::new(vec2(0.0, 0.0), vec2(0.5, 3.0), vec2(3.0, -3.0), vec2(4.0, 1.0))
+    }
+
+    fn quadratic() -> QuadraticBezier<f64, 2> {
+        QuadraticBezier::new(vec2(0.0, 0.0), vec2(2.0, 4.0), vec2(-1.0, 1.0))
+    }
+
+    /// Avoids the joins at multiples of 1/2, 1/3 and 1/4 where splines only have one-sided derivatives.
+    const INTERIOR: [f64; 6] = [0.05, 0.2, 0.3, 0.45, 0.7, 0.95];
+
+    fn assert_derivatives<C: Curve<f64, 2>>(curve: &C) {
+        let h = 1e-5;
+        for t in INTERIOR {
+            let d = (curve.point(t + h) - curve.point(t - h)) / (2.0 * h);
+            assert_near(curve.derivative(t), d, 1e-6);
+            let dd = (curve.derivative(t + h) - curve.derivative(t - h)) / (2.0 * h);
+            assert_near(curve.second_derivative(t), dd, 1e-6);
+        }
+    }
+
+    fn assert_constant<C: Curve<f64, 2>>(curve: &C, at: Vec2d) {
+        for t in [-1.0, 0.0, 0.5, 1.0, 2.0] {
+            assert_eq!(curve.point(t), at);
+            assert_eq!(curve.derivative(t), Vec2d::default());
+            assert_eq!(curve.second_derivative(t), Vec2d::default());
+        }
+    }
+
+    #[test]
+    fn too_few_points_stay_at_the_first_point() {
+        let points = [vec2(1.0, 2.0), vec2(3.0, 4.0), vec2(5.0, 0.0)];
+        assert_constant(&CatmullRom::uniform(Vec::new()), Vec2d::default());
+        assert_constant(&CatmullRom::centripetal(points[..1].to_vec()), points[0]);
+        assert_constant(&BSpline::new(Vec::new()), Vec2d::default());
+        for n in 1..4 {
+            assert_constant(&BSpline::new(points[..n].to_vec()), points[0]);
+        }
+        assert_constant(&BezierPath::new(Vec::new()), Vec2d::default());
+    }
+
+    #[test]
+    fn minimal_splines_evaluate() {
+        let points = vec![vec2(0.0, 0.0), vec2(1.0, 2.0), vec2(3.0, 2.0), vec2(4.0, 0.0)];
+        let spline = CatmullRom::uniform(points[..2].to_vec());
+        assert_eq!(spline.point(0.0), points[0]);
+        assert_eq!(spline.point(1.0), points[1]);
+        let bspline = BSpline::new(points);
+        assert!((bspline.point(0.0) - vec2(7.0, 10.0) / 6.0).length() < 1e-12);
+    }
+
+    #[test]
+    fn empty_path_splits_into_empty_halves() {
+        let (head, tail) = BezierPath::<f64, 2>::new(Vec::new()).split(0.5);
+        assert!(head.segments.is_empty() && tail.segments.is_empty());
+    }
+
+    #[test]
+    fn derivatives_match_finite_differences() {
+        assert_derivatives(&quadratic());
+        assert_derivatives(&cubic());
+        assert_derivatives(&Hermite::new(vec2(0.0, 1.0), vec2(3.0, 0.0), vec2(2.0, 2.0), vec2(-1.0, 4.0)));
+        assert_derivatives(&CatmullRom::uniform(points()));
+        assert_derivatives(&CatmullRom::centripetal(points()));
+        assert_derivatives(&CatmullRom::new(points(), 1.0));
+        assert_derivatives(&CatmullRom::centripetal(points()).to_path());
+        assert_derivatives(&BSpline::new(points()));
+    }
+
+    #[test]
+    fn arc_length_moves_at_constant_speed() {
+        let curve = ArcLength::new(cubic(), 64);
+        assert!((curve.total_length() - cubic().length()).abs() < 1e-9);
+        let n = 40;
+        let chord = |i: usize| curve.point(i as f64 / n as f64).distance_to(&curve.point((i + 1) as f64 / n as f64));
+        let expected = curve.total_length() / n as f64;
+        for i in 0..n {
+            assert!((chord(i) / expected - 1.0).abs() < 0.01, "{i}: {} vs {expected}", chord(i));
+        }
+        for t in INTERIOR {
+            assert!((curve.derivative(t).length() / curve.total_length() - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(curve.point(0.0), cubic().p0);
+        assert_near(curve.point(1.0), cubic().p3, 1e-12);
+
+        // The raw parameterization is far from uniform for this curve.
+        let raw = cubic();
+        let speeds = INTERIOR.map(|t| raw.derivative(t).length());
+        assert!(speeds.iter().cloned().fold(0.0, f64::max) > 2.0 * speeds.iter().cloned().fold(f64::MAX, f64::min));
+    }
+
+    #[test]
+    fn split_halves_reproduce_the_curve() {
+        let at = 0.3;
+        let (head, tail) = cubic().split(at);
+        for s in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            assert_near(head.point(s), cubic().point(at * s), 1e-12);
+            assert_near(tail.point(s), cubic().point(at + (1.0 - at) * s), 1e-12);
+        }
+        let (head, tail) = quadratic().split(at);
+        for s in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            assert_near(head.point(s), quadratic().point(at * s), 1e-12);
+            assert_near(tail.point(s), quadratic().point(at + (1.0 - at) * s), 1e-12);
+        }
+
+        // Four segments, 0.6 falls at 0.4 into the third.
+        let path = CatmullRom::centripetal(points()).to_path();
+        let (head, tail) = path.split(0.6);
+        assert_eq!((head.segments.len(), tail.segments.len()), (3, 2));
+        assert_near(head.point(1.0), path.point(0.6), 1e-12);
+        assert_near(tail.point(0.0), path.point(0.6), 1e-12);
+        for s in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            for i in 0..2 {
+                assert_near(head.segments[i].point(s), path.segments[i].point(s), 0.0);
+            }
+            assert_near(head.segments[2].point(s), path.segments[2].point(0.4 * s), 1e-12);
+            assert_near(tail.segments[0].point(s), path.segments[2].point(0.4 + 0.6 * s), 1e-12);
+            assert_near(tail.segments[1].point(s), path.segments[3].point(s), 0.0);
+        }
+    }
+
+    #[test]
+    fn bounding_box_is_tight() {
+        fn check<C: Curve<f64, 2>>(curve: &C, (min, max): (Vec2d, Vec2d)) {
+            let samples: Vec<Vec2d> = (0..=2000).map(|i| curve.point(i as f64 / 2000.0)).collect();
+            for p in &samples {
+                assert!(p.x() >= min.x() - 1e-12 && p.y() >= min.y() - 1e-12, "{p:?} below {min:?}");
+                assert!(p.x() <= max.x() + 1e-12 && p.y() <= max.y() + 1e-12, "{p:?} above {max:?}");
+            }
+            let touches = |bound: f64, axis: usize| samples.iter().any(|p| (p[0][axis] - bound).abs() < 1e-4);
+            for axis in 0..2 {
+                assert!(touches(min[0][axis], axis) && touches(max[0][axis], axis), "{min:?} {max:?}");
+            }
+        }
+        // Each of these has extrema strictly inside the parameter range.
+        check(&quadratic(), quadratic().bounding_box());
+        check(&cubic(), cubic().bounding_box());
+        let spline = CatmullRom::centripetal(points());
+        check(&spline, spline.bounding_box());
+        let bspline = BSpline::new(points());
+        check(&bspline, bspline.bounding_box());
+        assert!(cubic().bounding_box().0.y() < cubic().p0.y().min(cubic().p3.y()));
+        assert!(quadratic().bounding_box().1.x() > quadratic().p2.x());
+    }
+
+    #[test]
+    fn zero_alpha_is_uniform_catmull_rom() {
+        let points = points();
+        let spline = CatmullRom::new(points.clone(), 0.0);
+        let n = points.len();
+        // Ends use phantom points mirrored through the first and last point.
+        let at = |i: isize| match i {
+            -1 => points[0] * 2.0 - points[1],
+            i if i as usize == n => points[n - 1] * 2.0 - points[n - 2],
+            i => points[i as usize],
+        };
+        for i in 0..spline.segment_count() {
+            let [p0, p1, p2, p3] = [-1, 0, 1, 2].map(|k| at(i as isize + k));
+            let segment = spline.segment(i);
+            assert_near(segment.m0, (p2 - p0) * 0.5, 1e-12);
+            assert_near(segment.m1, (p3 - p1) * 0.5, 1e-12);
+            for t in [0.0, 0.3, 0.5, 0.8, 1.0] {
+                let (t2, t3) = (t * t, t * t * t);
+                let expected = (p1 * 2.0
+                    + (p2 - p0) * t
+                    + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+                    + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+                    * 0.5;
+                assert_near(segment.point(t), expected, 1e-12);
+            }
+        }
+        for (i, &p) in points.iter().enumerate() {
+            let t = i as f64 / (n - 1) as f64;
+            assert_near(spline.point(t), p, 1e-12);
+            assert_near(CatmullRom::centripetal(points.clone()).point(t), p, 1e-12);
+        }
+    }
+}
@@ -0,0 +1,412 @@
+/*
+    Implementation of matrix decompositions.
+    QR by Householder reflections, symmetric eigen-decomposition by cyclic Jacobi rotations
+    and SVD by one-sided Jacobi rotations.
+*/
+
+use super::*;
+use num_traits::Float;
+use std::ops::AddAssign;
+
+const MAX_SWEEPS: usize = 64;
+
+fn column_dot<T: Float, const R: usize>(a: &[T; R], b: &[T; R]) -> T {
+    a.iter().zip(b).fold(T::zero(), |sum, (&x, &y)| sum + x * y)
+}
+
+/// Rotates the pair of columns `(p, q)` in place by the angle with cosine `c` and sine `s`.
+fn rotate_columns<T: Float, const R: usize, const C: usize>(
+    m: &mut [[T; R]; C],
+    p: usize,
+    q: usize,
+    c: T,
+    s: T,
+) {
+    let (mut column_p, mut column_q) = (m[p], m[q]);
+    for (mp, mq) in column_p.iter_mut().zip(column_q.iter_mut()) {
+        (*mp, *mq) = (c * *mp - s * *mq, s * *mp + c * *mq);
+    }
+    (m[p], m[q]) = (column_p, column_q);
+}
+
+/// Tangent of the smaller rotation angle solving `t² + 2ζt - 1 = 0`.
+fn jacobi_tangent<T: Float>(zeta: T) -> T {
+    let t = T::one() / (zeta.abs() + (T::one() + zeta * zeta).sqrt());
+    if zeta < T::zero() {
+        -t
+    } else {
+        t
+    }
+}
+
+// QR
+
+impl<T, const R: usize, const C: usize> TMat<T, R, C>
+where
+    T: Default + Copy + Float,
+{
+    /// Householder QR. Returns `(q, r)` with `q` orthogonal, `r` upper triangular
+    /// and `self == q * r`.
+    pub fn qr(&self) -> (TMat<T, R, R>, TMat<T, R, C>) {
+        let mut q = TMat::<T, R, R>::default();
+        for i in 0..R {
+            q[i][i] = T::one();
+        }
+        let mut r = *self;
+        let two = T::one() + T::one();
+
+        for k in 0..R.saturating_sub(1).min(C) {
+            let mut v = [T::zero(); R];
+            v[k..].copy_from_slice(&r[k][k..]);
+            let norm = column_dot(&v, &v).sqrt();
+            if norm == T::zero() {
+                continue;
+            }
+            let alpha = if v[k] > T::zero() { -norm } else { norm };
+            v[k] = v[k] - alpha;
+            let scale = two / column_dot(&v, &v);
+
+            for column in r.data.iter_mut().skip(k) {
+                let s = column_dot(column, &v) * scale;
+                for (e, &vi) in column.iter_mut().zip(&v).skip(k) {
+                    *e = *e - s * vi;
+                }
+            }
+            r[k][k] = alpha;
+            for e in r[k].iter_mut().skip(k + 1) {
+                *e = T::zero();
+            }
+
+            // q = q * H, where H = I - scale * v * vᵀ.
+            for i in 0..R {
+                let mut s = T::zero();
+                for l in k..R {
+                    s = s + q[l][i] * v[l];
+                }
+                let s = s * scale;
+                for l in k..R {
+                    q[l][i] = q[l][i] - s * v[l];
+                }
+            }
+        }
+        (q, r)
+    }
+}
+
+// Eigen & SVD
+
+impl<T, const N: usize> TMat<T, N, N>
+where
+    T: Default + Copy + Float + AddAssign,
+{
+    /// Eigen-decomposition of a symmetric matrix by cyclic Jacobi rotations.\
+    /// Returns `(values, vectors)` sorted by descending eigenvalue, the eigenvectors
+    /// being the columns of `vectors`, so that `self == vectors * diag(values) * vectorsᵀ`.\
+    /// Only the symmetric part of `self` is taken into account.
+    pub fn symmetric_eigen(&self) -> (TVec<T, N>, Self) {
+        let half = T::one() / (T::one() + T::one());
+        let mut a = *self;
+        for p in 0..N {
+            for q in (p + 1)..N {
+                let e = (a[p][q] + a[q][p]) * half;
+                a[p][q] = e;
+                a[q][p] = e;
+            }
+        }
+        let mut v = Self::identity_n();
+        let scale = a.data.iter().flatten().fold(T::zero(), |sum, &e| sum + e * e);
+        let threshold = T::epsilon() * T::epsilon() * scale;
+
+        for _ in 0..MAX_SWEEPS {
+            let mut off = T::zero();
+            for p in 0..N {
+                for q in (p + 1)..N {
+                    off += a[p][q] * a[p][q];
+                }
+            }
+            if off <= threshold {
+                break;
+            }
+            for p in 0..N {
+                for q in (p + 1)..N {
+                    if a[p][q] == T::zero() {
+                        continue;
+                    }
+                    let zeta = (a[q][q] - a[p][p]) / (a[p][q] + a[p][q]);
+                    let t = jacobi_tangent(zeta);
+                    let c = T::one() / (T::one() + t * t).sqrt();
+                    let s = t * c;
+
+                    // a = Jᵀ * a * J, applied to columns then rows.
+                    rotate_columns(&mut a.data, p, q, c, s);
+                    for column in a.data.iter_mut() {
+                        let (ap, aq) = (column[p], column[q]);
+                        column[p] = c * ap - s * aq;
+                        column[q] = s * ap + c * aq;
+                    }
+                    a[p][q] = T::zero();
+                    a[q][p] = T::zero();
+                    rotate_columns(&mut v.data, p, q, c, s);
+                }
+            }
+        }
+
+        let mut order: [usize; N] = std::array::from_fn(|i| i);
+        order.sort_by(|&i, &j| a[j][j].partial_cmp(&a[i][i]).unwrap_or(std::cmp::Ordering::Equal));
+        let values = TVec::from([order.map(|i| a[i][i])]);
+        let vectors = Self::from(order.map(|i| v[i]));
+        (values, vectors)
+    }
+
+    /// Singular value decomposition by one-sided Jacobi rotations.\
+    /// Returns `(u, sigma, v)` with `u` and `v` orthogonal and `sigma` non-negative
+    /// in descending order, so that `self == u * diag(sigma) * vᵀ`.
+    pub fn svd(&self) -> (Self, TVec<T, N>, Self) {
+        let mut u = *self;
+        let mut v = Self::identity_n();
+
+        for _ in 0..MAX_SWEEPS {
+            let mut rotated = false;
+            for p in 0..N {
+                for q in (p + 1)..N {
+                    let alpha = column_dot(&u[p], &u[p]);
+                    let beta = column_dot(&u[q], &u[q]);
+                    let gamma = column_dot(&u[p], &u[q]);
+                    if gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+                    let zeta = (beta - alpha) / (gamma + gamma);
+                    let t = jacobi_tangent(zeta);
+                    let c = T::one() / (T::one() + t * t).sqrt();
+                    let s = t * c;
+                    rotate_columns(&mut u.data, p, q, c, s);
+                    rotate_columns(&mut v.data, p, q, c, s);
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+
+        let norms = u.data.map(|column| column_dot(&column, &column).sqrt());
+        let mut order: [usize; N] = std::array::from_fn(|i| i);
+        order.sort_by(|&i, &j| norms[j].partial_cmp(&norms[i]).unwrap_or(std::cmp::Ordering::Equal));
+        let sigma = order.map(|i| norms[i]);
+        let mut u = Self::from(order.map(|i| u[i]));
+        let v = Self::from(order.map(|i| v[i]));
+
+        // Columns belonging to vanishing singular values carry no direction,
+        // complete them to an orthonormal basis instead.
+        let tolerance = T::epsilon() * T::from(N).unwrap() * sigma.first().copied().unwrap_or(T::zero());
+        let mut filled = [false; N];
+        for i in 0..N {
+            if sigma[i] > tolerance {
+                let inv = T::one() / sigma[i];
+                u[i] = u[i].map(|e| e * inv);
+                filled[i] = true;
+            }
+        }
+        for i in 0..N {
+            if filled[i] {
+                continue;
+            }
+            let mut best = ([T::zero(); N], T::zero());
+            for axis in 0..N {
+                let mut w = [T::zero(); N];
+                w[axis] = T::one();
+                for k in (0..N).filter(|&k| filled[k]) {
+                    let d = column_dot(&w, &u[k]);
+                    for (e, &uk) in w.iter_mut().zip(&u[k]) {
+                        *e = *e - d * uk;
+                    }
+                }
+                let length = column_dot(&w, &w).sqrt();
+                if length > best.1 {
+                    best = (w, length);
+                }
+            }
+            let inv = T::one() / best.1;
+            u[i] = best.0.map(|e| e * inv);
+            filled[i] = true;
+        }
+
+        (u, TVec::from([sigma]), v)
+    }
+
+    /// Nearest rotation matrix in the Frobenius norm, `u * vᵀ` from the SVD.\
+    /// Use it to remove the drift accumulated by repeatedly multiplied rotations.
+    pub fn orthonormalize(&self) -> Self {
+        let (mut u, _, v) = self.svd();
+        let mut r = u * v.transpose();
+        if N > 0 && r.determinant() < T::zero() {
+            // Flip the axis of the smallest singular value to turn a reflection into a rotation.
+            u[N - 1] = u[N - 1].map(|e| -e);
+            r = u * v.transpose();
+        }
+        r
+    }
+
+    fn identity_n() -> Self {
+        let mut m = Self::default();
+        for i in 0..N {
+            m[i][i] = T::one();
+        }
+        m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn random<const R: usize, const C: usize>(rng: &mut StdRng) -> TMat<f64, R, C> {
+        let mut m = TMat::default();
+        for column in m.data.iter_mut() {
+            for e in column.iter_mut() {
+                *e = rng.gen_range(-3.0..3.0);
+            }
+        }
+        m
+    }
+
+    fn diagonal<const N: usize>(values: &TVec<f64, N>) -> TMat<f64, N, N> {
+        let mut m = TMat::default();
+        for i in 0..N {
+            m[i][i] = values[0][i];
+        }
+        m
+    }
+
+    fn assert_close<const R: usize, const C: usize>(a: &TMat<f64, R, C>, b: &TMat<f64, R, C>) {
+        for i in 0..C {
+            for j in 0..R {
+                assert!((a[i][j] - b[i][j]).abs() < 1e-9, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    fn assert_orthonormal<const N: usize>(q: &TMat<f64, N, N>) {
+        assert_close(&(q.transpose() * *q), &TMat::identity_n());
+    }
+
+    fn fixed<const N: usize>() -> Vec<TMat<f64, N, N>> {
+        let mut hilbert = TMat::default();
+        let mut pivoting = TMat::<f64, N, N>::identity_n();
+        for i in 0..N {
+            for j in 0..N {
+                hilbert[i][j] = 1.0 / (i + j + 1) as f64;
+            }
+            // Zero diagonal, so LU has to pivot.
+            pivoting[i][i] = 0.0;
+            pivoting[i][(i + 1) % N] = 1.0 + i as f64;
+        }
+        vec![TMat::identity_n(), hilbert, pivoting]
+    }
+
+    /// `lu` factors the rows of the `data` array, so `L * U == P * data`.
+    fn check_lu<const N: usize>(a: &TMat<f64, N, N>) {
+        let (lu, perm, _) = a.lu().unwrap();
+        for i in 0..N {
+            for j in 0..N {
+                let product = (0..N)
+                    .filter(|&k| k <= i && k <= j)
+                    .map(|k| if k == i { lu[k][j] } else { lu[i][k] * lu[k][j] })
+                    .sum::<f64>();
+                assert!((product - a.data[perm[i]][j]).abs() < 1e-9);
+            }
+        }
+    }
+
+    fn check_qr<const R: usize, const C: usize>(a: &TMat<f64, R, C>) {
+        let (q, r) = a.qr();
+        assert_close(&(q * r), a);
+        assert_orthonormal(&q);
+        for (col, column) in r.data.iter().enumerate() {
+            for &e in column.iter().skip(col + 1) {
+                assert!(e.abs() < 1e-12);
+            }
+        }
+    }
+
+    fn check_eigen<const N: usize>(a: &TMat<f64, N, N>) {
+        let symmetric = *a + a.transpose();
+        let (values, vectors) = symmetric.symmetric_eigen();
+        assert_close(&(vectors * diagonal(&values) * vectors.transpose()), &symmetric);
+        assert_orthonormal(&vectors);
+        for i in 1..N {
+            assert!(values[0][i - 1] >= values[0][i]);
+        }
+    }
+
+    fn check_svd<const N: usize>(a: &TMat<f64, N, N>) {
+        let (u, sigma, v) = a.svd();
+        assert_close(&(u * diagonal(&sigma) * v.transpose()), a);
+        assert_orthonormal(&u);
+        assert_orthonormal(&v);
+        for i in 1..N {
+            assert!(sigma[0][i - 1] >= sigma[0][i] && sigma[0][i] >= 0.0);
+        }
+    }
+
+    fn check_all<const N: usize>(rng: &mut StdRng) {
+        let matrices = fixed::<N>().into_iter().chain((0..50).map(|_| random::<N, N>(rng)));
+        for a in matrices {
+            check_lu(&a);
+            check_qr(&a);
+            check_eigen(&a);
+            check_svd(&a);
+        }
+    }
+
+    #[test]
+    fn reconstruction() {
+        let mut rng = StdRng::seed_from_u64(5);
+        check_all::<2>(&mut rng);
+        check_all::<3>(&mut rng);
+        check_all::<4>(&mut rng);
+        check_all::<6>(&mut rng);
+    }
+
+    #[test]
+    fn rectangular_qr() {
+        let mut rng = StdRng::seed_from_u64(6);
+        check_qr(&random::<5, 3>(&mut rng));
+        check_qr(&random::<2, 4>(&mut rng));
+    }
+
+    #[test]
+    fn known_eigenvalues() {
+        let (values, _) = Mat3d::from([[2.0, 1.0, 0.0], [1.0, 2.0, 0.0], [0.0, 0.0, 1.0]]).symmetric_eigen();
+        assert_close(&values, &Vec3d::from([[3.0, 1.0, 1.0]]));
+    }
+
+    #[test]
+    fn rank_deficient_svd() {
+        let a = Mat3d::from([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 0.0, 0.0]]);
+        check_svd(&a);
+        let (u, sigma, v) = Mat3d::default().svd();
+        assert_orthonormal(&u);
+        assert_orthonormal(&v);
+        assert_eq!(sigma, Vec3d::default());
+    }
+
+    #[test]
+    fn orthonormalize_removes_drift() {
+        let rotation = Mat3d::from([[0.0, 1.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+        let mut drifted = rotation;
+        drifted[0][0] += 0.01;
+        drifted[1][2] -= 0.02;
+        let o = drifted.orthonormalize();
+        assert_orthonormal(&o);
+        assert!((o.determinant() - 1.0).abs() < 1e-9);
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((o[i][j] - rotation[i][j]).abs() < 0.03);
+            }
+        }
+        let reflection = Mat3d::from([[-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+        assert!((reflection.orthonormalize().determinant() - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,355 @@
+/*
+    Implementation of dual quaternion.
+    σ = r + εd, ε² = 0
+
+    Unit dual quaternions encode rigid transforms, the real part being the rotation
+    and the dual part `t * r / 2` with `t` the translation as a pure quaternion.
+    Blending them with `dlb` keeps volume where blended matrices collapse,
+    which is why skinning prefers them over linear blend skinning.
+*/
+
+use super::*;
+use quaternion::Quaternion;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DualQuaternion<T> {
+    pub real: Quaternion<T>,
+    pub dual: Quaternion<T>,
+}
+
+impl<T> DualQuaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    pub fn new_from(real: Quaternion<T>, dual: Quaternion<T>) -> Self {
+        Self { real, dual }
+    }
+
+    pub fn identity() -> Self {
+        Self::new_from(Quaternion::identity(), Quaternion::new_from(T::zero(), T::zero(), T::zero(), T::zero()))
+    }
+
+    /// Rotates first, then translates. `rotation` must be a unit quaternion.
+    pub fn from_rotation_translation(rotation: Quaternion<T>, translation: Vec3<T>) -> Self {
+        let half = T::one() / (T::one() + T::one());
+        let t = Quaternion::new_from(T::zero(), translation.x(), translation.y(), translation.z());
+        Self::new_from(rotation, t * rotation * half)
+    }
+
+    pub fn from_rotation(rotation: Quaternion<T>) -> Self {
+        Self::from_rotation_translation(rotation, Vec3::default())
+    }
+
+    pub fn from_translation(translation: Vec3<T>) -> Self {
+        Self::from_rotation_translation(Quaternion::identity(), translation)
+    }
+
+    pub fn rotation(&self) -> Quaternion<T> {
+        self.real
+    }
+
+    /// Expects a unit dual quaternion.
+    pub fn translation(&self) -> Vec3<T> {
+        let t = self.dual * self.real.conjugate() * (T::one() + T::one());
+        Vec3::from([[t.b, t.c, t.d]])
+    }
+
+    /// Quaternion conjugate of both parts, `r* + εd*`.
+    pub fn conjugate(&self) -> Self {
+        Self::new_from(self.real.conjugate(), self.dual.conjugate())
+    }
+
+    /// Dual number conjugate, `r - εd`.
+    pub fn dual_conjugate(&self) -> Self {
+        Self::new_from(self.real, -self.dual)
+    }
+
+    /// Both conjugates combined, `r* - εd*`. Transforms points as `σ p σ̄` with `p = 1 + εp`.
+    pub fn combined_conjugate(&self) -> Self {
+        Self::new_from(self.real.conjugate(), -self.dual.conjugate())
+    }
+
+    /// Equals `conjugate` for unit dual quaternions.
+    pub fn inverse(&self) -> Self {
+        let real = self.real.inverse();
+        Self::new_from(real, -(real * self.dual * real))
+    }
+
+    /// Real part of the dual norm, the dual part vanishes for unit dual quaternions.
+    pub fn norm(&self) -> T {
+        self.real.abs()
+    }
+
+    /// Scales to a unit real part and removes the component of the dual part
+    /// that breaks `r · d = 0`, so the result is a rigid transform again.
+    pub fn normalize(&self) -> Self {
+        let inv = T::one() / self.norm();
+        let real = self.real * inv;
+        let dual = self.dual * inv;
+        Self::new_from(real, dual - real * real.dot(&dual))
+    }
+
+    /// Expects a unit dual quaternion.
+    pub fn transform_point(&self, point: Vec3<T>) -> Vec3<T> {
+        self.real.rotate_vec3(point) + self.translation()
+    }
+
+    /// Ignores translation.
+    pub fn transform_vector(&self, vector: Vec3<T>) -> Vec3<T> {
+        self.real.rotate_vec3(vector)
+    }
+
+    /// Raises a unit dual quaternion to the power `t` by scaling its screw motion.
+    pub fn powf(&self, t: T) -> Self {
+        let (r, d) = (self.real, self.dual);
+        let s = (r.b * r.b + r.c * r.c + r.d * r.d).sqrt();
+        if s <= T::epsilon() {
+            // Pure translation, the screw axis is undefined.
+            // `-σ` is the same transform, flip it so the real part scales from the identity.
+            let sign = r.a.signum();
+            return Self::new_from(r * sign, d * (t * sign));
+        }
+        // r = (cos θ/2, sin θ/2 l), d = (-δ/2 sin θ/2, δ/2 cos θ/2 l + sin θ/2 m)
+        // with angle θ, pitch δ, axis direction l and moment m.
+        let half_angle = s.atan2(r.a);
+        let half_pitch = -d.a / s;
+        let l = [r.b / s, r.c / s, r.d / s];
+        let m = [
+            (d.b - l[0] * half_pitch * r.a) / s,
+            (d.c - l[1] * half_pitch * r.a) / s,
+            (d.d - l[2] * half_pitch * r.a) / s,
+        ];
+
+        let (sin, cos) = (half_angle * t).sin_cos();
+        let half_pitch = half_pitch * t;
+        let dual = |i: usize| half_pitch * cos * l[i] + sin * m[i];
+        Self::new_from(
+            Quaternion::new_from(cos, sin * l[0], sin * l[1], sin * l[2]),
+            Quaternion::new_from(-half_pitch * sin, dual(0), dual(1), dual(2)),
+        )
+    }
+
+    /// Screw linear interpolation of unit dual quaternions along the shortest path,
+    /// constant speed in both rotation and translation.
+    pub fn sclerp(&self, other: &Self, t: T) -> Self {
+        let end = if self.real.dot(&other.real) < T::zero() { -*other } else { *other };
+        *self * (self.conjugate() * end).powf(t)
+    }
+
+    /// Dual quaternion linear blending of `(transform, weight)` pairs, as used for skinning.\
+    /// Inputs are flipped into the hemisphere of the first one before summing.
+    /// Returns the identity for no inputs or a zero total weight.
+    pub fn dlb(pairs: &[(Self, T)]) -> Self {
+        let Some(&(pivot, _)) = pairs.first() else {
+            return Self::identity();
+        };
+        let zero = Quaternion::new_from(T::zero(), T::zero(), T::zero(), T::zero());
+        let mut sum = Self::new_from(zero, zero);
+        for &(dq, weight) in pairs {
+            let weight = if pivot.real.dot(&dq.real) < T::zero() { -weight } else { weight };
+            sum = sum + dq * weight;
+        }
+        if sum.norm() <= T::epsilon() {
+            return Self::identity();
+        }
+        sum.normalize()
+    }
+
+    /// Expects a rigid transform, the upper-left 3x3 block being a pure rotation.
+    pub fn from_mat4(m: &Mat4<T>) -> Self {
+        let translation = Vec3::from([[m[3][0], m[3][1], m[3][2]]]);
+        Self::from_rotation_translation(Quaternion::from_mat4(m), translation)
+    }
+
+    /// Expects a unit dual quaternion.
+    pub fn to_mat4(self) -> Mat4<T> {
+        let mut m = self.real.to_mat4();
+        let t = self.translation();
+        m[3][..3].copy_from_slice(&t.data[0]);
+        m
+    }
+}
+
+impl<T> Default for DualQuaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T> std::ops::Add for DualQuaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new_from(self.real + rhs.real, self.dual + rhs.dual)
+    }
+}
+
+impl<T> std::ops::Sub for DualQuaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new_from(self.real - rhs.real, self.dual - rhs.dual)
+    }
+}
+
+impl<T> std::ops::Neg for DualQuaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::new_from(-self.real, -self.dual)
+    }
+}
+
+impl<T> std::ops::Mul<T> for DualQuaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new_from(self.real * rhs, self.dual * rhs)
+    }
+}
+
+/// Composition, `a * b` applies `b` first like matrix products.
+impl<T> std::ops::Mul for DualQuaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new_from(self.real * rhs.real, self.real * rhs.dual + self.dual * rhs.real)
+    }
+}
+
+impl<T> std::ops::MulAssign for DualQuaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T> From<DualQuaternion<T>> for Mat4<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    fn from(value: DualQuaternion<T>) -> Self {
+        value.to_mat4()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::random;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    type DQ = DualQuaternion<f32>;
+
+    fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+        Vec3f::from([[x, y, z]])
+    }
+
+    fn rigid(rng: &mut StdRng) -> DQ {
+        let translation = vec3(rng.gen_range(-5.0..5.0), rng.gen_range(-5.0..5.0), rng.gen_range(-5.0..5.0));
+        DQ::from_rotation_translation(random::rotation(rng), translation)
+    }
+
+    fn components(dq: &DQ) -> [f32; 8] {
+        let (r, d) = (dq.real, dq.dual);
+        [r.a, r.b, r.c, r.d, d.a, d.b, d.c, d.d]
+    }
+
+    /// `σ` and `-σ` are the same transform.
+    fn assert_same(a: &DQ, b: &DQ) {
+        let (a, b) = (components(a), components(b));
+        let close = |sign: f32| a.iter().zip(&b).all(|(x, y)| (x - sign * y).abs() < 1e-4);
+        assert!(close(1.0) || close(-1.0), "{:?} != {:?}", a, b);
+    }
+
+    fn assert_vec3_eq(a: Vec3f, b: Vec3f) {
+        assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn inverse_composes_to_identity() {
+        let mut rng = StdRng::seed_from_u64(25);
+        for _ in 0..100 {
+            let a = rigid(&mut rng);
+            assert_same(&(a * a.inverse()), &DQ::identity());
+            assert_same(&(a.inverse() * a), &DQ::identity());
+            assert_same(&a.inverse(), &a.conjugate());
+        }
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        let mut rng = StdRng::seed_from_u64(25);
+        for _ in 0..100 {
+            let a = rigid(&mut rng);
+            assert_same(&DQ::from_mat4(&a.to_mat4()), &a);
+        }
+    }
+
+    #[test]
+    fn transform_point_matches_matrix() {
+        let mut rng = StdRng::seed_from_u64(25);
+        for _ in 0..100 {
+            let (a, b) = (rigid(&mut rng), rigid(&mut rng));
+            let p = vec3(rng.gen_range(-3.0..3.0), rng.gen_range(-3.0..3.0), rng.gen_range(-3.0..3.0));
+            assert_vec3_eq(a.transform_point(p), (a.to_mat4() * p.extend(1.0)).truncate());
+            assert_vec3_eq(a.transform_vector(p), (a.to_mat4() * p.extend(0.0)).truncate());
+            // Composition applies the right operand first, like matrices.
+            assert_vec3_eq((a * b).transform_point(p), a.transform_point(b.transform_point(p)));
+        }
+    }
+
+    #[test]
+    fn sclerp_endpoints() {
+        let mut rng = StdRng::seed_from_u64(25);
+        for _ in 0..100 {
+            let (a, b) = (rigid(&mut rng), rigid(&mut rng));
+            assert_same(&a.sclerp(&b, 0.0), &a);
+            assert_same(&a.sclerp(&b, 1.0), &b);
+            assert_same(&a.sclerp(&-b, 1.0), &b);
+        }
+        // Pure translations have no screw axis.
+        let (a, b) = (DQ::from_translation(vec3(1.0, 2.0, 3.0)), DQ::from_translation(vec3(-3.0, 0.0, 1.0)));
+        assert_same(&a.sclerp(&b, 0.5), &DQ::from_translation(vec3(-1.0, 1.0, 2.0)));
+    }
+
+    #[test]
+    fn powf_of_negated_translation() {
+        let translation = vec3(2.0, -4.0, 6.0);
+        for dq in [DQ::from_translation(translation), -DQ::from_translation(translation)] {
+            let half = dq.powf(0.5);
+            assert!(half.real.a > 0.0);
+            assert_vec3_eq(half.translation(), translation * 0.5);
+            assert_same(&dq.powf(0.0), &DQ::identity());
+            assert_vec3_eq(dq.powf(1.0).translation(), translation);
+        }
+    }
+
+    #[test]
+    fn dlb_antipodal_inputs() {
+        let mut rng = StdRng::seed_from_u64(25);
+        for _ in 0..100 {
+            let a = rigid(&mut rng);
+            // `-a` is the same transform, blending it with `a` must not cancel out.
+            let blended = DQ::dlb(&[(a, 0.5), (-a, 0.5)]);
+            assert_same(&blended, &a);
+            assert_same(&DQ::dlb(&[(-a, 0.3), (a, 0.7)]), &a);
+        }
+        assert_same(&DQ::dlb(&[]), &DQ::identity());
+    }
+}
@@ -0,0 +1,390 @@
+/*
+    Implementation of the fast Fourier transform.
+    Power-of-two lengths use an iterative radix-2 transform,
+    any other length goes through Bluestein's chirp-z algorithm.
+
+    The forward transform is X[k] = Σ x[j] e^(-2πi jk/n), unnormalized.
+    The inverse transform is scaled by 1/n, so that ifft(fft(x)) == x.
+*/
+
+use super::*;
+use angle::Rad;
+use complex::Complex;
+use num_traits::Float;
+
+/// In-place forward transform of any length.
+pub fn fft<T>(data: &mut [Complex<T>])
+where
+    T: Default + Copy + Float,
+{
+    transform(data, false);
+}
+
+/// In-place inverse transform of any length, scaled by 1/n.
+pub fn ifft<T>(data: &mut [Complex<T>])
+where
+    T: Default + Copy + Float,
+{
+    transform(data, true);
+    let scale = T::one() / T::from(data.len()).unwrap();
+    for z in data.iter_mut() {
+        *z *= scale;
+    }
+}
+
+/// Forward transform of real samples.\
+/// Returns the `n / 2 + 1` non-negative frequency bins,
+/// the remaining ones being their complex conjugates. An empty input yields a single zero bin.
+pub fn rfft<T>(input: &[T]) -> Vec<Complex<T>>
+where
+    T: Default + Copy + Float,
+{
+    let n = input.len();
+    if n == 0 {
+        return vec![Complex::new_from(T::zero(), T::zero())];
+    }
+    if n % 2 == 1 || n < 2 {
+        let mut data: Vec<_> = input.iter().map(|&x| Complex::new_from(x, T::zero())).collect();
+        fft(&mut data);
+        data.truncate(n / 2 + 1);
+        return data;
+    }
+
+    // Pack even samples into the real and odd samples into the imaginary part,
+    // transform at half length and separate the two spectra.
+    let m = n / 2;
+    let mut packed: Vec<_> = input.chunks_exact(2).map(|p| Complex::new_from(p[0], p[1])).collect();
+    fft(&mut packed);
+
+    let half = T::one() / (T::one() + T::one());
+    let angle = -tau::<T>() / T::from(n).unwrap();
+    (0..=m)
+        .map(|k| {
+            let z = packed[k % m];
+            let mirror = packed[(m - k) % m].conjugate();
+            let even = (z + mirror) * half;
+            let odd = (z - mirror) * half;
+            // odd / i == odd * -i
+            let odd = Complex::new_from(odd.y, -odd.x);
+            even + Complex::from_angle(Rad(angle * T::from(k).unwrap())) * odd
+        })
+        .collect()
+}
+
+/// Inverse of `rfft`. `n` is the length of the original signal, `spectrum` must hold `n / 2 + 1` bins.
+pub fn irfft<T>(spectrum: &[Complex<T>], n: usize) -> Vec<T>
+where
+    T: Default + Copy + Float,
+{
+    assert_eq!(spectrum.len(), n / 2 + 1, "irfft expects n / 2 + 1 bins");
+    if n % 2 == 1 || n < 2 {
+        let mut data: Vec<_> = (0..n)
+            .map(|k| if k < spectrum.len() { spectrum[k] } else { spectrum[n - k].conjugate() })
+            .collect();
+        ifft(&mut data);
+        return data.iter().map(|z| z.x).collect();
+    }
+
+    let m = n / 2;
+    let half = T::one() / (T::one() + T::one());
+    let angle = tau::<T>() / T::from(n).unwrap();
+    let mut packed: Vec<_> = (0..m)
+        .map(|k| {
+            let x = spectrum[k];
+            let mirror = spectrum[m - k].conjugate();
+            let even = (x + mirror) * half;
+            let odd = (x - mirror) * half * Complex::from_angle(Rad(angle * T::from(k).unwrap()));
+            // even + i * odd
+            even + Complex::new_from(-odd.y, odd.x)
+        })
+        .collect();
+    ifft(&mut packed);
+    packed.iter().flat_map(|z| [z.x, z.y]).collect()
+}
+
+// Windows
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+}
+
+impl Window {
+    /// Periodic window of length `n`, the form suited for spectral analysis.
+    pub fn coefficients<T>(self, n: usize) -> Vec<T>
+    where
+        T: Float,
+    {
+        let step = tau::<T>() / T::from(n.max(1)).unwrap();
+        let c = |x: f64| T::from(x).unwrap();
+        (0..n)
+            .map(|i| {
+                let x = step * T::from(i).unwrap();
+                match self {
+                    Window::Rectangular => T::one(),
+                    Window::Hann => c(0.5) - c(0.5) * x.cos(),
+                    Window::Hamming => c(0.54) - c(0.46) * x.cos(),
+                    Window::Blackman => c(0.42) - c(0.5) * x.cos() + c(0.08) * (x + x).cos(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn apply<T>(self, samples: &mut [T])
+    where
+        T: Float,
+    {
+        let coefficients = self.coefficients::<T>(samples.len());
+        for (s, w) in samples.iter_mut().zip(coefficients) {
+            *s = *s * w;
+        }
+    }
+}
+
+// Spectrum
+
+/// Windowed power spectrum of real samples, `n / 2 + 1` bins.\
+/// Normalized by the squared window sum, so a sine of amplitude `a`
+/// centered on a bin reads `(a / 2)²` there.
+pub fn power_spectrum<T>(samples: &[T], window: Window) -> Vec<T>
+where
+    T: Default + Copy + Float,
+{
+    let coefficients = window.coefficients::<T>(samples.len());
+    let windowed: Vec<T> = samples.iter().zip(&coefficients).map(|(&s, &w)| s * w).collect();
+    let sum = coefficients.iter().fold(T::zero(), |sum, &w| sum + w);
+    let scale = if sum > T::zero() { T::one() / (sum * sum) } else { T::zero() };
+    rfft(&windowed)
+        .iter()
+        .map(|z| (z.x * z.x + z.y * z.y) * scale)
+        .collect()
+}
+
+/// Frequency in Hz of bin `k` for a transform of length `n`.
+pub fn bin_frequency<T>(k: usize, n: usize, sample_rate: T) -> T
+where
+    T: Float,
+{
+    T::from(k).unwrap() * sample_rate / T::from(n).unwrap()
+}
+
+// Transforms
+
+fn tau<T: Float>() -> T {
+    T::from(std::f64::consts::TAU).unwrap()
+}
+
+fn transform<T>(data: &mut [Complex<T>], inverse: bool)
+where
+    T: Default + Copy + Float,
+{
+    match data.len() {
+        0 | 1 => (),
+        n if n.is_power_of_two() => radix2(data, inverse),
+        _ => bluestein(data, inverse),
+    }
+}
+
+/// Iterative Cooley-Tukey, `data.len()` must be a power of two.
+fn radix2<T>(data: &mut [Complex<T>], inverse: bool)
+where
+    T: Default + Copy + Float,
+{
+    let n = data.len();
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { T::one() } else { -T::one() };
+    let step = sign * tau::<T>() / T::from(n).unwrap();
+    let twiddles: Vec<_> = (0..n / 2)
+        .map(|j| Complex::from_angle(Rad(step * T::from(j).unwrap())))
+        .collect();
+
+    let mut len = 2;
+    while len <= n {
+        let stride = n / len;
+        for block in data.chunks_exact_mut(len) {
+            let (lo, hi) = block.split_at_mut(len / 2);
+            for (j, (a, b)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                let t = *b * twiddles[j * stride];
+                *b = *a - t;
+                *a += t;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Bluestein's algorithm, expresses a transform of any length
+/// as a convolution evaluated with power-of-two transforms.
+fn bluestein<T>(data: &mut [Complex<T>], inverse: bool)
+where
+    T: Default + Copy + Float,
+{
+    let n = data.len();
+    let m = (2 * n - 1).next_power_of_two();
+    let sign = if inverse { T::one() } else { -T::one() };
+    let step = sign * T::from(std::f64::consts::PI).unwrap() / T::from(n).unwrap();
+    // k² is reduced modulo 2n to keep the angle small.
+    let chirp: Vec<_> = (0..n)
+        .map(|k| Complex::from_angle(Rad(step * T::from((k * k) % (2 * n)).unwrap())))
+        .collect();
+
+    let mut a = vec![Complex::new(); m];
+    for ((a, &x), &w) in a.iter_mut().zip(data.iter()).zip(&chirp) {
+        *a = x * w;
+    }
+    let mut b = vec![Complex::new(); m];
+    b[0] = chirp[0].conjugate();
+    for k in 1..n {
+        b[k] = chirp[k].conjugate();
+        b[m - k] = b[k];
+    }
+
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+    for (a, &b) in a.iter_mut().zip(&b) {
+        *a *= b;
+    }
+    radix2(&mut a, true);
+
+    let scale = T::one() / T::from(m).unwrap();
+    for ((x, &c), &w) in data.iter_mut().zip(&a).zip(&chirp) {
+        *x = c * w * scale;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type C = Complex<f64>;
+
+    /// O(n²) reference, `sign` is -1 for the forward and 1 for the unscaled inverse transform.
+    fn dft(x: &[C], sign: f64) -> Vec<C> {
+        let n = x.len();
+        (0..n)
+            .map(|k| {
+                x.iter().enumerate().fold(C::new(), |sum, (j, &v)| {
+                    let angle = sign * tau::<f64>() * ((j * k) % n) as f64 / n as f64;
+                    sum + v * C::from_angle(Rad(angle))
+                })
+            })
+            .collect()
+    }
+
+    fn signal(n: usize) -> Vec<C> {
+        (0..n)
+            .map(|i| C::new_from((i as f64 * 0.37).sin() + 0.1, (i as f64 * 1.3).cos()))
+            .collect()
+    }
+
+    fn assert_close(a: &[C], b: &[C], eps: f64) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            assert!((*x - *y).abs() < eps, "{:?} != {:?}", x, y);
+        }
+    }
+
+    fn check_against_dft(n: usize) {
+        let x = signal(n);
+        let eps = 1e-10 * (n as f64).max(1.0);
+
+        let mut forward = x.clone();
+        fft(&mut forward);
+        assert_close(&forward, &dft(&x, -1.0), eps);
+
+        let mut inverse = x.clone();
+        ifft(&mut inverse);
+        let expected: Vec<C> = dft(&x, 1.0).iter().map(|&z| z / n as f64).collect();
+        assert_close(&inverse, &expected, eps);
+
+        ifft(&mut forward);
+        assert_close(&forward, &x, eps);
+    }
+
+    #[test]
+    fn power_of_two_matches_dft() {
+        for n in [2, 4, 8, 16, 64, 256] {
+            check_against_dft(n);
+        }
+    }
+
+    #[test]
+    fn bluestein_matches_dft() {
+        for n in [1, 3, 5, 7, 12, 17, 30, 100] {
+            check_against_dft(n);
+        }
+    }
+
+    #[test]
+    fn bluestein_matches_radix2() {
+        let x = signal(32);
+        let (mut a, mut b) = (x.clone(), x);
+        radix2(&mut a, false);
+        bluestein(&mut b, false);
+        assert_close(&a, &b, 1e-9);
+    }
+
+    #[test]
+    fn empty_input() {
+        let mut data: Vec<C> = Vec::new();
+        fft(&mut data);
+        ifft(&mut data);
+        assert!(data.is_empty());
+
+        let spectrum = rfft::<f64>(&[]);
+        assert_close(&spectrum, &[C::new_from(0.0, 0.0)], 1e-12);
+        assert!(irfft(&spectrum, 0).is_empty());
+        assert_eq!(power_spectrum::<f64>(&[], Window::Hann), vec![0.0]);
+    }
+
+    #[test]
+    fn real_transform_round_trip() {
+        // Even lengths use the packed half-length transform, odd ones the full transform.
+        for n in [1, 2, 3, 4, 7, 8, 12, 17, 64, 100] {
+            let samples: Vec<f64> = signal(n).iter().map(|z| z.x).collect();
+            let complex: Vec<C> = samples.iter().map(|&s| C::new_from(s, 0.0)).collect();
+            let spectrum = rfft(&samples);
+            assert_eq!(spectrum.len(), n / 2 + 1);
+            assert_close(&spectrum, &dft(&complex, -1.0)[..n / 2 + 1], 1e-9 * n as f64);
+
+            let back = irfft(&spectrum, n);
+            assert_eq!(back.len(), n);
+            for (a, b) in back.iter().zip(&samples) {
+                assert!((a - b).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn power_spectrum_of_centered_sine() {
+        let n = 64;
+        let samples: Vec<f32> = (0..n)
+            .map(|i| 2.0 * (std::f32::consts::TAU * 8.0 * i as f32 / n as f32).sin())
+            .collect();
+        for window in [Window::Rectangular, Window::Hann, Window::Hamming, Window::Blackman] {
+            let power = power_spectrum(&samples, window);
+            assert_eq!(power.len(), n / 2 + 1);
+            assert!((power[8] - 1.0).abs() < 1e-4, "{:?} {}", window, power[8]);
+        }
+        assert!(power_spectrum(&samples, Window::Rectangular)[3] < 1e-8);
+    }
+
+    #[test]
+    fn window_coefficients() {
+        let hamming: Vec<f64> = Window::Hamming.coefficients(4);
+        assert!((hamming[0] - 0.08).abs() < 1e-12 && (hamming[2] - 1.0).abs() < 1e-12);
+        let blackman: Vec<f64> = Window::Blackman.coefficients(4);
+        assert!(blackman[0].abs() < 1e-12);
+        assert_eq!(bin_frequency(8, 64, 44100.0_f32), 5512.5);
+    }
+}
@@ -0,0 +1,883 @@
+/*
+    Implementation of geometric primitives and intersection tests.
+    Planes are stored as n·p + d = 0, with the normal pointing to the positive half-space.
+*/
+
+use super::*;
+use gfx::DepthRange;
+use num_traits::Float;
+use quaternion::Quaternion;
+
+fn half<T: Float>() -> T {
+    T::one() / (T::one() + T::one())
+}
+
+// Ray
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ray<T> {
+    pub origin: Vec3<T>,
+    pub direction: Vec3<T>,
+}
+
+impl<T> Ray<T>
+where
+    T: Float + Default + std::ops::AddAssign + std::ops::DivAssign,
+{
+    pub fn new(origin: Vec3<T>, direction: Vec3<T>) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn at(&self, t: T) -> Vec3<T> {
+        self.origin + self.direction * t
+    }
+
+    /// Parameter of the point on the ray closest to `point`, clamped to `t >= 0`.
+    pub fn closest_t(&self, point: Vec3<T>) -> T {
+        let t = (point - self.origin).dot(&self.direction) / self.direction.length_squared();
+        t.max(T::zero())
+    }
+
+    pub fn closest_point(&self, point: Vec3<T>) -> Vec3<T> {
+        self.at(self.closest_t(point))
+    }
+
+    pub fn intersect_plane(&self, plane: &Plane<T>) -> Option<T> {
+        let denom = plane.normal.dot(&self.direction);
+        if denom.abs() <= T::epsilon() {
+            return None;
+        }
+        let t = -plane.signed_distance(self.origin) / denom;
+        if t >= T::zero() {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    /// Möller–Trumbore. Returns `(t, u, v)` where `u` and `v` are the
+    /// barycentric weights of `triangle.b` and `triangle.c`. Hits both faces.
+    pub fn intersect_triangle(&self, triangle: &Triangle<T>) -> Option<(T, T, T)> {
+        let e1 = triangle.b - triangle.a;
+        let e2 = triangle.c - triangle.a;
+        let p = self.direction.cross(&e2);
+        let det = e1.dot(&p);
+        if det.abs() <= T::epsilon() {
+            return None;
+        }
+        let inv_det = T::one() / det;
+        let s = self.origin - triangle.a;
+        let u = s.dot(&p) * inv_det;
+        if u < T::zero() || u > T::one() {
+            return None;
+        }
+        let q = s.cross(&e1);
+        let v = self.direction.dot(&q) * inv_det;
+        if v < T::zero() || u + v > T::one() {
+            return None;
+        }
+        let t = e2.dot(&q) * inv_det;
+        if t >= T::zero() {
+            Some((t, u, v))
+        } else {
+            None
+        }
+    }
+
+    /// Slab test. Returns the entry and exit parameters, entry is clamped to `0`
+    /// when the origin lies inside the box.
+    pub fn intersect_aabb(&self, aabb: &Aabb<T>) -> Option<(T, T)> {
+        let mut t_min = T::zero();
+        let mut t_max = T::infinity();
+        for i in 0..3 {
+            let origin = self.origin[0][i];
+            let direction = self.direction[0][i];
+            let (lo, hi) = (aabb.min[0][i], aabb.max[0][i]);
+            if direction.abs() <= T::epsilon() {
+                if origin < lo || origin > hi {
+                    return None;
+                }
+                continue;
+            }
+            let inv = T::one() / direction;
+            let mut t0 = (lo - origin) * inv;
+            let mut t1 = (hi - origin) * inv;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some((t_min, t_max))
+    }
+
+    pub fn intersect_sphere(&self, sphere: &Sphere<T>) -> Option<T> {
+        let m = self.origin - sphere.center;
+        let a = self.direction.length_squared();
+        let b = m.dot(&self.direction);
+        let c = m.length_squared() - sphere.radius * sphere.radius;
+        if c > T::zero() && b > T::zero() {
+            return None;
+        }
+        let discriminant = b * b - a * c;
+        if discriminant < T::zero() {
+            return None;
+        }
+        let t = (-b - discriminant.sqrt()) / a;
+        Some(t.max(T::zero()))
+    }
+
+    /// Uses the box orientation to transform the ray into box space.
+    pub fn intersect_obb(&self, obb: &Obb<T>) -> Option<(T, T)> {
+        let local = Ray::new(obb.local_point(self.origin), obb.local_vector(self.direction));
+        local.intersect_aabb(&Aabb::new(-obb.half_extents, obb.half_extents))
+    }
+}
+
+// Plane
+
+#[derive(Debug, Clone, Copy)]
+pub struct Plane<T> {
+    pub normal: Vec3<T>,
+    pub d: T,
+}
+
+impl<T> Plane<T>
+where
+    T: Float + Default + std::ops::AddAssign + std::ops::DivAssign,
+{
+    pub fn new(normal: Vec3<T>, d: T) -> Self {
+        Self { normal, d }
+    }
+
+    pub fn from_point_normal(point: Vec3<T>, normal: Vec3<T>) -> Self {
+        let normal = normal.normalize();
+        Self {
+            normal,
+            d: -normal.dot(&point),
+        }
+    }
+
+    /// Counter-clockwise points face the normal.
+    pub fn from_points(a: Vec3<T>, b: Vec3<T>, c: Vec3<T>) -> Self {
+        Self::from_point_normal(a, (b - a).cross(&(c - a)))
+    }
+
+    /// Scales the equation so the normal has unit length.
+    pub fn normalize(&self) -> Self {
+        let length = self.normal.length();
+        Self {
+            normal: self.normal / length,
+            d: self.d / length,
+        }
+    }
+
+    /// Exact for normalized planes, scaled by the normal length otherwise.
+    pub fn signed_distance(&self, point: Vec3<T>) -> T {
+        self.normal.dot(&point) + self.d
+    }
+
+    pub fn closest_point(&self, point: Vec3<T>) -> Vec3<T> {
+        point - self.normal * (self.signed_distance(point) / self.normal.length_squared())
+    }
+}
+
+// Axis-aligned bounding box
+
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb<T> {
+    pub min: Vec3<T>,
+    pub max: Vec3<T>,
+}
+
+impl<T> Aabb<T>
+where
+    T: Float + Default + std::ops::AddAssign + std::ops::DivAssign,
+{
+    pub fn new(min: Vec3<T>, max: Vec3<T>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center_extents(center: Vec3<T>, half_extents: Vec3<T>) -> Self {
+        Self {
+            min: center - half_extents,
+            max: center + half_extents,
+        }
+    }
+
+    /// Returns `None` for an empty slice.
+    pub fn from_points(points: &[Vec3<T>]) -> Option<Self> {
+        let first = *points.first()?;
+        let mut aabb = Self::new(first, first);
+        for &p in &points[1..] {
+            aabb = aabb.expand(p);
+        }
+        Some(aabb)
+    }
+
+    pub fn center(&self) -> Vec3<T> {
+        (self.min + self.max) * half::<T>()
+    }
+
+    pub fn half_extents(&self) -> Vec3<T> {
+        (self.max - self.min) * half::<T>()
+    }
+
+    pub fn expand(&self, point: Vec3<T>) -> Self {
+        Self {
+            min: self.min.min(&point),
+            max: self.max.max(&point),
+        }
+    }
+
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(&other.min),
+            max: self.max.max(&other.max),
+        }
+    }
+
+    pub fn contains_point(&self, point: Vec3<T>) -> bool {
+        (0..3).all(|i| point[0][i] >= self.min[0][i] && point[0][i] <= self.max[0][i])
+    }
+
+    pub fn contains_aabb(&self, other: &Self) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    pub fn intersects_aabb(&self, other: &Self) -> bool {
+        (0..3).all(|i| self.min[0][i] <= other.max[0][i] && self.max[0][i] >= other.min[0][i])
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere<T>) -> bool {
+        let closest = self.closest_point(sphere.center);
+        (closest - sphere.center).length_squared() <= sphere.radius * sphere.radius
+    }
+
+    pub fn closest_point(&self, point: Vec3<T>) -> Vec3<T> {
+        self.min.max(&self.max.min(&point))
+    }
+
+    /// Bounding box of this box after an affine transform.
+    pub fn transform(&self, matrix: &Mat4<T>) -> Self {
+        let m = &matrix.data;
+        let mut min = Vec3::from([[m[3][0], m[3][1], m[3][2]]]);
+        let mut max = min;
+        for (col, column) in m.iter().take(3).enumerate() {
+            let a = Vec3::from([[column[0], column[1], column[2]]]) * self.min[0][col];
+            let b = Vec3::from([[column[0], column[1], column[2]]]) * self.max[0][col];
+            min += a.min(&b);
+            max += a.max(&b);
+        }
+        Self::new(min, max)
+    }
+}
+
+// Sphere
+
+#[derive(Debug, Clone, Copy)]
+pub struct Sphere<T> {
+    pub center: Vec3<T>,
+    pub radius: T,
+}
+
+impl<T> Sphere<T>
+where
+    T: Float + Default + std::ops::AddAssign + std::ops::DivAssign,
+{
+    pub fn new(center: Vec3<T>, radius: T) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains_point(&self, point: Vec3<T>) -> bool {
+        (point - self.center).length_squared() <= self.radius * self.radius
+    }
+
+    pub fn intersects_sphere(&self, other: &Self) -> bool {
+        let r = self.radius + other.radius;
+        (other.center - self.center).length_squared() <= r * r
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb<T>) -> bool {
+        aabb.intersects_sphere(self)
+    }
+
+    /// Returns the point itself when inside the sphere.
+    pub fn closest_point(&self, point: Vec3<T>) -> Vec3<T> {
+        let offset = point - self.center;
+        let distance = offset.length();
+        if distance <= self.radius {
+            point
+        } else {
+            self.center + offset * (self.radius / distance)
+        }
+    }
+}
+
+// Oriented bounding box
+
+#[derive(Debug, Clone, Copy)]
+pub struct Obb<T> {
+    pub center: Vec3<T>,
+    /// Orthonormal local axes.
+    pub axes: [Vec3<T>; 3],
+    pub half_extents: Vec3<T>,
+}
+
+impl<T> Obb<T>
+where
+    T: Float + Default + std::ops::AddAssign + std::ops::DivAssign,
+{
+    pub fn new(center: Vec3<T>, axes: [Vec3<T>; 3], half_extents: Vec3<T>) -> Self {
+        Self {
+            center,
+            axes,
+            half_extents,
+        }
+    }
+
+    pub fn from_rotation(center: Vec3<T>, rotation: Quaternion<T>, half_extents: Vec3<T>) -> Self {
+        let m = rotation.to_mat3();
+        Self {
+            center,
+            axes: [
+                Vec3::from([m[0]]),
+                Vec3::from([m[1]]),
+                Vec3::from([m[2]]),
+            ],
+            half_extents,
+        }
+    }
+
+    fn local_point(&self, point: Vec3<T>) -> Vec3<T> {
+        self.local_vector(point - self.center)
+    }
+
+    fn local_vector(&self, vector: Vec3<T>) -> Vec3<T> {
+        Vec3::from([[
+            vector.dot(&self.axes[0]),
+            vector.dot(&self.axes[1]),
+            vector.dot(&self.axes[2]),
+        ]])
+    }
+
+    pub fn contains_point(&self, point: Vec3<T>) -> bool {
+        let local = self.local_point(point);
+        (0..3).all(|i| local[0][i].abs() <= self.half_extents[0][i])
+    }
+
+    pub fn closest_point(&self, point: Vec3<T>) -> Vec3<T> {
+        let local = self.local_point(point);
+        let mut result = self.center;
+        for i in 0..3 {
+            let e = self.half_extents[0][i];
+            result += self.axes[i] * local[0][i].max(-e).min(e);
+        }
+        result
+    }
+
+    pub fn corners(&self) -> [Vec3<T>; 8] {
+        let mut corners = [self.center; 8];
+        for (n, corner) in corners.iter_mut().enumerate() {
+            for i in 0..3 {
+                let sign = if n & (1 << i) != 0 { T::one() } else { -T::one() };
+                *corner += self.axes[i] * (self.half_extents[0][i] * sign);
+            }
+        }
+        corners
+    }
+
+    pub fn bounding_aabb(&self) -> Aabb<T> {
+        let mut extents = Vec3::default();
+        for (axis, direction) in self.axes.iter().enumerate() {
+            let offset = *direction * self.half_extents[0][axis];
+            extents += offset.abs();
+        }
+        Aabb::from_center_extents(self.center, extents)
+    }
+}
+
+// Triangle
+
+#[derive(Debug, Clone, Copy)]
+pub struct Triangle<T> {
+    pub a: Vec3<T>,
+    pub b: Vec3<T>,
+    pub c: Vec3<T>,
+}
+
+impl<T> Triangle<T>
+where
+    T: Float + Default + std::ops::AddAssign + std::ops::DivAssign,
+{
+    pub fn new(a: Vec3<T>, b: Vec3<T>, c: Vec3<T>) -> Self {
+        Self { a, b, c }
+    }
+
+    /// Unit normal, counter-clockwise winding.
+    pub fn normal(&self) -> Vec3<T> {
+        (self.b - self.a).cross(&(self.c - self.a)).normalize()
+    }
+
+    pub fn area(&self) -> T {
+        (self.b - self.a).cross(&(self.c - self.a)).length() * half::<T>()
+    }
+
+    pub fn plane(&self) -> Plane<T> {
+        Plane::from_points(self.a, self.b, self.c)
+    }
+
+    /// Barycentric weights `(u, v, w)` of `point` projected onto the triangle plane,
+    /// such that `point ≈ a * u + b * v + c * w`.
+    pub fn barycentric(&self, point: Vec3<T>) -> Vec3<T> {
+        let v0 = self.b - self.a;
+        let v1 = self.c - self.a;
+        let v2 = point - self.a;
+        let d00 = v0.dot(&v0);
+        let d01 = v0.dot(&v1);
+        let d11 = v1.dot(&v1);
+        let d20 = v2.dot(&v0);
+        let d21 = v2.dot(&v1);
+        let denom = d00 * d11 - d01 * d01;
+        let v = (d11 * d20 - d01 * d21) / denom;
+        let w = (d00 * d21 - d01 * d20) / denom;
+        Vec3::from([[T::one() - v - w, v, w]])
+    }
+
+    pub fn contains_point(&self, point: Vec3<T>) -> bool {
+        let bary = self.barycentric(point);
+        bary.x() >= T::zero() && bary.y() >= T::zero() && bary.z() >= T::zero()
+    }
+
+    /// Closest point on the triangle, from Ericson's Real-Time Collision Detection.
+    pub fn closest_point(&self, point: Vec3<T>) -> Vec3<T> {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let ab = b - a;
+        let ac = c - a;
+
+        let ap = point - a;
+        let d1 = ab.dot(&ap);
+        let d2 = ac.dot(&ap);
+        if d1 <= T::zero() && d2 <= T::zero() {
+            return a;
+        }
+
+        let bp = point - b;
+        let d3 = ab.dot(&bp);
+        let d4 = ac.dot(&bp);
+        if d3 >= T::zero() && d4 <= d3 {
+            return b;
+        }
+
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= T::zero() && d1 >= T::zero() && d3 <= T::zero() {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        let cp = point - c;
+        let d5 = ab.dot(&cp);
+        let d6 = ac.dot(&cp);
+        if d6 >= T::zero() && d5 <= d6 {
+            return c;
+        }
+
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= T::zero() && d2 >= T::zero() && d6 <= T::zero() {
+            return a + ac * (d2 / (d2 - d6));
+        }
+
+        let va = d3 * d6 - d5 * d4;
+        if va <= T::zero() && (d4 - d3) >= T::zero() && (d5 - d6) >= T::zero() {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        let denom = T::one() / (va + vb + vc);
+        a + ab * (vb * denom) + ac * (vc * denom)
+    }
+}
+
+// Segment
+
+#[derive(Debug, Clone, Copy)]
+pub struct Segment<T> {
+    pub a: Vec3<T>,
+    pub b: Vec3<T>,
+}
+
+impl<T> Segment<T>
+where
+    T: Float + Default + std::ops::AddAssign + std::ops::DivAssign,
+{
+    pub fn new(a: Vec3<T>, b: Vec3<T>) -> Self {
+        Self { a, b }
+    }
+
+    pub fn length(&self) -> T {
+        (self.b - self.a).length()
+    }
+
+    pub fn at(&self, t: T) -> Vec3<T> {
+        self.a + (self.b - self.a) * t
+    }
+
+    /// Parameter in `[0, 1]` of the point closest to `point`.
+    pub fn closest_t(&self, point: Vec3<T>) -> T {
+        let ab = self.b - self.a;
+        let length_squared = ab.length_squared();
+        if length_squared <= T::epsilon() {
+            return T::zero();
+        }
+        ((point - self.a).dot(&ab) / length_squared).max(T::zero()).min(T::one())
+    }
+
+    pub fn closest_point(&self, point: Vec3<T>) -> Vec3<T> {
+        self.at(self.closest_t(point))
+    }
+
+    /// Closest points between two segments, as `(on_self, on_other)`.
+    pub fn closest_points(&self, other: &Self) -> (Vec3<T>, Vec3<T>) {
+        let d1 = self.b - self.a;
+        let d2 = other.b - other.a;
+        let r = self.a - other.a;
+        let a = d1.length_squared();
+        let e = d2.length_squared();
+        let f = d2.dot(&r);
+        let clamp = |x: T| x.max(T::zero()).min(T::one());
+
+        let (s, t);
+        if a <= T::epsilon() && e <= T::epsilon() {
+            return (self.a, other.a);
+        }
+        if a <= T::epsilon() {
+            s = T::zero();
+            t = clamp(f / e);
+        } else {
+            let c = d1.dot(&r);
+            if e <= T::epsilon() {
+                t = T::zero();
+                s = clamp(-c / a);
+            } else {
+                let b = d1.dot(&d2);
+                let denom = a * e - b * b;
+                let s0 = if denom != T::zero() {
+                    clamp((b * f - c * e) / denom)
+                } else {
+                    T::zero()
+                };
+                let t0 = (b * s0 + f) / e;
+                if t0 < T::zero() {
+                    t = T::zero();
+                    s = clamp(-c / a);
+                } else if t0 > T::one() {
+                    t = T::one();
+                    s = clamp((b - c) / a);
+                } else {
+                    t = t0;
+                    s = s0;
+                }
+            }
+        }
+        (self.at(s), other.at(t))
+    }
+}
+
+// Frustum
+
+/// Six inward-facing planes in the order left, right, bottom, top, near, far.
+#[derive(Debug, Clone, Copy)]
+pub struct Frustum<T> {
+    pub planes: [Plane<T>; 6],
+}
+
+impl<T> Frustum<T>
+where
+    T: Float + Default + std::ops::AddAssign + std::ops::DivAssign,
+{
+    /// Gribb–Hartmann extraction from a view-projection matrix
+    /// using the OpenGL `[-1, 1]` clip-space depth range.
+    pub fn from_matrix(view_projection: &Mat4<T>) -> Self {
+        Self::from_matrix_with(view_projection, DepthRange::OpenGl)
+    }
+
+    /// `depth` must match the projection.\
+    /// Without a far plane, as built by `perspective_infinite`, the far plane
+    /// degenerates to one with a zero normal that contains every point.
+    pub fn from_matrix_with(view_projection: &Mat4<T>, depth: DepthRange) -> Self {
+        let m = &view_projection.data;
+        let row = |r: usize| [m[0][r], m[1][r], m[2][r], m[3][r]];
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let plane = |a: [T; 4], b: [T; 4], sign: T| {
+            let normal = Vec3::from([[a[0] + b[0] * sign, a[1] + b[1] * sign, a[2] + b[2] * sign]]);
+            let d = a[3] + b[3] * sign;
+            // Rounding leaves a tiny normal where the rows cancel out.
+            let scale = (0..3).fold(T::zero(), |m, i| m.max(a[i].abs()).max(b[i].abs()));
+            let epsilon = T::epsilon() * T::from(16).unwrap();
+            if normal.length() <= epsilon * scale {
+                return Plane::new(Vec3::default(), d.signum());
+            }
+            Plane::new(normal, d).normalize()
+        };
+        let (pos, neg) = (T::one(), -T::one());
+        let zero = [T::zero(); 4];
+        // Near and far bound z_clip by -w, 0 or w depending on the range.
+        let (near, far) = match depth {
+            DepthRange::OpenGl => (plane(r3, r2, pos), plane(r3, r2, neg)),
+            DepthRange::ZeroToOne => (plane(zero, r2, pos), plane(r3, r2, neg)),
+            DepthRange::ReverseZ => (plane(r3, r2, neg), plane(zero, r2, pos)),
+        };
+        Self {
+            planes: [
+                plane(r3, r0, pos),
+                plane(r3, r0, neg),
+                plane(r3, r1, pos),
+                plane(r3, r1, neg),
+                near,
+                far,
+            ],
+        }
+    }
+
+    pub fn contains_point(&self, point: Vec3<T>) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= T::zero())
+    }
+
+    /// Conservative: may report spheres near frustum corners as intersecting.
+    pub fn intersects_sphere(&self, sphere: &Sphere<T>) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(sphere.center) >= -sphere.radius)
+    }
+
+    /// Conservative: may report boxes near frustum corners as intersecting.
+    pub fn intersects_aabb(&self, aabb: &Aabb<T>) -> bool {
+        self.planes.iter().all(|plane| {
+            let pick = |i: usize| {
+                if plane.normal[0][i] >= T::zero() {
+                    aabb.max[0][i]
+                } else {
+                    aabb.min[0][i]
+                }
+            };
+            plane.signed_distance(Vec3::from([[pick(0), pick(1), pick(2)]])) >= T::zero()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const DEPTHS: [DepthRange; 3] = [DepthRange::OpenGl, DepthRange::ZeroToOne, DepthRange::ReverseZ];
+
+    fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+        Vec3f::from([[x, y, z]])
+    }
+
+    fn assert_close(a: Vec3f, b: Vec3f) {
+        assert!((a - b).length() < 1e-5, "{a:?} != {b:?}");
+    }
+
+    fn unit_triangle() -> Triangle<f32> {
+        Triangle::new(vec3(0.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0))
+    }
+
+    /// Unit cube centered at `(1, 0, 0)`, turned 45 degrees around z.
+    fn turned_box() -> Obb<f32> {
+        let rotation = Quaternion::from_axis_angle(vec3(0.0, 0.0, 1.0), Deg(45.0));
+        Obb::from_rotation(vec3(1.0, 0.0, 0.0), rotation, vec3(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn frustum_planes_follow_depth_range() {
+        let eye = vec3(1.0, 2.0, 3.0);
+        let view = Mat4f::look_at(eye, vec3(1.0, 2.0, -7.0), vec3(0.0, 1.0, 0.0));
+        let ahead = |distance: f32| eye - vec3(0.0, 0.0, distance);
+        for depth in DEPTHS {
+            for far in [100.0, f32::INFINITY] {
+                let frustum = Frustum::from_matrix_with(&(Mat4f::perspective_with(Rad(1.0), 1.5, 0.1, far, depth) * view), depth);
+                assert!(frustum.planes.iter().all(|p| p.d.is_finite() && p.normal.length().is_finite()));
+                assert!(frustum.contains_point(ahead(0.2)), "{depth:?} {far}");
+                assert!(frustum.contains_point(ahead(50.0)), "{depth:?} {far}");
+                assert!(!frustum.contains_point(ahead(0.05)), "{depth:?} {far}");
+                assert!(!frustum.contains_point(ahead(50.0) + vec3(100.0, 0.0, 0.0)), "{depth:?} {far}");
+                assert!(frustum.planes[4].signed_distance(ahead(0.1)).abs() < 1e-5, "{depth:?} {far}");
+                assert_eq!(frustum.contains_point(ahead(1e4)), far.is_infinite(), "{depth:?} {far}");
+                if far.is_finite() {
+                    assert!((frustum.planes[5].signed_distance(ahead(far))).abs() < 1e-3, "{depth:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn from_matrix_is_opengl() {
+        let m = Mat4f::perspective(Rad(1.0), 1.5, 0.1, 100.0);
+        let (a, b) = (Frustum::from_matrix(&m), Frustum::from_matrix_with(&m, DepthRange::OpenGl));
+        for (a, b) in a.planes.iter().zip(&b.planes) {
+            assert_eq!((a.normal, a.d), (b.normal, b.d));
+        }
+    }
+
+    #[test]
+    fn ray_plane() {
+        let plane = Plane::from_point_normal(vec3(0.0, 0.0, 2.0), vec3(0.0, 0.0, 3.0));
+        let ray = |origin: Vec3f, direction: Vec3f| Ray::new(origin, direction).intersect_plane(&plane);
+        assert_eq!(ray(vec3(1.0, 1.0, 0.0), vec3(0.0, 0.0, 1.0)), Some(2.0));
+        assert_eq!(ray(vec3(1.0, 1.0, 4.0), vec3(0.0, 0.0, -2.0)), Some(1.0));
+        assert_eq!(ray(vec3(1.0, 1.0, 0.0), vec3(0.0, 0.0, -1.0)), None);
+        assert_eq!(ray(vec3(1.0, 1.0, 0.0), vec3(1.0, 0.0, 0.0)), None);
+        assert_eq!(ray(vec3(1.0, 1.0, 2.0), vec3(0.0, 0.0, 1.0)), Some(0.0));
+    }
+
+    #[test]
+    fn ray_triangle() {
+        let triangle = unit_triangle();
+        let ray = |origin: Vec3f, direction: Vec3f| Ray::new(origin, direction).intersect_triangle(&triangle);
+        assert_eq!(ray(vec3(0.25, 0.5, 1.0), vec3(0.0, 0.0, -1.0)), Some((1.0, 0.25, 0.5)));
+        assert_eq!(ray(vec3(0.25, 0.5, -2.0), vec3(0.0, 0.0, 1.0)), Some((2.0, 0.25, 0.5)));
+        assert_eq!(ray(vec3(1.0, 1.0, 1.0), vec3(0.0, 0.0, -1.0)), None);
+        assert_eq!(ray(vec3(-0.1, 0.5, 1.0), vec3(0.0, 0.0, -1.0)), None);
+        assert_eq!(ray(vec3(0.25, 0.5, 1.0), vec3(0.0, 0.0, 1.0)), None);
+        // Grazing along the plane and through the hypotenuse.
+        assert_eq!(ray(vec3(-1.0, 0.25, 0.0), vec3(1.0, 0.0, 0.0)), None);
+        assert_eq!(ray(vec3(0.5, 0.5, 1.0), vec3(0.0, 0.0, -1.0)), Some((1.0, 0.5, 0.5)));
+        assert_eq!(ray(vec3(0.25, 0.5, 0.0), vec3(0.0, 0.0, -1.0)), Some((0.0, 0.25, 0.5)));
+    }
+
+    #[test]
+    fn ray_aabb() {
+        let aabb = Aabb::new(vec3(-1.0, -1.0, -1.0), vec3(1.0, 1.0, 1.0));
+        let ray = |origin: Vec3f, direction: Vec3f| Ray::new(origin, direction).intersect_aabb(&aabb);
+        assert_eq!(ray(vec3(-3.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0)), Some((2.0, 4.0)));
+        assert_eq!(ray(vec3(-3.0, -3.0, 0.0), vec3(1.0, 1.0, 0.0)), Some((2.0, 4.0)));
+        assert_eq!(ray(vec3(-3.0, 2.0, 0.0), vec3(1.0, 0.0, 0.0)), None);
+        assert_eq!(ray(vec3(-3.0, 0.0, 0.0), vec3(1.0, 2.0, 0.0)), None);
+        assert_eq!(ray(vec3(3.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0)), None);
+        assert_eq!(ray(vec3(-3.0, 1.0, 1.0), vec3(1.0, 0.0, 0.0)), Some((2.0, 4.0)));
+        assert_eq!(ray(vec3(-3.0, -1.0, 0.0), vec3(1.0, 1.0, 0.0)), Some((2.0, 2.0)));
+        assert_eq!(ray(vec3(0.5, 0.0, 0.0), vec3(1.0, 0.0, 0.0)), Some((0.0, 0.5)));
+    }
+
+    #[test]
+    fn ray_sphere() {
+        let sphere = Sphere::new(vec3(0.0, 0.0, 5.0), 1.0);
+        let ray = |origin: Vec3f, direction: Vec3f| Ray::new(origin, direction).intersect_sphere(&sphere);
+        assert_eq!(ray(vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0)), Some(4.0));
+        assert_eq!(ray(vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 2.0)), Some(2.0));
+        assert_eq!(ray(vec3(0.0, 2.0, 0.0), vec3(0.0, 0.0, 1.0)), None);
+        assert_eq!(ray(vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, -1.0)), None);
+        assert_eq!(ray(vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, 1.0)), Some(5.0));
+        assert_eq!(ray(vec3(0.0, 0.5, 5.0), vec3(1.0, 0.0, 0.0)), Some(0.0));
+    }
+
+    #[test]
+    fn ray_obb() {
+        let obb = turned_box();
+        let diagonal = std::f32::consts::SQRT_2;
+        let ray = |origin: Vec3f, direction: Vec3f| Ray::new(origin, direction).intersect_obb(&obb);
+        let (entry, exit) = ray(vec3(1.0, -5.0, 0.0), vec3(0.0, 1.0, 0.0)).unwrap();
+        assert!((entry - (5.0 - diagonal)).abs() < 1e-5 && (exit - (5.0 + diagonal)).abs() < 1e-5);
+        // Crosses the world-space bounds of the box but misses the box itself.
+        assert!(obb.bounding_aabb().contains_point(vec3(-0.3, 0.4, 0.0)));
+        assert_eq!(ray(vec3(-4.0, -3.3, 0.0), vec3(1.0, 1.0, 0.0)), None);
+        assert_eq!(ray(vec3(1.0, 5.0, 0.0), vec3(0.0, 1.0, 0.0)), None);
+        let (entry, _) = ray(vec3(1.0, -5.0, 1.0), vec3(0.0, 1.0, 0.0)).unwrap();
+        assert!((entry - (5.0 - diagonal)).abs() < 1e-5);
+        let (entry, exit) = ray(vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)).unwrap();
+        assert!(entry == 0.0 && (exit - diagonal).abs() < 1e-5);
+    }
+
+    #[test]
+    fn closest_points_inside_and_outside() {
+        let plane = Plane::new(vec3(0.0, 0.0, 2.0), -4.0);
+        assert_close(plane.closest_point(vec3(1.0, 2.0, 5.0)), vec3(1.0, 2.0, 2.0));
+        assert_close(plane.closest_point(vec3(1.0, 2.0, 2.0)), vec3(1.0, 2.0, 2.0));
+
+        let aabb = Aabb::new(vec3(-1.0, -1.0, -1.0), vec3(1.0, 1.0, 1.0));
+        assert_close(aabb.closest_point(vec3(0.5, -0.2, 0.9)), vec3(0.5, -0.2, 0.9));
+        assert_close(aabb.closest_point(vec3(3.0, 0.5, -4.0)), vec3(1.0, 0.5, -1.0));
+
+        let sphere = Sphere::new(vec3(1.0, 0.0, 0.0), 2.0);
+        assert_close(sphere.closest_point(vec3(1.5, 1.0, 0.0)), vec3(1.5, 1.0, 0.0));
+        assert_close(sphere.closest_point(vec3(1.0, 0.0, -5.0)), vec3(1.0, 0.0, -2.0));
+
+        let obb = turned_box();
+        let diagonal = std::f32::consts::SQRT_2;
+        assert_close(obb.closest_point(vec3(1.5, 0.5, 0.5)), vec3(1.5, 0.5, 0.5));
+        assert_close(obb.closest_point(vec3(1.0, -5.0, 0.0)), vec3(1.0, -diagonal, 0.0));
+        assert_close(obb.closest_point(vec3(3.0, 2.0, 3.0)), vec3(1.0 + diagonal / 2.0, diagonal / 2.0, 1.0));
+
+        let ray = Ray::new(vec3(0.0, 0.0, 0.0), vec3(2.0, 0.0, 0.0));
+        assert_close(ray.closest_point(vec3(3.0, 1.0, 0.0)), vec3(3.0, 0.0, 0.0));
+        assert_close(ray.closest_point(vec3(-3.0, 1.0, 0.0)), vec3(0.0, 0.0, 0.0));
+
+        let segment = Segment::new(vec3(0.0, 0.0, 0.0), vec3(2.0, 0.0, 0.0));
+        assert_close(segment.closest_point(vec3(1.0, 3.0, 0.0)), vec3(1.0, 0.0, 0.0));
+        assert_close(segment.closest_point(vec3(-1.0, 1.0, 0.0)), segment.a);
+        assert_close(segment.closest_point(vec3(5.0, 0.0, 0.0)), segment.b);
+        let point = Segment::new(segment.b, segment.b);
+        assert_close(point.closest_point(vec3(5.0, 1.0, 0.0)), segment.b);
+
+        let crossing = Segment::new(vec3(1.0, -1.0, 1.0), vec3(1.0, 1.0, 1.0));
+        let (a, b) = segment.closest_points(&crossing);
+        assert_close(a, vec3(1.0, 0.0, 0.0));
+        assert_close(b, vec3(1.0, 0.0, 1.0));
+        let parallel = Segment::new(vec3(3.0, 1.0, 0.0), vec3(5.0, 1.0, 0.0));
+        let (a, b) = segment.closest_points(&parallel);
+        assert_close(a, segment.b);
+        assert_close(b, parallel.a);
+    }
+
+    #[test]
+    fn triangle_closest_point_regions() {
+        let triangle = unit_triangle();
+        let closest = |x, y, z| triangle.closest_point(vec3(x, y, z));
+        assert_close(closest(0.2, 0.3, 5.0), vec3(0.2, 0.3, 0.0));
+        assert_close(closest(0.2, 0.3, 0.0), vec3(0.2, 0.3, 0.0));
+        assert_close(closest(-1.0, -1.0, 0.0), triangle.a);
+        assert_close(closest(2.0, -0.5, 1.0), triangle.b);
+        assert_close(closest(-0.5, 2.0, -1.0), triangle.c);
+        assert_close(closest(0.5, -1.0, 3.0), vec3(0.5, 0.0, 0.0));
+        assert_close(closest(-1.0, 0.5, 0.0), vec3(0.0, 0.5, 0.0));
+        assert_close(closest(1.0, 1.0, 0.0), vec3(0.5, 0.5, 0.0));
+    }
+
+    #[test]
+    fn barycentric_round_trip() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let mut point = || vec3(rng.gen_range(-10.0..10.0), rng.gen_range(-10.0..10.0), rng.gen_range(-10.0..10.0));
+        let mut tested = 0;
+        while tested < 100 {
+            let triangle = Triangle::new(point(), point(), point());
+            if triangle.area() < 10.0 {
+                continue;
+            }
+            tested += 1;
+            let (a, b, c) = (triangle.a, triangle.b, triangle.c);
+            let weights = point() * 0.1;
+            let (v, w) = (weights.x(), weights.y());
+            let u = 1.0 - v - w;
+            let on_plane = a * u + b * v + c * w;
+            // Points off the plane are projected onto it first.
+            let lifted = on_plane + triangle.normal() * weights.z();
+            for p in [on_plane, lifted] {
+                let bary = triangle.barycentric(p);
+                assert!((bary - vec3(u, v, w)).length() < 1e-3, "{bary:?} != {:?}", (u, v, w));
+            }
+            assert_eq!(triangle.contains_point(on_plane), u >= 0.0 && v >= 0.0 && w >= 0.0);
+
+            let ray = Ray::new(on_plane + triangle.normal() * 2.0, -triangle.normal());
+            if let Some((t, hit_v, hit_w)) = ray.intersect_triangle(&triangle) {
+                assert!((t - 2.0).abs() < 1e-3 && (hit_v - v).abs() < 1e-3 && (hit_w - w).abs() < 1e-3);
+            } else {
+                assert!(u < 1e-3 || v < 1e-3 || w < 1e-3);
+            }
+        }
+    }
+}
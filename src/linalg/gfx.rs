@@ -1,215 +1,552 @@
+/*
+    Implementation of transform builders.
+    Generic over `num_traits::Float`, `Mat4f` and `Mat3f` infer `f32` from their alias.
+
+    `Mat3<T>` holds the linear part of 3D transforms and 2D affine transforms,
+    `Mat4<T>` holds 3D affine transforms and projections.
+*/
+
 use super::*;
+use angle::Rad;
+use num_traits::Float;
+use geometry::Ray;
+use quaternion::{EulerOrder, Quaternion};
+use std::ops::{AddAssign, DivAssign};
+
+fn c<T: Float>(x: f64) -> T {
+    T::from(x).unwrap()
+}
+
+impl<T> Mat3<T>
+where
+    T: Default + Copy + Float,
+{
+    pub fn identity() -> Self {
+        let (o, l) = (T::zero(), T::one());
+        Self::from([[l, o, o], [o, l, o], [o, o, l]])
+    }
+}
 
-impl Mat4f {
-    pub fn identity() -> Mat4f {
-        Mat4f::from([
-            [1.0, 0.0, 0.0, 0.0],
-            [0.0, 1.0, 0.0, 0.0],
-            [0.0, 0.0, 1.0, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
+impl<T> Mat4<T>
+where
+    T: Default + Copy + Float,
+{
+    pub fn identity() -> Self {
+        let (o, l) = (T::zero(), T::one());
+        Self::from([
+            [l, o, o, o],
+            [o, l, o, o],
+            [o, o, l, o],
+            [o, o, o, l],
         ])
     }
+
+    /// Embeds a linear transform, leaving the translation at zero.
+    pub fn from_mat3(m: Mat3<T>) -> Self {
+        let mut result = Self::identity();
+        for i in 0..3 {
+            result[i][..3].copy_from_slice(&m[i]);
+        }
+        result
+    }
 }
 
 // Rotation
-impl Mat4f {
-    pub fn rotation_x(rad: f32) -> Mat4f {
-        let cos_theta = rad.cos();
-        let sin_theta = rad.sin();
-
-        Mat4f::from([
-            [1.0, 0.0, 0.0, 0.0],
-            [0.0, cos_theta, sin_theta, 0.0],
-            [0.0, -sin_theta, cos_theta, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
+
+impl<T> Mat3<T>
+where
+    T: Default + Copy + Float + AddAssign,
+{
+    pub fn rotation_x(angle: impl Into<Rad<T>>) -> Self {
+        let (sin_theta, cos_theta) = angle.into().sin_cos();
+        let (o, l) = (T::zero(), T::one());
+
+        Self::from([
+            [l, o, o],
+            [o, cos_theta, sin_theta],
+            [o, -sin_theta, cos_theta],
         ])
     }
 
-    pub fn rotation_y(rad: f32) -> Mat4f {
-        let cos_theta = rad.cos();
-        let sin_theta = rad.sin();
+    pub fn rotation_y(angle: impl Into<Rad<T>>) -> Self {
+        let (sin_theta, cos_theta) = angle.into().sin_cos();
+        let (o, l) = (T::zero(), T::one());
 
-        Mat4f::from([
-            [cos_theta, 0.0, -sin_theta, 0.0],
-            [0.0, 1.0, 0.0, 0.0],
-            [sin_theta, 0.0, cos_theta, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
+        Self::from([
+            [cos_theta, o, -sin_theta],
+            [o, l, o],
+            [sin_theta, o, cos_theta],
         ])
     }
 
-    pub fn rotation_z(rad: f32) -> Mat4f {
-        let cos_theta = rad.cos();
-        let sin_theta = rad.sin();
+    pub fn rotation_z(angle: impl Into<Rad<T>>) -> Self {
+        let (sin_theta, cos_theta) = angle.into().sin_cos();
+        let (o, l) = (T::zero(), T::one());
 
-        Mat4f::from([
-            [cos_theta, sin_theta, 0.0, 0.0],
-            [-sin_theta, cos_theta, 0.0, 0.0],
-            [0.0, 0.0, 1.0, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
+        Self::from([
+            [cos_theta, sin_theta, o],
+            [-sin_theta, cos_theta, o],
+            [o, o, l],
         ])
     }
 
-    pub fn rotation_xyz(rotation: Vec3f) -> Mat4f {
-        Self::rotation_x(rotation.x())
-            .mm(&Self::rotation_y(rotation.y()))
-            .mm(&Self::rotation_z(rotation.z()))
+    /// One angle per axis, `x` is always the rotation around X, composed in `order`.\
+    /// Same as `Quaternion::from_euler`.
+    pub fn rotation_euler(x: impl Into<Rad<T>>, y: impl Into<Rad<T>>, z: impl Into<Rad<T>>, order: EulerOrder) -> Self {
+        let (x, y, z) = (x.into(), y.into(), z.into());
+        let around = |axis: usize| match axis {
+            0 => Self::rotation_x(x),
+            1 => Self::rotation_y(y),
+            _ => Self::rotation_z(z),
+        };
+        let (i, j, k) = order.axes();
+        around(i).mm(&around(j)).mm(&around(k))
+    }
+
+    /// Angles in radians, one per axis, applied in the order of the name like the variants below.\
+    /// `rotation_euler` takes typed angles instead.
+    pub fn rotation_xyz(rotation: Vec3<T>) -> Self {
+        Self::rotation_x(Rad(rotation.x()))
+            .mm(&Self::rotation_y(Rad(rotation.y())))
+            .mm(&Self::rotation_z(Rad(rotation.z())))
     }
 
-    pub fn rotation_xzy(rotation: Vec3f) -> Mat4f {
-        Self::rotation_x(rotation.x())
-            .mm(&Self::rotation_z(rotation.z()))
-            .mm(&Self::rotation_y(rotation.y()))
+    pub fn rotation_xzy(rotation: Vec3<T>) -> Self {
+        Self::rotation_x(Rad(rotation.x()))
+            .mm(&Self::rotation_z(Rad(rotation.z())))
+            .mm(&Self::rotation_y(Rad(rotation.y())))
     }
 
-    pub fn rotation_yxz(rotation: Vec3f) -> Mat4f {
-        Self::rotation_y(rotation.y())
-            .mm(&Self::rotation_x(rotation.x()))
-            .mm(&Self::rotation_z(rotation.z()))
+    pub fn rotation_yxz(rotation: Vec3<T>) -> Self {
+        Self::rotation_y(Rad(rotation.y()))
+            .mm(&Self::rotation_x(Rad(rotation.x())))
+            .mm(&Self::rotation_z(Rad(rotation.z())))
     }
 
-    pub fn rotation_yzx(rotation: Vec3f) -> Mat4f {
-        Self::rotation_y(rotation.y())
-            .mm(&Self::rotation_z(rotation.z()))
-            .mm(&Self::rotation_x(rotation.x()))
+    pub fn rotation_yzx(rotation: Vec3<T>) -> Self {
+        Self::rotation_y(Rad(rotation.y()))
+            .mm(&Self::rotation_z(Rad(rotation.z())))
+            .mm(&Self::rotation_x(Rad(rotation.x())))
     }
 
-    pub fn rotation_zyx(rotation: Vec3f) -> Mat4f {
-        Self::rotation_z(rotation.z())
-            .mm(&Self::rotation_y(rotation.y()))
-            .mm(&Self::rotation_x(rotation.x()))
+    pub fn rotation_zyx(rotation: Vec3<T>) -> Self {
+        Self::rotation_z(Rad(rotation.z()))
+            .mm(&Self::rotation_y(Rad(rotation.y())))
+            .mm(&Self::rotation_x(Rad(rotation.x())))
     }
 
-    pub fn rotation_zxy(rotation: Vec3f) -> Mat4f {
-        Self::rotation_z(rotation.z())
-            .mm(&Self::rotation_x(rotation.x()))
-            .mm(&Self::rotation_y(rotation.y()))
+    pub fn rotation_zxy(rotation: Vec3<T>) -> Self {
+        Self::rotation_z(Rad(rotation.z()))
+            .mm(&Self::rotation_x(Rad(rotation.x())))
+            .mm(&Self::rotation_y(Rad(rotation.y())))
     }
 
-    pub fn rotation_axis(axis: Vec3f, theta: f32) -> Mat4f {
-        let (rx, ry, rz) = (axis.data[0][0], axis.data[0][1], axis.data[0][2]);
-        let c1 = theta.cos();
-        let c2 = 1.0 - c1;
-        let s1 = theta.sin();
-        let s2 = 1.0 - s1;
+    /// `axis` must be normalized.
+    pub fn rotation_axis(axis: Vec3<T>, angle: impl Into<Rad<T>>) -> Self {
+        let (rx, ry, rz) = (axis.x(), axis.y(), axis.z());
+        let (s1, c1) = angle.into().sin_cos();
+        let c2 = T::one() - c1;
 
         Self::from([
-            [
-                c1 + rx * rx * c2,
-                ry * rx * c2 + rz * s1,
-                rz * rx * c2 - ry * s1,
-                0.0,
-            ],
-            [
-                rx * ry * c2 - rz * s1,
-                c1 + ry * ry * c2,
-                rz * ry * c2 + rx * s1,
-                0.0,
-            ],
-            [
-                rx * rz * c2 + ry * s1,
-                ry * rz * c2 - rx * s1,
-                c1 + rz * rz * c2,
-                0.0,
-            ],
-            [0.0, 0.0, 0.0, 1.0],
+            [c1 + rx * rx * c2, ry * rx * c2 + rz * s1, rz * rx * c2 - ry * s1],
+            [rx * ry * c2 - rz * s1, c1 + ry * ry * c2, rz * ry * c2 + rx * s1],
+            [rx * rz * c2 + ry * s1, ry * rz * c2 - rx * s1, c1 + rz * rz * c2],
         ])
     }
 
-    pub fn rotation_qua(rotation: Qua) -> Mat4f {
-        let x = rotation.b;
-        let y = rotation.c;
-        let z = rotation.d;
-        let w = rotation.a;
-
-        let xx = x * x;
-        let yy = y * y;
-        let zz = z * z;
-        let xy = x * y;
-        let xz = x * z;
-        let yz = y * z;
-        let wx = w * x;
-        let wy = w * y;
-        let wz = w * z;
-
-        Mat4f::from([
-            [1.0 - 2.0 * (yy + zz), 2.0 * (xy - wz), 2.0 * (xz + wy), 0.0],
-            [2.0 * (xy + wz), 1.0 - 2.0 * (xx + zz), 2.0 * (yz - wx), 0.0],
-            [2.0 * (xz - wy), 2.0 * (yz + wx), 1.0 - 2.0 * (xx + yy), 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-        ])
+    pub fn rotation_qua(rotation: Quaternion<T>) -> Self {
+        rotation.to_mat3()
+    }
+}
+
+impl<T> Mat4<T>
+where
+    T: Default + Copy + Float + AddAssign,
+{
+    pub fn rotation_x(angle: impl Into<Rad<T>>) -> Self {
+        Self::from_mat3(Mat3::rotation_x(angle))
+    }
+
+    pub fn rotation_y(angle: impl Into<Rad<T>>) -> Self {
+        Self::from_mat3(Mat3::rotation_y(angle))
+    }
+
+    pub fn rotation_z(angle: impl Into<Rad<T>>) -> Self {
+        Self::from_mat3(Mat3::rotation_z(angle))
+    }
+
+    pub fn rotation_euler(x: impl Into<Rad<T>>, y: impl Into<Rad<T>>, z: impl Into<Rad<T>>, order: EulerOrder) -> Self {
+        Self::from_mat3(Mat3::rotation_euler(x, y, z, order))
+    }
+
+    pub fn rotation_xyz(rotation: Vec3<T>) -> Self {
+        Self::from_mat3(Mat3::rotation_xyz(rotation))
+    }
+
+    pub fn rotation_xzy(rotation: Vec3<T>) -> Self {
+        Self::from_mat3(Mat3::rotation_xzy(rotation))
+    }
+
+    pub fn rotation_yxz(rotation: Vec3<T>) -> Self {
+        Self::from_mat3(Mat3::rotation_yxz(rotation))
+    }
+
+    pub fn rotation_yzx(rotation: Vec3<T>) -> Self {
+        Self::from_mat3(Mat3::rotation_yzx(rotation))
+    }
+
+    pub fn rotation_zyx(rotation: Vec3<T>) -> Self {
+        Self::from_mat3(Mat3::rotation_zyx(rotation))
+    }
+
+    pub fn rotation_zxy(rotation: Vec3<T>) -> Self {
+        Self::from_mat3(Mat3::rotation_zxy(rotation))
+    }
+
+    /// `axis` must be normalized.
+    pub fn rotation_axis(axis: Vec3<T>, angle: impl Into<Rad<T>>) -> Self {
+        Self::from_mat3(Mat3::rotation_axis(axis, angle))
+    }
+
+    pub fn rotation_qua(rotation: Quaternion<T>) -> Self {
+        rotation.to_mat4()
     }
 }
 
 // Translation
-impl Mat4f {
-    pub fn translation(transform: Vec3f) -> Mat4f {
-        let (x, y, z) = (transform[0][0], transform[0][1], transform[0][2]);
-        Mat4f::from([
-            [1.0, 0.0, 0.0, 0.0],
-            [0.0, 1.0, 0.0, 0.0],
-            [0.0, 0.0, 1.0, 0.0],
-            [x, y, z, 1.0],
-        ])
+
+impl<T> Mat4<T>
+where
+    T: Default + Copy + Float,
+{
+    pub fn translation(transform: Vec3<T>) -> Self {
+        Self::translation_xyz(transform.x(), transform.y(), transform.z())
     }
 
-    pub fn translation_xyz(x: f32, y: f32, z: f32) -> Mat4f {
-        Mat4f::from([
-            [1.0, 0.0, 0.0, 0.0],
-            [0.0, 1.0, 0.0, 0.0],
-            [0.0, 0.0, 1.0, 0.0],
-            [x, y, z, 1.0],
-        ])
+    pub fn translation_xyz(x: T, y: T, z: T) -> Self {
+        let mut result = Self::identity();
+        result[3][..3].copy_from_slice(&[x, y, z]);
+        result
     }
 }
 
 // Scale
-impl Mat4f {
-    pub fn scale(transform: Vec3f) -> Mat4f {
-        let (x, y, z) = (transform[0][0], transform[0][1], transform[0][2]);
-        Mat4f::from([
-            [x, 0.0, 0.0, 0.0],
-            [0.0, y, 0.0, 0.0],
-            [0.0, 0.0, z, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-        ])
+
+impl<T> Mat3<T>
+where
+    T: Default + Copy + Float,
+{
+    pub fn scale(transform: Vec3<T>) -> Self {
+        Self::scale_xyz(transform.x(), transform.y(), transform.z())
     }
 
-    pub fn scale_xyz(x: f32, y: f32, z: f32) -> Mat4f {
-        Mat4f::from([
-            [x, 0.0, 0.0, 0.0],
-            [0.0, y, 0.0, 0.0],
-            [0.0, 0.0, z, 0.0],
-            [0.0, 0.0, 0.0, 1.0],
-        ])
+    pub fn scale_xyz(x: T, y: T, z: T) -> Self {
+        let o = T::zero();
+        Self::from([[x, o, o], [o, y, o], [o, o, z]])
+    }
+}
+
+impl<T> Mat4<T>
+where
+    T: Default + Copy + Float,
+{
+    pub fn scale(transform: Vec3<T>) -> Self {
+        Self::from_mat3(Mat3::scale(transform))
+    }
+
+    pub fn scale_xyz(x: T, y: T, z: T) -> Self {
+        Self::from_mat3(Mat3::scale_xyz(x, y, z))
+    }
+}
+
+// 2D Affine
+
+impl<T> Mat3<T>
+where
+    T: Default + Copy + Float + AddAssign,
+{
+    pub fn translate_2d(offset: Vec2<T>) -> Self {
+        let mut result = Self::identity();
+        result[2][..2].copy_from_slice(&offset.data[0]);
+        result
+    }
+
+    /// Counter-clockwise rotation around the origin, the same matrix as `rotation_z`.
+    pub fn rotate_2d(angle: impl Into<Rad<T>>) -> Self {
+        Self::rotation_z(angle)
+    }
+
+    pub fn scale_2d(scale: Vec2<T>) -> Self {
+        Self::scale_xyz(scale.x(), scale.y(), T::one())
+    }
+
+    /// Shear, `x' = x + tan(x_angle) * y` and `y' = y + tan(y_angle) * x`.
+    pub fn skew(x_angle: impl Into<Rad<T>>, y_angle: impl Into<Rad<T>>) -> Self {
+        let (o, l) = (T::zero(), T::one());
+        Self::from([[l, y_angle.into().tan(), o], [x_angle.into().tan(), l, o], [o, o, l]])
     }
 }
 
 // Projection
 
-impl Mat4f {
-    pub fn ortho(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4f {
-        let rl = 1.0 / (right - left);
-        let tb = 1.0 / (top - bottom);
-        let fnf = 1.0 / (far - near);
-
-        Mat4f::from([
-            [2.0 * rl, 0.0, 0.0, 0.0],
-            [0.0, 2.0 * tb, 0.0, 0.0],
-            [0.0, 0.0, -2.0 * fnf, 0.0],
-            [-(right + left) * rl, -(top + bottom) * tb, -(far + near) * fnf, 1.0],
+/// Mapping of view depth to normalized device depth.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DepthRange {
+    /// OpenGL's default, near at -1 and far at 1.
+    #[default]
+    OpenGl,
+    /// Near at 0 and far at 1, needs `glClipControl(GL_LOWER_LEFT, GL_ZERO_TO_ONE)`.
+    ZeroToOne,
+    /// Reverse-Z, near at 1 and far at 0. Needs the same clip control as `ZeroToOne`,
+    /// a `GL_GREATER` depth test and a depth clear value of 0.
+    /// Spreads float depth precision evenly over distance.
+    ReverseZ,
+}
+
+impl DepthRange {
+    /// Normalized device depth of the near and far planes.
+    pub fn near_far<T: Float>(self) -> (T, T) {
+        match self {
+            DepthRange::OpenGl => (-T::one(), T::one()),
+            DepthRange::ZeroToOne => (T::zero(), T::one()),
+            DepthRange::ReverseZ => (T::one(), T::zero()),
+        }
+    }
+}
+
+impl<T> Mat4<T>
+where
+    T: Default + Copy + Float,
+{
+    pub fn ortho(left: T, right: T, bottom: T, top: T, near: T, far: T) -> Self {
+        Self::ortho_with(left, right, bottom, top, near, far, DepthRange::OpenGl)
+    }
+
+    pub fn ortho_with(left: T, right: T, bottom: T, top: T, near: T, far: T, depth: DepthRange) -> Self {
+        let rl = T::one() / (right - left);
+        let tb = T::one() / (top - bottom);
+        let (o, l, two) = (T::zero(), T::one(), c::<T>(2.0));
+        // Depth is linear, z_ndc = a * z + b with z = -near and z = -far hitting the range ends.
+        let (ndc_near, ndc_far) = depth.near_far::<T>();
+        let a = (ndc_near - ndc_far) / (far - near);
+        let b = ndc_near + a * near;
+
+        Self::from([
+            [two * rl, o, o, o],
+            [o, two * tb, o, o],
+            [o, o, a, o],
+            [-(right + left) * rl, -(top + bottom) * tb, b, l],
         ])
     }
 
-    pub fn perspective(fov: f32, aspect: f32, near: f32, far: f32) -> Mat4f {
-        let f = 1.0 / (fov / 2.0).tan();
-        let nf = 1.0 / (near - far);
+    /// `fov` is the vertical field of view.
+    pub fn perspective(fov: impl Into<Rad<T>>, aspect: T, near: T, far: T) -> Self {
+        Self::perspective_with(fov, aspect, near, far, DepthRange::OpenGl)
+    }
+
+    /// `far` may be `T::infinity()`, see `perspective_infinite`.
+    pub fn perspective_with(fov: impl Into<Rad<T>>, aspect: T, near: T, far: T, depth: DepthRange) -> Self {
+        let top = near * (fov.into() / c::<T>(2.0)).tan();
+        let right = top * aspect;
+        Self::frustum(-right, right, -top, top, near, far, depth)
+    }
+
+    /// Perspective without a far plane, everything beyond `near` stays inside the depth range.\
+    /// Pairs well with `DepthRange::ReverseZ`.
+    pub fn perspective_infinite(fov: impl Into<Rad<T>>, aspect: T, near: T, depth: DepthRange) -> Self {
+        Self::perspective_with(fov, aspect, near, T::infinity(), depth)
+    }
+
+    /// Off-center perspective, the bounds are measured on the near plane.\
+    /// Used for asymmetric views such as stereo eyes, tiled rendering or oblique screens.
+    /// `far` may be `T::infinity()`.
+    pub fn frustum(left: T, right: T, bottom: T, top: T, near: T, far: T, depth: DepthRange) -> Self {
+        let rl = T::one() / (right - left);
+        let tb = T::one() / (top - bottom);
+        let (o, l, two) = (T::zero(), T::one(), c::<T>(2.0));
+        // z_ndc = (a * z + b) / -z, so z_ndc = -a + b / near at the near plane and -a at infinity.
+        let (ndc_near, ndc_far) = depth.near_far::<T>();
+        let (a, b) = if far.is_infinite() {
+            (-ndc_far, (ndc_near - ndc_far) * near)
+        } else {
+            let b = (ndc_near - ndc_far) * near * far / (far - near);
+            (b / far - ndc_far, b)
+        };
 
-        Mat4f::from([
-            [f / aspect, 0.0, 0.0, 0.0],
-            [0.0, f, 0.0, 0.0],
-            [0.0, 0.0, (far + near) * nf, -1.0],
-            [0.0, 0.0, (2.0 * far * near) * nf, 0.0],
+        Self::from([
+            [two * near * rl, o, o, o],
+            [o, two * near * tb, o, o],
+            [(right + left) * rl, (top + bottom) * tb, a, -l],
+            [o, o, b, o],
         ])
     }
 }
 
+// Screen
+
+/// Window coordinates of a world point, `viewport` being `(x, y, width, height)` as given to `glViewport`.\
+/// Y grows upwards like OpenGL's window space, flip mouse coordinates with `height - y`.
+/// Z is the normalized device depth, in the range of the projection's `DepthRange`.
+/// Returns `None` for points at or behind the eye plane.
+pub fn project<T>(world: Vec3<T>, view_proj: &Mat4<T>, viewport: Vec4<T>) -> Option<Vec3<T>>
+where
+    T: Default + Copy + Float + std::ops::AddAssign,
+{
+    let clip = *view_proj * world.extend(T::one());
+    if clip.w() <= T::zero() {
+        return None;
+    }
+    let half = c::<T>(0.5);
+    let (x, y, z) = (clip.x() / clip.w(), clip.y() / clip.w(), clip.z() / clip.w());
+    Some(Vec3::from([[
+        viewport.x() + (x + T::one()) * half * viewport.z(),
+        viewport.y() + (y + T::one()) * half * viewport.w(),
+        z,
+    ]]))
+}
+
+/// Inverse of `project`, `screen.z` being the normalized device depth.\
+/// Returns `None` if the point maps to infinity, e.g. the far plane of an infinite projection.
+pub fn unproject<T>(screen: Vec3<T>, inv_view_proj: &Mat4<T>, viewport: Vec4<T>) -> Option<Vec3<T>>
+where
+    T: Default + Copy + Float + std::ops::AddAssign,
+{
+    let two = c::<T>(2.0);
+    let ndc = Vec4::from([[
+        two * (screen.x() - viewport.x()) / viewport.z() - T::one(),
+        two * (screen.y() - viewport.y()) / viewport.w() - T::one(),
+        screen.z(),
+        T::one(),
+    ]]);
+    let world = *inv_view_proj * ndc;
+    if world.w().abs() <= T::epsilon() {
+        return None;
+    }
+    Some(world.truncate() / world.w())
+}
+
+/// Ray from the near plane through a window position, for picking.\
+/// `depth` must match the projection, the direction is normalized.
+pub fn unproject_ray<T>(screen: Vec2<T>, inv_view_proj: &Mat4<T>, viewport: Vec4<T>, depth: DepthRange) -> Option<Ray<T>>
+where
+    T: Default + Copy + Float + std::ops::AddAssign + std::ops::DivAssign,
+{
+    let (ndc_near, ndc_far) = depth.near_far::<T>();
+    // Halfway through the depth range stays finite even without a far plane.
+    let ndc_mid = (ndc_near + ndc_far) * c::<T>(0.5);
+    let near = unproject(screen.extend(ndc_near), inv_view_proj, viewport)?;
+    let mid = unproject(screen.extend(ndc_mid), inv_view_proj, viewport)?;
+    Some(Ray::new(near, (mid - near).normalize()))
+}
+
+// View
+
+impl<T> Mat4<T>
+where
+    T: Default + Copy + Float + AddAssign + DivAssign,
+{
+    /// Right-handed view matrix looking from `eye` towards `target`.
+    pub fn look_at(eye: Vec3<T>, target: Vec3<T>, up: Vec3<T>) -> Self {
+        let f = (target - eye).normalize();
+        let s = f.cross(&up).normalize();
+        let u = s.cross(&f);
+        let (o, l) = (T::zero(), T::one());
+
+        Self::from([
+            [s.x(), u.x(), -f.x(), o],
+            [s.y(), u.y(), -f.y(), o],
+            [s.z(), u.z(), -f.z(), o],
+            [-s.dot(&eye), -u.dot(&eye), f.dot(&eye), l],
+        ])
+    }
+}
+
+// Inverse
+
+impl<T> Mat4<T>
+where
+    T: Default + Copy + Float,
+{
+    /// Inverse for affine transforms (bottom row `0, 0, 0, 1`),
+    /// e.g. products of `translation`, `rotation_*` and `scale`.\
+    /// Cheaper than the general `inverse`. Returns `None` if the linear part is singular.
+    pub fn inverse_affine(&self) -> Option<Self> {
+        let m = &self.data;
+        let linear = Mat3::from([
+            [m[0][0], m[0][1], m[0][2]],
+            [m[1][0], m[1][1], m[1][2]],
+            [m[2][0], m[2][1], m[2][2]],
+        ]);
+        let inv = linear.inverse()?;
+        let (tx, ty, tz) = (m[3][0], m[3][1], m[3][2]);
+
+        let mut result = Self::from_mat3(inv);
+        for j in 0..3 {
+            result[3][j] = -(inv[0][j] * tx + inv[1][j] * ty + inv[2][j] * tz);
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const DEPTHS: [DepthRange; 3] = [DepthRange::OpenGl, DepthRange::ZeroToOne, DepthRange::ReverseZ];
+
+    fn vec3(x: f64, y: f64, z: f64) -> Vec3d {
+        Vec3d::from([[x, y, z]])
+    }
+
+    fn view_proj(far: f64, depth: DepthRange) -> Mat4d {
+        let view = Mat4d::look_at(vec3(1.0, 2.0, 3.0), vec3(0.0, 0.0, -5.0), vec3(0.0, 1.0, 0.0));
+        Mat4d::perspective_with(Rad(1.0), 1.5, 0.1, far, depth) * view
+    }
+
+    #[test]
+    fn project_unproject_round_trip() {
+        let mut rng = StdRng::seed_from_u64(21);
+        let viewport = Vec4d::from([[10.0, 20.0, 800.0, 600.0]]);
+        let eye = vec3(1.0, 2.0, 3.0);
+        for depth in DEPTHS {
+            for far in [100.0, f64::INFINITY] {
+                let m = view_proj(far, depth);
+                let inv = m.inverse().unwrap();
+                for _ in 0..100 {
+                    // Random points in front of the eye, inside the view volume.
+                    let screen = vec3(rng.gen_range(10.0..810.0), rng.gen_range(20.0..620.0), 0.0);
+                    let ray = unproject_ray(screen.truncate(), &inv, viewport, depth).unwrap();
+                    let world = ray.at(rng.gen_range(0.0..90.0));
+                    let projected = project(world, &m, viewport).unwrap();
+                    assert!((projected.truncate() - screen.truncate()).length() < 1e-6, "{depth:?} {far}");
+                    let back = unproject(projected, &inv, viewport).unwrap();
+                    let scale = 1.0 + (world - eye).length();
+                    assert!((back - world).length() < 1e-9 * scale, "{depth:?} {far} {back:?} {world:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn depth_range_ends() {
+        let viewport = Vec4d::from([[0.0, 0.0, 800.0, 600.0]]);
+        let forward = |distance: f64| vec3(0.0, 0.0, -distance);
+        for depth in DEPTHS {
+            let (ndc_near, ndc_far) = depth.near_far::<f64>();
+            let finite = Mat4d::perspective_with(Rad(1.0), 1.5, 0.1, 100.0, depth);
+            let infinite = Mat4d::perspective_infinite(Rad(1.0), 1.5, 0.1, depth);
+            for m in [finite, infinite] {
+                let z = project(forward(0.1), &m, viewport).unwrap().z();
+                assert!((z - ndc_near).abs() < 1e-12, "{depth:?}");
+            }
+            let z = project(forward(100.0), &finite, viewport).unwrap().z();
+            assert!((z - ndc_far).abs() < 1e-12, "{depth:?}");
+            let z = project(forward(1e12), &infinite, viewport).unwrap().z();
+            assert!((z - ndc_far).abs() < 1e-9, "{depth:?}");
+            // The far plane of an infinite projection has no finite preimage.
+            let inv = infinite.inverse().unwrap();
+            assert!(unproject(vec3(400.0, 300.0, ndc_far), &inv, viewport).is_none());
+        }
+    }
+}
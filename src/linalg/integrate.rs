@@ -0,0 +1,170 @@
+/*
+    Implementation of numerical integrators.
+    States only need `+` and scaling by the scalar type, as `TMat` provides,
+    so `Vec2f`, `Vec3f` or any struct implementing `Add` and `Mul<T>` can be stepped.
+
+    `euler` and `rk4` step first-order systems dy/dt = f(t, y).
+    The second-order steppers take a position, a velocity and an acceleration,
+    `Integrator` picks one of them at runtime.
+*/
+
+use num_traits::Float;
+use std::ops::{Add, Mul};
+
+fn half<T: Float>() -> T {
+    T::one() / (T::one() + T::one())
+}
+
+// First-Order Systems
+
+/// Explicit Euler, first-order accurate. Gains energy on oscillating systems, prefer the others for motion.
+pub fn euler<T, S>(y: S, t: T, dt: T, f: impl Fn(T, S) -> S) -> S
+where
+    T: Float,
+    S: Copy + Add<Output = S> + Mul<T, Output = S>,
+{
+    y + f(t, y) * dt
+}
+
+/// Classic fourth-order Runge-Kutta, four evaluations of `f` per step.
+pub fn rk4<T, S>(y: S, t: T, dt: T, f: impl Fn(T, S) -> S) -> S
+where
+    T: Float,
+    S: Copy + Add<Output = S> + Mul<T, Output = S>,
+{
+    let h = half::<T>() * dt;
+    let k1 = f(t, y);
+    let k2 = f(t + h, y + k1 * h);
+    let k3 = f(t + h, y + k2 * h);
+    let k4 = f(t + dt, y + k3 * dt);
+    let sixth = dt / T::from(6).unwrap();
+    y + (k1 + (k2 + k3) * (T::one() + T::one()) + k4) * sixth
+}
+
+// Second-Order Systems
+
+/// Semi-implicit (symplectic) Euler, updates the velocity first and moves with the new one.\
+/// As cheap as explicit Euler but keeps the energy of oscillating systems bounded.
+/// Returns `(position, velocity)`.
+pub fn semi_implicit_euler<T, S>(position: S, velocity: S, t: T, dt: T, acceleration: impl Fn(T, S, S) -> S) -> (S, S)
+where
+    T: Float,
+    S: Copy + Add<Output = S> + Mul<T, Output = S>,
+{
+    let velocity = velocity + acceleration(t, position, velocity) * dt;
+    (position + velocity * dt, velocity)
+}
+
+/// Velocity Verlet, second-order accurate and symplectic for accelerations depending on position only.\
+/// Velocity-dependent terms such as drag see a first-order prediction of the new velocity.
+/// Returns `(position, velocity)`.
+pub fn velocity_verlet<T, S>(position: S, velocity: S, t: T, dt: T, acceleration: impl Fn(T, S, S) -> S) -> (S, S)
+where
+    T: Float,
+    S: Copy + Add<Output = S> + Mul<T, Output = S>,
+{
+    let h = half::<T>() * dt;
+    let a0 = acceleration(t, position, velocity);
+    let position = position + velocity * dt + a0 * (h * dt);
+    let predicted = velocity + a0 * dt;
+    let a1 = acceleration(t + dt, position, predicted);
+    (position, velocity + (a0 + a1) * h)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Integrator {
+    Euler,
+    #[default]
+    SemiImplicitEuler,
+    VelocityVerlet,
+    Rk4,
+}
+
+impl Integrator {
+    /// Advances a position and velocity by `dt` under `acceleration(t, position, velocity)`.\
+    /// Returns `(position, velocity)`.
+    pub fn step<T, S>(self, position: S, velocity: S, t: T, dt: T, acceleration: impl Fn(T, S, S) -> S) -> (S, S)
+    where
+        T: Float,
+        S: Copy + Add<Output = S> + Mul<T, Output = S>,
+    {
+        match self {
+            Integrator::Euler => (
+                position + velocity * dt,
+                velocity + acceleration(t, position, velocity) * dt,
+            ),
+            Integrator::SemiImplicitEuler => semi_implicit_euler(position, velocity, t, dt, acceleration),
+            Integrator::VelocityVerlet => velocity_verlet(position, velocity, t, dt, acceleration),
+            Integrator::Rk4 => {
+                // RK4 on the pair (position, velocity), whose derivative is (velocity, acceleration).
+                let h = half::<T>() * dt;
+                let (x1, v1) = (position, velocity);
+                let a1 = acceleration(t, x1, v1);
+                let (x2, v2) = (position + v1 * h, velocity + a1 * h);
+                let a2 = acceleration(t + h, x2, v2);
+                let (x3, v3) = (position + v2 * h, velocity + a2 * h);
+                let a3 = acceleration(t + h, x3, v3);
+                let (x4, v4) = (position + v3 * dt, velocity + a3 * dt);
+                let a4 = acceleration(t + dt, x4, v4);
+                let two = T::one() + T::one();
+                let sixth = dt / T::from(6).unwrap();
+                (
+                    position + (v1 + (v2 + v3) * two + v4) * sixth,
+                    velocity + (a1 + (a2 + a3) * two + a4) * sixth,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::*;
+
+    const DT: f32 = 0.01;
+    const STEPS: usize = 10_000;
+
+    /// Energy of the unit harmonic oscillator `x'' = -x`, relative to its start.
+    fn energy_ratio(mut step: impl FnMut(Vec2f, Vec2f, f32) -> (Vec2f, Vec2f)) -> f32 {
+        let (mut x, mut v) = (Vec2f::from([[1.0, 0.0]]), Vec2f::default());
+        let energy = |x: Vec2f, v: Vec2f| 0.5 * (x.length_squared() + v.length_squared());
+        let start = energy(x, v);
+        for i in 0..STEPS {
+            (x, v) = step(x, v, i as f32 * DT);
+        }
+        energy(x, v) / start
+    }
+
+    #[test]
+    fn harmonic_oscillator_energy() {
+        let spring = |_: f32, x: Vec2f, _: Vec2f| -x;
+        let ratio = |integrator: Integrator| energy_ratio(|x, v, t| integrator.step(x, v, t, DT, spring));
+
+        // (1 + dt²)^n ≈ e.
+        let euler = ratio(Integrator::Euler);
+        assert!((euler - std::f32::consts::E).abs() < 0.01, "{euler}");
+        // Symplectic Euler conserves x² + v² - dt x v, the true energy wobbles by O(dt) around it.
+        let semi_implicit = ratio(Integrator::SemiImplicitEuler);
+        assert!((semi_implicit - 1.0).abs() < DT, "{semi_implicit}");
+        for integrator in [Integrator::VelocityVerlet, Integrator::Rk4] {
+            let r = ratio(integrator);
+            assert!((r - 1.0).abs() < 1e-3, "{integrator:?} {r}");
+        }
+    }
+
+    #[test]
+    fn first_order_steppers() {
+        // State (x, v) with derivative (v, -x).
+        let f = |_: f32, y: Vec2f| Vec2f::from([[y.y(), -y.x()]]);
+        let (mut y_euler, mut y_rk4) = (Vec2f::from([[1.0, 0.0]]), Vec2f::from([[1.0, 0.0]]));
+        for i in 0..STEPS {
+            let t = i as f32 * DT;
+            y_euler = euler(y_euler, t, DT, f);
+            y_rk4 = rk4(y_rk4, t, DT, f);
+        }
+        let (euler, rk4) = (y_euler.length_squared(), y_rk4.length_squared());
+        assert!((euler - std::f32::consts::E).abs() < 0.01, "{euler}");
+        assert!((rk4 - 1.0).abs() < 1e-3, "{rk4}");
+    }
+}
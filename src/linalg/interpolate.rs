@@ -0,0 +1,222 @@
+/*
+    Implementation of the `Interpolate` trait.
+    One entry point for blending any two values of the same type,
+    so code such as tweens can stay generic over what it animates.
+
+    `lerp` is the componentwise blend, `interpolate` the natural one for the type:
+    slerp for rotations, the perceptual OKLab mix for colors, and lerp otherwise.
+*/
+
+use super::*;
+use complex::Complex;
+use dual_quaternion::DualQuaternion;
+use num_traits::Float;
+use quaternion::Quaternion;
+
+pub trait Interpolate: Copy {
+    type Scalar: Float;
+
+    /// Componentwise linear interpolation, `self` at `t = 0` and `other` at `t = 1`.
+    fn lerp(&self, other: &Self, t: Self::Scalar) -> Self;
+
+    /// Natural interpolation for the type, `lerp` unless the type overrides it.
+    fn interpolate(&self, other: &Self, t: Self::Scalar) -> Self {
+        self.lerp(other, t)
+    }
+}
+
+impl Interpolate for f32 {
+    type Scalar = f32;
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        scalar::lerp(*self, *other, t)
+    }
+}
+
+impl Interpolate for f64 {
+    type Scalar = f64;
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        scalar::lerp(*self, *other, t)
+    }
+}
+
+/// Covers every `TVec` as well. Vectors are treated as points, use `TVec::slerp` for directions.
+impl<T, const R: usize, const C: usize> Interpolate for TMat<T, R, C>
+where
+    T: Default + Copy + Float,
+{
+    type Scalar = T;
+
+    fn lerp(&self, other: &Self, t: T) -> Self {
+        *self * (T::one() - t) + *other * t
+    }
+}
+
+impl<T> Interpolate for Quaternion<T>
+where
+    T: Default + Copy + Float,
+{
+    type Scalar = T;
+
+    fn lerp(&self, other: &Self, t: T) -> Self {
+        Quaternion::lerp(self, other, t)
+    }
+
+    /// Shortest-arc `slerp`, the inputs must be unit quaternions.
+    fn interpolate(&self, other: &Self, t: T) -> Self {
+        self.slerp(other, t)
+    }
+}
+
+impl<T> Interpolate for DualQuaternion<T>
+where
+    T: Default + Copy + Float,
+{
+    type Scalar = T;
+
+    /// Linear blend renormalized as in `dlb`, the inputs must be unit dual quaternions.
+    fn lerp(&self, other: &Self, t: T) -> Self {
+        DualQuaternion::dlb(&[(*self, T::one() - t), (*other, t)])
+    }
+
+    /// Screw motion `sclerp`, the inputs must be unit dual quaternions.
+    fn interpolate(&self, other: &Self, t: T) -> Self {
+        self.sclerp(other, t)
+    }
+}
+
+impl<T> Interpolate for Complex<T>
+where
+    T: Default + Copy + Float,
+{
+    type Scalar = T;
+
+    fn lerp(&self, other: &Self, t: T) -> Self {
+        Complex::lerp(self, other, t)
+    }
+
+    /// `slerp`, the inputs must be unit complex numbers.
+    fn interpolate(&self, other: &Self, t: T) -> Self {
+        self.slerp(other, t)
+    }
+}
+
+impl<T: Float> Interpolate for Rad<T> {
+    type Scalar = T;
+
+    fn lerp(&self, other: &Self, t: T) -> Self {
+        Rad(scalar::lerp(self.0, other.0, t))
+    }
+}
+
+impl<T: Float> Interpolate for Deg<T> {
+    type Scalar = T;
+
+    fn lerp(&self, other: &Self, t: T) -> Self {
+        Deg(scalar::lerp(self.0, other.0, t))
+    }
+}
+
+impl Interpolate for Transform {
+    type Scalar = f32;
+
+    /// Same as `interpolate`, a componentwise rotation would not stay a rotation.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Transform::lerp(self, other, t)
+    }
+}
+
+impl Interpolate for Color {
+    type Scalar = f32;
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Color::lerp(*self, *other, t)
+    }
+
+    /// Perceptual `mix` through OKLab.
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.mix(*other, t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::{One, Zero};
+    use std::fmt::Debug;
+
+    /// `lerp` hits both ends exactly and passes through `middle` halfway.
+    fn assert_endpoints<V: Interpolate + PartialEq + Debug>(a: V, b: V, middle: V) {
+        let (zero, one) = (V::Scalar::zero(), V::Scalar::one());
+        let half = one / (one + one);
+        assert_eq!(a.lerp(&b, zero), a);
+        assert_eq!(a.lerp(&b, one), b);
+        assert_eq!(a.lerp(&b, half), middle);
+    }
+
+    /// For types without a more natural blend.
+    fn assert_interpolate_is_lerp<V: Interpolate + PartialEq + Debug>(a: V, b: V) {
+        let (zero, one) = (V::Scalar::zero(), V::Scalar::one());
+        let half = one / (one + one);
+        for t in [zero, half, one] {
+            assert_eq!(a.interpolate(&b, t), a.lerp(&b, t));
+        }
+    }
+
+    #[test]
+    fn endpoints() {
+        assert_endpoints(1.0_f32, 3.0, 2.0);
+        assert_endpoints(-4.0_f64, 8.0, 2.0);
+        assert_endpoints(Rad(0.5_f32), Rad(1.5), Rad(1.0));
+        assert_endpoints(Deg(90.0_f64), Deg(-90.0), Deg(0.0));
+        let (u, v) = (Vec3f::from([[1.0, 2.0, 3.0]]), Vec3f::from([[3.0, -2.0, 5.0]]));
+        assert_endpoints(u, v, Vec3f::from([[2.0, 0.0, 4.0]]));
+        let m = Mat4f::translation_xyz(2.0, 4.0, 6.0);
+        assert_endpoints(Mat4f::identity(), m, Mat4f::translation_xyz(1.0, 2.0, 3.0));
+        assert_endpoints(Color::BLACK, Color::new(1.0, 0.5, 0.0, 0.0), Color::new(0.5, 0.25, 0.0, 0.5));
+
+        assert_interpolate_is_lerp(1.0_f32, 3.0);
+        assert_interpolate_is_lerp(Rad(0.5_f32), Rad(1.5));
+        assert_interpolate_is_lerp(u, v);
+        assert_interpolate_is_lerp(Mat4f::identity(), m);
+    }
+
+    #[test]
+    fn rotations_stay_unit() {
+        let a = Quaternion::from_axis_angle(Vec3f::from([[1.0, 2.0, 2.0]]) / 3.0, Rad(0.4));
+        let b = Quaternion::from_axis_angle(Vec3f::from([[0.0, 0.6, -0.8]]), Rad(2.9));
+        assert!(a.interpolate(&b, 0.0).dot(&a) > 1.0 - 1e-6);
+        assert!(a.interpolate(&b, 1.0).dot(&b).abs() > 1.0 - 1e-6);
+        let half = a.interpolate(&b, 0.5);
+        assert!((half.abs() - 1.0).abs() < 1e-6, "{half:?}");
+        assert!((half.dot(&a).abs() - half.dot(&b).abs()).abs() < 1e-6);
+        assert!(a.lerp(&b, 0.5).abs() < 0.99);
+
+        let (a, b) = (Complex::from_angle(Rad(0.3_f32)), Complex::from_angle(Rad(2.5)));
+        let half = a.interpolate(&b, 0.5);
+        assert!((half.abs() - 1.0).abs() < 1e-6, "{half:?}");
+        assert!((half.arg().0 - 1.4).abs() < 1e-6);
+        assert!(a.lerp(&b, 0.5).abs() < 0.99);
+    }
+
+    #[test]
+    fn color_interpolate_is_mix() {
+        let colors = [Color::RED, Color::BLUE, Color::new(0.2, 0.8, 0.4, 0.5), Color::WHITE];
+        for a in colors {
+            for b in colors {
+                for t in [0.0, 0.25, 0.5, 1.0] {
+                    assert_eq!(a.interpolate(&b, t), a.mix(b, t));
+                }
+            }
+        }
+        let (a, b) = (Color::new(0.1, 0.9, 0.3, 1.0), Color::new(0.8, 0.2, 0.6, 0.0));
+        for (mixed, expected) in [(a.interpolate(&b, 0.0), a), (a.interpolate(&b, 1.0), b)] {
+            let (mixed, expected) = (mixed.to_array(), expected.to_array());
+            assert!(mixed.iter().zip(&expected).all(|(x, y)| (x - y).abs() < 1e-4), "{mixed:?} != {expected:?}");
+        }
+        // The OKLab mix keeps blue to yellow from passing through gray.
+        let mixed = Color::BLUE.interpolate(&Color::YELLOW, 0.5);
+        assert_ne!(mixed, Color::BLUE.lerp(Color::YELLOW, 0.5));
+    }
+}
@@ -354,3 +354,330 @@ where
         *self = *self / rhs;
     }
 }
+
+// Determinant & Inverse
+
+impl<T, const N: usize> TMat<T, N, N>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    /// Uses closed-form expansion for 2x2, 3x3 and 4x4 matrices,
+    /// and LU decomposition for any other size.
+    pub fn determinant(&self) -> T {
+        let m = &self.data;
+        match N {
+            0 => T::one(),
+            1 => m[0][0],
+            2 => m[0][0] * m[1][1] - m[1][0] * m[0][1],
+            3 => {
+                m[0][0] * (m[1][1] * m[2][2] - m[2][1] * m[1][2])
+                    - m[1][0] * (m[0][1] * m[2][2] - m[2][1] * m[0][2])
+                    + m[2][0] * (m[0][1] * m[1][2] - m[1][1] * m[0][2])
+            }
+            4 => {
+                let s0 = m[0][0] * m[1][1] - m[1][0] * m[0][1];
+                let s1 = m[0][0] * m[1][2] - m[1][0] * m[0][2];
+                let s2 = m[0][0] * m[1][3] - m[1][0] * m[0][3];
+                let s3 = m[0][1] * m[1][2] - m[1][1] * m[0][2];
+                let s4 = m[0][1] * m[1][3] - m[1][1] * m[0][3];
+                let s5 = m[0][2] * m[1][3] - m[1][2] * m[0][3];
+                let c5 = m[2][2] * m[3][3] - m[3][2] * m[2][3];
+                let c4 = m[2][1] * m[3][3] - m[3][1] * m[2][3];
+                let c3 = m[2][1] * m[3][2] - m[3][1] * m[2][2];
+                let c2 = m[2][0] * m[3][3] - m[3][0] * m[2][3];
+                let c1 = m[2][0] * m[3][2] - m[3][0] * m[2][2];
+                let c0 = m[2][0] * m[3][1] - m[3][0] * m[2][1];
+                s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0
+            }
+            _ => self.determinant_lu(),
+        }
+    }
+
+    /// Returns `None` if the matrix is singular.\
+    /// Uses closed-form adjugates for 2x2, 3x3 and 4x4 matrices,
+    /// and LU decomposition for any other size.
+    pub fn inverse(&self) -> Option<Self> {
+        let m = &self.data;
+        let mut inv = Self::default();
+        match N {
+            2 => {
+                let det = self.determinant();
+                if self.is_singular(det) {
+                    return None;
+                }
+                let inv_det = T::one() / det;
+                inv[0][0] = m[1][1] * inv_det;
+                inv[0][1] = -m[0][1] * inv_det;
+                inv[1][0] = -m[1][0] * inv_det;
+                inv[1][1] = m[0][0] * inv_det;
+                Some(inv)
+            }
+            3 => {
+                let det = self.determinant();
+                if self.is_singular(det) {
+                    return None;
+                }
+                let inv_det = T::one() / det;
+                inv[0][0] = (m[1][1] * m[2][2] - m[2][1] * m[1][2]) * inv_det;
+                inv[0][1] = (m[2][1] * m[0][2] - m[0][1] * m[2][2]) * inv_det;
+                inv[0][2] = (m[0][1] * m[1][2] - m[1][1] * m[0][2]) * inv_det;
+                inv[1][0] = (m[2][0] * m[1][2] - m[1][0] * m[2][2]) * inv_det;
+                inv[1][1] = (m[0][0] * m[2][2] - m[2][0] * m[0][2]) * inv_det;
+                inv[1][2] = (m[1][0] * m[0][2] - m[0][0] * m[1][2]) * inv_det;
+                inv[2][0] = (m[1][0] * m[2][1] - m[2][0] * m[1][1]) * inv_det;
+                inv[2][1] = (m[2][0] * m[0][1] - m[0][0] * m[2][1]) * inv_det;
+                inv[2][2] = (m[0][0] * m[1][1] - m[1][0] * m[0][1]) * inv_det;
+                Some(inv)
+            }
+            4 => {
+                let s0 = m[0][0] * m[1][1] - m[1][0] * m[0][1];
+                let s1 = m[0][0] * m[1][2] - m[1][0] * m[0][2];
+                let s2 = m[0][0] * m[1][3] - m[1][0] * m[0][3];
+                let s3 = m[0][1] * m[1][2] - m[1][1] * m[0][2];
+                let s4 = m[0][1] * m[1][3] - m[1][1] * m[0][3];
+                let s5 = m[0][2] * m[1][3] - m[1][2] * m[0][3];
+                let c5 = m[2][2] * m[3][3] - m[3][2] * m[2][3];
+                let c4 = m[2][1] * m[3][3] - m[3][1] * m[2][3];
+                let c3 = m[2][1] * m[3][2] - m[3][1] * m[2][2];
+                let c2 = m[2][0] * m[3][3] - m[3][0] * m[2][3];
+                let c1 = m[2][0] * m[3][2] - m[3][0] * m[2][2];
+                let c0 = m[2][0] * m[3][1] - m[3][0] * m[2][1];
+
+                let det = s0 * c5 - s1 * c4 + s2 * c3 + s3 * c2 - s4 * c1 + s5 * c0;
+                if self.is_singular(det) {
+                    return None;
+                }
+                let inv_det = T::one() / det;
+
+                inv[0][0] = (m[1][1] * c5 - m[1][2] * c4 + m[1][3] * c3) * inv_det;
+                inv[0][1] = (-m[0][1] * c5 + m[0][2] * c4 - m[0][3] * c3) * inv_det;
+                inv[0][2] = (m[3][1] * s5 - m[3][2] * s4 + m[3][3] * s3) * inv_det;
+                inv[0][3] = (-m[2][1] * s5 + m[2][2] * s4 - m[2][3] * s3) * inv_det;
+
+                inv[1][0] = (-m[1][0] * c5 + m[1][2] * c2 - m[1][3] * c1) * inv_det;
+                inv[1][1] = (m[0][0] * c5 - m[0][2] * c2 + m[0][3] * c1) * inv_det;
+                inv[1][2] = (-m[3][0] * s5 + m[3][2] * s2 - m[3][3] * s1) * inv_det;
+                inv[1][3] = (m[2][0] * s5 - m[2][2] * s2 + m[2][3] * s1) * inv_det;
+
+                inv[2][0] = (m[1][0] * c4 - m[1][1] * c2 + m[1][3] * c0) * inv_det;
+                inv[2][1] = (-m[0][0] * c4 + m[0][1] * c2 - m[0][3] * c0) * inv_det;
+                inv[2][2] = (m[3][0] * s4 - m[3][1] * s2 + m[3][3] * s0) * inv_det;
+                inv[2][3] = (-m[2][0] * s4 + m[2][1] * s2 - m[2][3] * s0) * inv_det;
+
+                inv[3][0] = (-m[1][0] * c3 + m[1][1] * c1 - m[1][2] * c0) * inv_det;
+                inv[3][1] = (m[0][0] * c3 - m[0][1] * c1 + m[0][2] * c0) * inv_det;
+                inv[3][2] = (-m[3][0] * s3 + m[3][1] * s1 - m[3][2] * s0) * inv_det;
+                inv[3][3] = (m[2][0] * s3 - m[2][1] * s1 + m[2][2] * s0) * inv_det;
+                Some(inv)
+            }
+            _ => self.inverse_lu(),
+        }
+    }
+
+    /// LU decomposition with partial pivoting.\
+    /// Returns `(lu, perm, sign)` where `lu` packs the unit lower factor below the diagonal
+    /// and the upper factor on and above it, `perm` is the row permutation
+    /// and `sign` is the parity of the permutation.\
+    /// Returns `None` if the matrix is singular.
+    pub fn lu(&self) -> Option<([[T; N]; N], [usize; N], T)> {
+        let mut a = self.data;
+        let mut perm = [0_usize; N];
+        for (i, p) in perm.iter_mut().enumerate() {
+            *p = i;
+        }
+        let mut sign = T::one();
+        // Pivots are compared against their own original row, so uniformly small rows
+        // such as those of a millimetre scale are not mistaken for singular ones.
+        let mut scale = [T::zero(); N];
+        for (s, row) in scale.iter_mut().zip(&a) {
+            *s = row.iter().fold(T::zero(), |m, e| m.max(e.abs()));
+        }
+
+        for k in 0..N {
+            let mut pivot = k;
+            for i in (k + 1)..N {
+                if a[i][k].abs() > a[pivot][k].abs() {
+                    pivot = i;
+                }
+            }
+            if a[pivot][k].abs() <= T::epsilon() * scale[perm[pivot]] {
+                return None;
+            }
+            if pivot != k {
+                a.swap(pivot, k);
+                perm.swap(pivot, k);
+                sign = -sign;
+            }
+            let pivot_row = a[k];
+            for row in a.iter_mut().skip(k + 1) {
+                let factor = row[k] / pivot_row[k];
+                row[k] = factor;
+                for j in (k + 1)..N {
+                    row[j] = row[j] - factor * pivot_row[j];
+                }
+            }
+        }
+        Some((a, perm, sign))
+    }
+
+    pub fn determinant_lu(&self) -> T {
+        match self.lu() {
+            Some((lu, _, sign)) => {
+                let mut det = sign;
+                for (i, row) in lu.iter().enumerate() {
+                    det = det * row[i];
+                }
+                det
+            }
+            None => T::zero(),
+        }
+    }
+
+    pub fn inverse_lu(&self) -> Option<Self> {
+        let (lu, perm, _) = self.lu()?;
+        let mut inv = Self::default();
+        for col in 0..N {
+            // Forward substitution on the permuted unit column.
+            let mut y = [T::zero(); N];
+            for i in 0..N {
+                let mut sum = if perm[i] == col { T::one() } else { T::zero() };
+                for j in 0..i {
+                    sum = sum - lu[i][j] * y[j];
+                }
+                y[i] = sum;
+            }
+            // Backward substitution.
+            for i in (0..N).rev() {
+                let mut sum = y[i];
+                for j in (i + 1)..N {
+                    sum = sum - lu[i][j] * inv[col][j];
+                }
+                inv[col][i] = sum / lu[i][i];
+            }
+        }
+        // Rows of `data` were treated as rows of the system,
+        // so the solved columns land transposed.
+        Some(inv.transpose())
+    }
+
+    /// Relative test against the Hadamard bound `|det| <= Π |column|`,
+    /// so scaling the matrix, or any single column, does not change the verdict.
+    fn is_singular(&self, det: T) -> bool {
+        let bound = self.data.iter().fold(T::one(), |product, column| {
+            product * column.iter().fold(T::zero(), |sum, &e| sum + e * e).sqrt()
+        });
+        det == T::zero() || !det.is_finite() || det.abs() <= T::epsilon() * bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn identity<const N: usize>() -> TMat<f64, N, N> {
+        let mut m = TMat::default();
+        for i in 0..N {
+            m[i][i] = 1.0;
+        }
+        m
+    }
+
+    /// Identity plus a small perturbation, diagonally dominant and therefore well-conditioned.
+    fn well_conditioned<const N: usize>(rng: &mut StdRng) -> TMat<f64, N, N> {
+        let mut m = identity::<N>();
+        for column in m.data.iter_mut() {
+            for e in column.iter_mut() {
+                *e += rng.gen_range(-0.4..0.4);
+            }
+        }
+        m
+    }
+
+    fn assert_identity<const N: usize>(m: &TMat<f64, N, N>) {
+        let id = identity::<N>();
+        for i in 0..N {
+            for j in 0..N {
+                assert!((m[i][j] - id[i][j]).abs() < 1e-9, "{:?}", m);
+            }
+        }
+    }
+
+    fn round_trip<const N: usize>(rng: &mut StdRng) {
+        let m = well_conditioned::<N>(rng);
+        assert_identity(&(m * m.inverse().unwrap()));
+        assert_identity(&(m.inverse().unwrap() * m));
+        assert_identity(&(m * m.inverse_lu().unwrap()));
+        assert!((m.determinant() - m.determinant_lu()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn inverse_round_trip() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            round_trip::<2>(&mut rng);
+            round_trip::<3>(&mut rng);
+            round_trip::<4>(&mut rng);
+            round_trip::<6>(&mut rng);
+        }
+    }
+
+    #[test]
+    fn singular_has_no_inverse() {
+        let m2: TMat<f64, 2, 2> = TMat::from([[1.0, 2.0], [2.0, 4.0]]);
+        let m3: TMat<f64, 3, 3> = TMat::from([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 1.0, 1.0]]);
+        let mut m4 = identity::<4>();
+        m4[3] = m4[1];
+        assert!(m2.inverse().is_none());
+        assert!(m3.inverse().is_none());
+        assert!(m3.inverse_lu().is_none());
+        assert!(m4.inverse().is_none());
+        assert!(TMat::<f64, 5, 5>::default().inverse().is_none());
+        assert_eq!(m3.determinant_lu(), 0.0);
+    }
+
+    #[test]
+    fn small_and_mixed_scales_invert() {
+        let m = Mat4f::scale_xyz(1e-3, 1e-3, 1e-3);
+        let expected = Mat4f::scale_xyz(1e3, 1e3, 1e3);
+        for inv in [m.inverse().unwrap(), m.inverse_lu().unwrap()] {
+            for i in 0..4 {
+                for j in 0..4 {
+                    assert!((inv[i][j] - expected[i][j]).abs() < 1e-2, "{:?}", inv);
+                }
+            }
+        }
+
+        // Columns spanning eight orders of magnitude, still well-conditioned once scaled.
+        let mut rng = StdRng::seed_from_u64(1);
+        let magnitudes = [1e-4, 1.0, 1e4, 1e-2, 1e2, 1.0];
+        let mut m6 = well_conditioned::<6>(&mut rng);
+        let mut m4 = well_conditioned::<4>(&mut rng);
+        for (i, &magnitude) in magnitudes.iter().enumerate() {
+            m6[i] = m6[i].map(|e| e * magnitude);
+            if i < 4 {
+                m4[i] = m4[i].map(|e| e * magnitude);
+            }
+        }
+        assert_identity(&(m4 * m4.inverse().unwrap()));
+        assert_identity(&(m4 * m4.inverse_lu().unwrap()));
+        assert_identity(&(m6 * m6.inverse().unwrap()));
+        assert!(((m4.determinant() - m4.determinant_lu()) / m4.determinant()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn affine_inverse_matches_general() {
+        let m = Mat4f::translation_xyz(1.0, 2.0, 3.0)
+            * Mat4f::rotation_axis(Vec3f::from([[1.0, 1.0, 0.0]]).normalize(), Rad(0.7))
+            * Mat4f::scale_xyz(2.0, 3.0, 0.5);
+        let affine = m.inverse_affine().unwrap();
+        let general = m.inverse().unwrap();
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((affine[i][j] - general[i][j]).abs() < 1e-5);
+            }
+        }
+        assert!(Mat4f::scale_xyz(1.0, 0.0, 1.0).inverse_affine().is_none());
+    }
+}
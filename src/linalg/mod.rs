@@ -2,11 +2,28 @@
 
 pub mod matrix;
 pub mod vector;
+pub mod swizzle;
 pub mod scalar;
+pub mod angle;
+pub use angle::{Deg, Rad};
 pub mod complex;
+pub mod color;
+pub use color::Color;
 pub mod quaternion;
+pub mod dual_quaternion;
 pub mod gfx;
 pub mod simd;
+pub mod geometry;
+pub mod transform;
+pub use transform::Transform;
+pub mod decomposition;
+pub mod fft;
+pub mod curve;
+pub mod noise;
+pub mod random;
+pub mod integrate;
+pub mod interpolate;
+pub use interpolate::Interpolate;
 pub mod aliases;
 pub use aliases::*;
 
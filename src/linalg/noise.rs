@@ -0,0 +1,480 @@
+/*
+    Implementation of coherent noise.
+    Perlin (improved, 2002), simplex and Worley noise in 2, 3 and 4 dimensions,
+    plus fractal combinators that work on any of them.
+
+    Results only depend on the seed: the permutation is built with splitmix64,
+    and evaluation uses nothing but IEEE-exact operations (+, -, *, /, floor, sqrt),
+    so the same seed produces the same values on every platform.
+*/
+
+use super::*;
+
+/// Seeded noise generator.
+#[derive(Debug, Clone)]
+pub struct Noise {
+    seed: u64,
+    perm: [u8; 512],
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn signed(value: f32, negative: bool) -> f32 {
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+// Gradients, selected by the low bits of a hash.
+
+fn grad2(hash: u8, [x, y]: [f32; 2]) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+/// The 12 cube edge directions of improved Perlin noise.
+fn grad3(hash: u8, [x, y, z]: [f32; 3]) -> f32 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    signed(u, h & 1 != 0) + signed(v, h & 2 != 0)
+}
+
+/// The 32 tesseract edge directions.
+fn grad4(hash: u8, [x, y, z, w]: [f32; 4]) -> f32 {
+    let (a, b, c) = match (hash >> 3) & 3 {
+        0 => (y, z, w),
+        1 => (x, z, w),
+        2 => (x, y, w),
+        _ => (x, y, z),
+    };
+    signed(a, hash & 1 != 0) + signed(b, hash & 2 != 0) + signed(c, hash & 4 != 0)
+}
+
+impl Noise {
+    pub fn new(seed: u64) -> Self {
+        let mut table: [u8; 256] = std::array::from_fn(|i| i as u8);
+        let mut state = seed;
+        for i in (1..256).rev() {
+            let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            table.swap(i, j);
+        }
+        let perm = std::array::from_fn(|i| table[i & 255]);
+        Self { seed, perm }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Hash of a lattice point, chained through the permutation table.
+    fn lattice<const N: usize>(&self, cell: [i32; N]) -> u8 {
+        cell.iter()
+            .fold(0_u8, |h, &c| self.perm[h as usize + (c & 255) as usize])
+    }
+
+    /// Full 32-bit hash of a lattice point, used to place Worley feature points.
+    fn cell_hash<const N: usize>(&self, cell: [i32; N], salt: u32) -> u32 {
+        let mut hash = self.seed ^ ((salt as u64) << 32);
+        for c in cell {
+            let mut state = hash ^ c as u32 as u64;
+            hash = splitmix64(&mut state);
+        }
+        hash as u32
+    }
+
+    // Perlin
+
+    fn perlin<const N: usize>(&self, p: [f32; N], grad: fn(u8, [f32; N]) -> f32) -> f32 {
+        let cell = p.map(|x| x.floor());
+        let f: [f32; N] = std::array::from_fn(|i| p[i] - cell[i]);
+        let weights = f.map(fade);
+        let cell = cell.map(|x| x as i32);
+
+        // Multilinear blend of the gradient ramps at the 2^N corners.
+        (0..1_usize << N).fold(0.0, |sum, corner| {
+            let bit = |i: usize| (corner >> i) & 1;
+            let hash = self.lattice::<N>(std::array::from_fn(|i| cell[i].wrapping_add(bit(i) as i32)));
+            let offset = std::array::from_fn(|i| f[i] - bit(i) as f32);
+            let weight = (0..N).fold(1.0, |w, i| {
+                w * if bit(i) == 1 { weights[i] } else { 1.0 - weights[i] }
+            });
+            sum + weight * grad(hash, offset)
+        })
+    }
+
+    /// Roughly in [-1, 1], zero at integer lattice points.
+    pub fn perlin2(&self, p: Vec2f) -> f32 {
+        self.perlin(p.data[0], grad2)
+    }
+
+    pub fn perlin3(&self, p: Vec3f) -> f32 {
+        self.perlin(p.data[0], grad3)
+    }
+
+    pub fn perlin4(&self, p: Vec4f) -> f32 {
+        // Three-component gradients reach further than in 2D and 3D.
+        self.perlin(p.data[0], grad4) * 0.75
+    }
+
+    // Simplex
+
+    fn simplex<const N: usize>(
+        &self,
+        p: [f32; N],
+        skew: f32,
+        unskew: f32,
+        radius: f32,
+        grad: fn(u8, [f32; N]) -> f32,
+    ) -> f32 {
+        let s = p.iter().sum::<f32>() * skew;
+        let cell = p.map(|x| (x + s).floor());
+        let t = cell.iter().sum::<f32>() * unskew;
+        let x0: [f32; N] = std::array::from_fn(|i| p[i] - (cell[i] - t));
+        let cell = cell.map(|x| x as i32);
+
+        // Rank the coordinates to find which simplex of the skewed cell contains the point.
+        let mut rank = [0_usize; N];
+        for i in 0..N {
+            for j in (i + 1)..N {
+                if x0[i] > x0[j] {
+                    rank[i] += 1;
+                } else {
+                    rank[j] += 1;
+                }
+            }
+        }
+
+        (0..=N).fold(0.0, |sum, k| {
+            let offset: [i32; N] = std::array::from_fn(|i| (k > 0 && rank[i] >= N - k) as i32);
+            let x: [f32; N] = std::array::from_fn(|i| x0[i] - offset[i] as f32 + k as f32 * unskew);
+            let falloff = radius - x.iter().map(|v| v * v).sum::<f32>();
+            if falloff <= 0.0 {
+                return sum;
+            }
+            let hash = self.lattice::<N>(std::array::from_fn(|i| cell[i].wrapping_add(offset[i])));
+            let falloff = falloff * falloff;
+            sum + falloff * falloff * grad(hash, x)
+        })
+    }
+
+    /// Roughly in [-1, 1].
+    pub fn simplex2(&self, p: Vec2f) -> f32 {
+        // (√3 - 1) / 2 and (3 - √3) / 6
+        70.0 * self.simplex(p.data[0], 0.366_025_42, 0.211_324_87, 0.5, grad2)
+    }
+
+    pub fn simplex3(&self, p: Vec3f) -> f32 {
+        32.0 * self.simplex(p.data[0], 1.0 / 3.0, 1.0 / 6.0, 0.6, grad3)
+    }
+
+    pub fn simplex4(&self, p: Vec4f) -> f32 {
+        // (√5 - 1) / 4 and (5 - √5) / 20
+        27.0 * self.simplex(p.data[0], 0.309_017, 0.138_196_6, 0.6, grad4)
+    }
+
+    // Worley
+
+    fn worley<const N: usize>(&self, p: [f32; N]) -> (f32, f32) {
+        let cell = p.map(|x| x.floor() as i32);
+        let (mut f1, mut f2) = (f32::INFINITY, f32::INFINITY);
+        for neighbour in 0..3_usize.pow(N as u32) {
+            let mut digits = neighbour;
+            let mut candidate = [0_i32; N];
+            for c in candidate.iter_mut() {
+                *c = (digits % 3) as i32 - 1;
+                digits /= 3;
+            }
+            let candidate: [i32; N] = std::array::from_fn(|i| cell[i].wrapping_add(candidate[i]));
+            let distance_squared = (0..N).fold(0.0, |sum, i| {
+                let jitter = self.cell_hash(candidate, i as u32) as f32 / 4_294_967_296.0;
+                let d = candidate[i] as f32 + jitter - p[i];
+                sum + d * d
+            });
+            if distance_squared < f1 {
+                f2 = f1;
+                f1 = distance_squared;
+            } else if distance_squared < f2 {
+                f2 = distance_squared;
+            }
+        }
+        (f1.sqrt(), f2.sqrt())
+    }
+
+    /// Distances to the nearest and second nearest feature point, as `(f1, f2)`.\
+    /// Each unit cell holds one feature point. `f2 - f1` outlines the cells.\
+    /// Only the surrounding cells are searched, which is exact for `f1`
+    /// but can overestimate `f2` in rare configurations.
+    pub fn worley2(&self, p: Vec2f) -> (f32, f32) {
+        self.worley(p.data[0])
+    }
+
+    pub fn worley3(&self, p: Vec3f) -> (f32, f32) {
+        self.worley(p.data[0])
+    }
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+// Fractal
+
+/// Sums octaves of a noise function, each scaled in frequency by `lacunarity`
+/// and in amplitude by `gain`.
+#[derive(Debug, Clone, Copy)]
+pub struct Fractal {
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl Fractal {
+    pub fn new(octaves: u32, lacunarity: f32, gain: f32) -> Self {
+        Self {
+            octaves,
+            lacunarity,
+            gain,
+        }
+    }
+
+    fn sum<const L: usize>(
+        &self,
+        p: TVec<f32, L>,
+        noise: impl Fn(TVec<f32, L>) -> f32,
+        shape: impl Fn(f32) -> f32,
+    ) -> f32 {
+        let (mut sum, mut norm) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        for _ in 0..self.octaves {
+            sum += amplitude * shape(noise(p * frequency));
+            norm += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        if norm > 0.0 {
+            sum / norm
+        } else {
+            0.0
+        }
+    }
+
+    /// Fractal Brownian motion, in the range of `noise`.
+    pub fn fbm<const L: usize>(&self, p: TVec<f32, L>, noise: impl Fn(TVec<f32, L>) -> f32) -> f32 {
+        self.sum(p, noise, |n| n)
+    }
+
+    /// Sharp crests where `noise` crosses zero, in [0, 1].
+    pub fn ridged<const L: usize>(&self, p: TVec<f32, L>, noise: impl Fn(TVec<f32, L>) -> f32) -> f32 {
+        self.sum(p, noise, |n| {
+            let r = 1.0 - n.abs();
+            r * r
+        })
+    }
+
+    /// Sum of absolute octaves, in [0, 1].
+    pub fn turbulence<const L: usize>(&self, p: TVec<f32, L>, noise: impl Fn(TVec<f32, L>) -> f32) -> f32 {
+        self.sum(p, noise, f32::abs)
+    }
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Self::new(5, 2.0, 0.5)
+    }
+}
+
+/// Displaces `p` by `amount` times `noise` sampled at decorrelated offsets, one per component.\
+/// Feed the result to another noise function for swirling, marbled patterns.
+pub fn domain_warp<const L: usize>(
+    p: TVec<f32, L>,
+    amount: f32,
+    noise: impl Fn(TVec<f32, L>) -> f32,
+) -> TVec<f32, L> {
+    const OFFSETS: [f32; 4] = [0.0, 5.2, 1.7, 9.2];
+    let mut warped = p;
+    for i in 0..L {
+        let shift = TVec::from([std::array::from_fn(|j| OFFSETS[(i + j) % 4] + 1.3 * i as f32)]);
+        warped[0][i] += amount * noise(p + shift);
+    }
+    warped
+}
+
+// Baking
+
+/// Samples `noise` over the unit square and packs it as tightly packed RGBA8 rows,
+/// ready for `GLTexture::Texture2D(width, height, data.as_ptr() as *const c_void, mipmap)`.\
+/// `noise` receives the texel center in [0, 1)² and should return a value in [-1, 1],
+/// which is mapped to grayscale with opaque alpha.
+pub fn bake_rgba(width: u32, height: u32, noise: impl Fn(Vec2f) -> f32) -> Vec<u8> {
+    let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let uv = Vec2f::from([[
+                (x as f32 + 0.5) / width as f32,
+                (y as f32 + 0.5) / height as f32,
+            ]]);
+            let value = ((noise(uv) * 0.5 + 0.5).clamp(0.0, 1.0) * 255.0).round() as u8;
+            data.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec2(x: f32, y: f32) -> Vec2f {
+        Vec2f::from([[x, y]])
+    }
+
+    fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+        Vec3f::from([[x, y, z]])
+    }
+
+    fn vec4(x: f32, y: f32, z: f32, w: f32) -> Vec4f {
+        Vec4f::from([[x, y, z, w]])
+    }
+
+    /// Points of a grid not aligned with the lattice, as `(x, y, z, w)`.
+    fn grid() -> impl Iterator<Item = [f32; 4]> {
+        (0..40).flat_map(|i| {
+            (0..40).flat_map(move |j| {
+                (0..8).map(move |k| {
+                    let (x, y, z) = (i as f32 * 0.137 - 2.0, j as f32 * 0.173 - 3.0, k as f32 * 0.291);
+                    [x, y, z, x - y]
+                })
+            })
+        })
+    }
+
+    // Exact values, IEEE arithmetic must reproduce them on every platform.
+    #[test]
+    fn golden_values() {
+        let noise = Noise::new(42);
+        let (a, b) = ([0.3, 1.7, -0.6, 2.2], [-2.45, 5.1, 7.25, -3.3]);
+        let [p2, q2] = [a, b].map(|[x, y, _, _]| vec2(x, y));
+        let [p3, q3] = [a, b].map(|[x, y, z, _]| vec3(x, y, z));
+        let [p4, q4] = [a, b].map(|[x, y, z, w]| vec4(x, y, z, w));
+
+        assert_eq!(noise.perlin2(p2), -0.38302788);
+        assert_eq!(noise.perlin2(q2), 0.077308185);
+        assert_eq!(noise.perlin3(p3), 0.43119365);
+        assert_eq!(noise.perlin3(q3), 0.13362657);
+        assert_eq!(noise.perlin4(p4), -0.050089635);
+        assert_eq!(noise.perlin4(q4), 0.49831608);
+
+        assert_eq!(noise.simplex2(p2), -0.12972867);
+        assert_eq!(noise.simplex2(q2), 0.12256163);
+        assert_eq!(noise.simplex3(p3), -0.8400745);
+        assert_eq!(noise.simplex3(q3), 0.2825018);
+        assert_eq!(noise.simplex4(p4), -0.05356991);
+        assert_eq!(noise.simplex4(q4), 0.0942893);
+
+        assert_eq!(noise.worley2(p2), (0.26624882, 0.71817625));
+        assert_eq!(noise.worley2(q2), (0.23895493, 0.29506263));
+        assert_eq!(noise.worley3(p3), (0.4031466, 0.47643104));
+        assert_eq!(noise.worley3(q3), (0.08281232, 0.45673093));
+    }
+
+    fn sample(noise: &Noise, [x, y, z, w]: [f32; 4]) -> [f32; 8] {
+        let (f1, f2) = noise.worley3(vec3(x, y, z));
+        [
+            noise.perlin2(vec2(x, y)),
+            noise.perlin3(vec3(x, y, z)),
+            noise.perlin4(vec4(x, y, z, w)),
+            noise.simplex2(vec2(x, y)),
+            noise.simplex3(vec3(x, y, z)),
+            noise.simplex4(vec4(x, y, z, w)),
+            f1,
+            f2,
+        ]
+    }
+
+    #[test]
+    fn seeds() {
+        let (a, b) = (Noise::new(7), Noise::new(8));
+        let (same, clone) = (Noise::new(7), a.clone());
+        assert_eq!(a.seed(), 7);
+        let mut differs = [false; 8];
+        for p in grid() {
+            let sa = sample(&a, p);
+            assert_eq!(sa, sample(&same, p));
+            assert_eq!(sa, sample(&clone, p));
+            for (d, (x, y)) in differs.iter_mut().zip(sa.iter().zip(sample(&b, p))) {
+                *d |= *x != y;
+            }
+        }
+        assert!(differs.iter().all(|&d| d), "{differs:?}");
+    }
+
+    #[test]
+    fn ranges() {
+        let noise = Noise::new(3);
+        for p in grid() {
+            let s = sample(&noise, p);
+            for value in &s[..6] {
+                assert!((-1.0..=1.0).contains(value), "{p:?} {s:?}");
+            }
+            let (f1, f2) = (s[6], s[7]);
+            assert!(0.0 <= f1 && f1 <= f2, "{p:?} {s:?}");
+            let (f1, f2) = noise.worley2(vec2(p[0], p[1]));
+            assert!(0.0 <= f1 && f1 <= f2, "{p:?}");
+        }
+    }
+
+    #[test]
+    fn perlin_is_zero_at_lattice_points() {
+        let noise = Noise::new(11);
+        for x in -3..3 {
+            for y in -3..3 {
+                let (x, y) = (x as f32, y as f32);
+                assert_eq!(noise.perlin2(vec2(x, y)), 0.0);
+                assert_eq!(noise.perlin3(vec3(x, y, x + y)), 0.0);
+                assert_eq!(noise.perlin4(vec4(x, y, x - y, 2.0)), 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn bake_layout() {
+        let noise = Noise::new(5);
+        let data = bake_rgba(7, 3, |p| noise.simplex2(p * 4.0));
+        assert_eq!(data.len(), 7 * 3 * 4);
+        assert!(data.chunks(4).all(|t| t[3] == 255 && t[0] == t[1] && t[1] == t[2]));
+
+        assert!(bake_rgba(2, 2, |_| 0.0).chunks(4).all(|t| t == [128, 128, 128, 255]));
+        assert!(bake_rgba(2, 2, |_| 5.0).chunks(4).all(|t| t == [255, 255, 255, 255]));
+        assert!(bake_rgba(2, 2, |_| -5.0).chunks(4).all(|t| t == [0, 0, 0, 255]));
+        assert!(bake_rgba(0, 4, |_| 0.0).is_empty());
+    }
+}
@@ -4,10 +4,14 @@
 */
 
 use super::*;
+use angle::Rad;
 use crate::{lerp, vec_from};
 use complex::Complex;
 use std::num;
 
+/// Above `1 - SLERP_THRESHOLD` of angular cosine, slerp falls back to nlerp.
+pub(crate) const SLERP_THRESHOLD: f64 = 1e-4;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Quaternion<T> {
     pub a: T,
@@ -74,14 +78,336 @@ where
         self.lerp(other, t).normalize()
     }
 
+    /// Spherical interpolation of unit quaternions along the shortest arc.\
+    /// Falls back to `nlerp` when the inputs are nearly parallel.
     pub fn slerp(&self, other: &Self, t: T) -> Self {
-        let theta = self.dot(&other).acos();
-        let alpha = ((T::one() - t) * theta).sin() / theta.sin();
-        let beta = (t * theta).sin() / theta.sin();
+        let mut cos_theta = self.dot(other);
+        let mut end = *other;
+        if cos_theta < T::zero() {
+            cos_theta = -cos_theta;
+            end = -end;
+        }
+        if cos_theta > T::one() - T::from(SLERP_THRESHOLD).unwrap() {
+            return self.nlerp(&end, t);
+        }
+        let theta = cos_theta.min(T::one()).acos();
+        let sin_theta = theta.sin();
+        let alpha = ((T::one() - t) * theta).sin() / sin_theta;
+        let beta = (t * theta).sin() / sin_theta;
+        *self * alpha + end * beta
+    }
+
+    /// Quaternion exponential.
+    pub fn exp(&self) -> Self {
+        let (w, v) = self.decompose();
+        let theta = (v.x() * v.x() + v.y() * v.y() + v.z() * v.z()).sqrt();
+        let scale = if theta > T::epsilon() {
+            theta.sin() / theta
+        } else {
+            T::one()
+        };
+        Self::new_from(theta.cos(), v.x() * scale, v.y() * scale, v.z() * scale) * w.exp()
+    }
+
+    /// Quaternion natural logarithm.
+    pub fn ln(&self) -> Self {
+        let norm = self.abs();
+        let (w, v) = self.decompose();
+        let v_norm = (v.x() * v.x() + v.y() * v.y() + v.z() * v.z()).sqrt();
+        let scale = if v_norm > T::epsilon() {
+            (w / norm).max(-T::one()).min(T::one()).acos() / v_norm
+        } else {
+            T::zero()
+        };
+        Self::new_from(norm.ln(), v.x() * scale, v.y() * scale, v.z() * scale)
+    }
+
+    /// Inner control point of `current` for `squad`, given its neighbouring keyframes.
+    pub fn squad_control(prev: &Self, current: &Self, next: &Self) -> Self {
+        let hemisphere = |q: &Self| if current.dot(q) < T::zero() { -*q } else { *q };
+        let inv = current.conjugate();
+        let to_prev = (inv * hemisphere(prev)).ln();
+        let to_next = (inv * hemisphere(next)).ln();
+        let quarter = T::from(0.25).unwrap();
+        *current * (-(to_prev + to_next) * quarter).exp()
+    }
+
+    /// Spherical quadrangle interpolation between `self` and `other`,
+    /// with `a` and `b` their control points from `squad_control`.
+    pub fn squad(&self, other: &Self, a: &Self, b: &Self, t: T) -> Self {
+        let two = T::one() + T::one();
+        let outer = self.slerp_unclamped(other, t);
+        let inner = a.slerp_unclamped(b, t);
+        outer.slerp_unclamped(&inner, two * t * (T::one() - t))
+    }
+
+    /// Smooth interpolation through `keys`, `t` ranges from `0` to `keys.len() - 1`.\
+    /// End keys are used as their own neighbours.
+    pub fn squad_path(keys: &[Self], t: T) -> Self {
+        match keys.len() {
+            0 => return Self::identity(),
+            1 => return keys[0],
+            _ => (),
+        }
+        let last = keys.len() - 1;
+        let t = t.max(T::zero()).min(T::from(last).unwrap());
+        let i = t.floor().to_usize().unwrap().min(last - 1);
+        let local = t - T::from(i).unwrap();
+
+        let key = |index: isize| keys[index.clamp(0, last as isize) as usize];
+        let i = i as isize;
+        // Keep consecutive keys in the same hemisphere.
+        let q0 = key(i - 1);
+        let q1 = key(i);
+        let q2 = if q1.dot(&key(i + 1)) < T::zero() { -key(i + 1) } else { key(i + 1) };
+        let q3 = key(i + 2);
+
+        let a = Self::squad_control(&q0, &q1, &q2);
+        let b = Self::squad_control(&q1, &q2, &q3);
+        q1.squad(&q2, &a, &b, local)
+    }
+
+    /// `slerp` without the shortest-arc flip, as required inside `squad`.
+    fn slerp_unclamped(&self, other: &Self, t: T) -> Self {
+        let cos_theta = self.dot(other).max(-T::one()).min(T::one());
+        if cos_theta.abs() > T::one() - T::from(SLERP_THRESHOLD).unwrap() {
+            return self.nlerp(other, t);
+        }
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let alpha = ((T::one() - t) * theta).sin() / sin_theta;
+        let beta = (t * theta).sin() / sin_theta;
         *self * alpha + *other * beta
     }
 }
 
+/// Order in which Euler angles are applied, matching `Mat4f::rotation_xyz` and friends.\
+/// `Xyz` composes as `Rx * Ry * Rz`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EulerOrder {
+    Xyz,
+    Xzy,
+    Yxz,
+    Yzx,
+    Zyx,
+    Zxy,
+}
+
+impl EulerOrder {
+    /// Axis indices `(i, j, k)` of the composition `Ri * Rj * Rk`.
+    pub fn axes(&self) -> (usize, usize, usize) {
+        match self {
+            EulerOrder::Xyz => (0, 1, 2),
+            EulerOrder::Xzy => (0, 2, 1),
+            EulerOrder::Yxz => (1, 0, 2),
+            EulerOrder::Yzx => (1, 2, 0),
+            EulerOrder::Zyx => (2, 1, 0),
+            EulerOrder::Zxy => (2, 0, 1),
+        }
+    }
+
+    fn is_cyclic(&self) -> bool {
+        matches!(self, EulerOrder::Xyz | EulerOrder::Yzx | EulerOrder::Zxy)
+    }
+}
+
+// Rotation
+
+impl<T> Quaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    pub fn identity() -> Self {
+        Self::new_from(T::one(), T::zero(), T::zero(), T::zero())
+    }
+
+    /// `axis` does not need to be normalized.
+    pub fn from_axis_angle(axis: Vec3<T>, angle: impl Into<Rad<T>>) -> Self {
+        let (x, y, z) = (axis.x(), axis.y(), axis.z());
+        let length = (x * x + y * y + z * z).sqrt();
+        if length == T::zero() {
+            return Self::identity();
+        }
+        let (sin, cos) = (angle.into() / (T::one() + T::one())).sin_cos();
+        let s = sin / length;
+        Self::new_from(cos, x * s, y * s, z * s)
+    }
+
+    /// Returns `(axis, angle)`. The axis of the identity rotation is `+X`.
+    pub fn to_axis_angle(self) -> (Vec3<T>, Rad<T>) {
+        let q = self.normalize();
+        let s = (q.b * q.b + q.c * q.c + q.d * q.d).sqrt();
+        if s <= T::epsilon() {
+            return (Vec3::from([[T::one(), T::zero(), T::zero()]]), Rad(T::zero()));
+        }
+        let angle = Rad(s.atan2(q.a)) * (T::one() + T::one());
+        (Vec3::from([[q.b / s, q.c / s, q.d / s]]), angle)
+    }
+
+    /// One angle per axis, `x` is always the rotation around X whatever the order.
+    pub fn from_euler(x: impl Into<Rad<T>>, y: impl Into<Rad<T>>, z: impl Into<Rad<T>>, order: EulerOrder) -> Self {
+        let angles = [x.into(), y.into(), z.into()];
+        let (i, j, k) = order.axes();
+        let unit = |axis: usize| {
+            let mut v = [[T::zero(); 3]];
+            v[0][axis] = T::one();
+            Vec3::from(v)
+        };
+        Self::from_axis_angle(unit(i), angles[i])
+            * Self::from_axis_angle(unit(j), angles[j])
+            * Self::from_axis_angle(unit(k), angles[k])
+    }
+
+    /// Inverse of `from_euler`, returns `[x, y, z]`. Near gimbal lock the last angle is set to zero.
+    pub fn to_euler(self, order: EulerOrder) -> [Rad<T>; 3] {
+        let m = self.to_mat3();
+        // Row-major access on column-major storage.
+        let r = |row: usize, col: usize| m[col][row];
+        let (i, j, k) = order.axes();
+        let s = if order.is_cyclic() { T::one() } else { -T::one() };
+
+        let cos_b = r(i, i).hypot(r(i, j));
+        let b = (s * r(i, k)).atan2(cos_b);
+        let (a, c);
+        if cos_b > T::epsilon().sqrt() {
+            a = (-s * r(j, k)).atan2(r(k, k));
+            c = (-s * r(i, j)).atan2(r(i, i));
+        } else {
+            a = (s * r(k, j)).atan2(r(j, j));
+            c = T::zero();
+        }
+
+        let mut angles = [Rad(T::zero()); 3];
+        angles[i] = Rad(a);
+        angles[j] = Rad(b);
+        angles[k] = Rad(c);
+        angles
+    }
+
+    /// Expects a pure rotation matrix.
+    pub fn from_mat3(m: &Mat3<T>) -> Self {
+        // Row-major access on column-major storage.
+        let r = |row: usize, col: usize| m[col][row];
+        let one = T::one();
+        let two = one + one;
+        let quarter = one / (two * two);
+        let trace = r(0, 0) + r(1, 1) + r(2, 2);
+
+        let q = if trace > T::zero() {
+            let s = (trace + one).sqrt() * two;
+            Self::new_from(
+                quarter * s,
+                (r(2, 1) - r(1, 2)) / s,
+                (r(0, 2) - r(2, 0)) / s,
+                (r(1, 0) - r(0, 1)) / s,
+            )
+        } else if r(0, 0) > r(1, 1) && r(0, 0) > r(2, 2) {
+            let s = (one + r(0, 0) - r(1, 1) - r(2, 2)).sqrt() * two;
+            Self::new_from(
+                (r(2, 1) - r(1, 2)) / s,
+                quarter * s,
+                (r(0, 1) + r(1, 0)) / s,
+                (r(0, 2) + r(2, 0)) / s,
+            )
+        } else if r(1, 1) > r(2, 2) {
+            let s = (one + r(1, 1) - r(0, 0) - r(2, 2)).sqrt() * two;
+            Self::new_from(
+                (r(0, 2) - r(2, 0)) / s,
+                (r(0, 1) + r(1, 0)) / s,
+                quarter * s,
+                (r(1, 2) + r(2, 1)) / s,
+            )
+        } else {
+            let s = (one + r(2, 2) - r(0, 0) - r(1, 1)).sqrt() * two;
+            Self::new_from(
+                (r(1, 0) - r(0, 1)) / s,
+                (r(0, 2) + r(2, 0)) / s,
+                (r(1, 2) + r(2, 1)) / s,
+                quarter * s,
+            )
+        };
+        q.normalize()
+    }
+
+    /// Uses the upper-left 3x3 block, which must be a pure rotation.
+    pub fn from_mat4(m: &Mat4<T>) -> Self {
+        Self::from_mat3(&Mat3::from([
+            [m[0][0], m[0][1], m[0][2]],
+            [m[1][0], m[1][1], m[1][2]],
+            [m[2][0], m[2][1], m[2][2]],
+        ]))
+    }
+
+    pub fn to_mat3(self) -> Mat3<T> {
+        let (w, x, y, z) = (self.a, self.b, self.c, self.d);
+        let one = T::one();
+        let two = one + one;
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, xz, yz) = (x * y, x * z, y * z);
+        let (wx, wy, wz) = (w * x, w * y, w * z);
+
+        Mat3::from([
+            [one - two * (yy + zz), two * (xy + wz), two * (xz - wy)],
+            [two * (xy - wz), one - two * (xx + zz), two * (yz + wx)],
+            [two * (xz + wy), two * (yz - wx), one - two * (xx + yy)],
+        ])
+    }
+
+    pub fn to_mat4(self) -> Mat4<T> {
+        let m = self.to_mat3();
+        let (zero, one) = (T::zero(), T::one());
+        Mat4::from([
+            [m[0][0], m[0][1], m[0][2], zero],
+            [m[1][0], m[1][1], m[1][2], zero],
+            [m[2][0], m[2][1], m[2][2], zero],
+            [zero, zero, zero, one],
+        ])
+    }
+
+    /// Rotation whose local `-Z` points along `forward` and local `+Y` leans towards `up`,
+    /// matching the camera convention of `Mat4f::look_at`.
+    pub fn look_rotation(forward: Vec3<T>, up: Vec3<T>) -> Self {
+        let normalize = |v: [T; 3]| {
+            let l = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+            [v[0] / l, v[1] / l, v[2] / l]
+        };
+        let cross = |a: [T; 3], b: [T; 3]| {
+            [
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ]
+        };
+        let f = normalize(forward.data[0]);
+        let r = normalize(cross(f, up.data[0]));
+        let u = cross(r, f);
+        Self::from_mat3(&Mat3::from([r, u, [-f[0], -f[1], -f[2]]]))
+    }
+
+    /// Rotates `v` by this unit quaternion, i.e. `q * v * q⁻¹`.
+    pub fn rotate_vec3(&self, v: Vec3<T>) -> Vec3<T> {
+        let two = T::one() + T::one();
+        let (w, qx, qy, qz) = (self.a, self.b, self.c, self.d);
+        let (vx, vy, vz) = (v.x(), v.y(), v.z());
+        // t = 2 * (q.xyz × v)
+        let tx = two * (qy * vz - qz * vy);
+        let ty = two * (qz * vx - qx * vz);
+        let tz = two * (qx * vy - qy * vx);
+        // v + w * t + q.xyz × t
+        Vec3::from([[
+            vx + w * tx + (qy * tz - qz * ty),
+            vy + w * ty + (qz * tx - qx * tz),
+            vz + w * tz + (qx * ty - qy * tx),
+        ]])
+    }
+
+    /// Angle in radians of the shortest rotation between two unit quaternions.
+    pub fn angle_between(&self, other: &Self) -> T {
+        let d = self.dot(other).abs().min(T::one());
+        (T::one() + T::one()) * d.acos()
+    }
+}
+
 impl<T> From<[T; 4]> for Quaternion<T>
 where
     T: Copy,
@@ -223,6 +549,21 @@ where
     }
 }
 
+impl<T> std::ops::Neg for Quaternion<T>
+where
+    T: Default + Copy + num_traits::Float,
+{
+    type Output = Quaternion<T>;
+    fn neg(self) -> Self::Output {
+        Self {
+            a: -self.a,
+            b: -self.b,
+            c: -self.c,
+            d: -self.d,
+        }
+    }
+}
+
 impl<T> std::ops::Mul<T> for Quaternion<T>
 where
     T: Default + Copy + num_traits::Float,
@@ -297,3 +638,211 @@ where
         *self = *self / rhs;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::random;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const ORDERS: [EulerOrder; 6] = [
+        EulerOrder::Xyz,
+        EulerOrder::Xzy,
+        EulerOrder::Yxz,
+        EulerOrder::Yzx,
+        EulerOrder::Zyx,
+        EulerOrder::Zxy,
+    ];
+
+    fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+        Vec3f::from([[x, y, z]])
+    }
+
+    fn assert_vec3_eq(a: Vec3f, b: Vec3f) {
+        for i in 0..3 {
+            assert!((a[0][i] - b[0][i]).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    fn assert_mat_eq<const N: usize>(a: &TMat<f32, N, N>, b: &TMat<f32, N, N>) {
+        for i in 0..N {
+            for j in 0..N {
+                assert!((a[i][j] - b[i][j]).abs() < 1e-4, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    /// `q` and `-q` are the same rotation.
+    fn assert_same_rotation(a: &Qua, b: &Qua) {
+        assert!(a.dot(b).abs() > 1.0 - 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..200 {
+            let q = random::rotation(&mut rng);
+            assert_same_rotation(&Qua::from_mat3(&q.to_mat3()), &q);
+            assert_same_rotation(&Qua::from_mat4(&q.to_mat4()), &q);
+        }
+        // Angles near a half turn exercise every branch of `from_mat3`.
+        for m in [
+            Mat4f::rotation_x(Rad(3.0)),
+            Mat4f::rotation_y(Rad(-3.0)),
+            Mat4f::rotation_z(Rad(3.1)),
+            Mat4f::rotation_axis(vec3(1.0, 1.0, 1.0).normalize(), Rad(3.0)),
+        ] {
+            assert_mat_eq(&Qua::from_mat4(&m).to_mat4(), &m);
+        }
+    }
+
+    #[test]
+    fn axis_angle_round_trip() {
+        let axis = vec3(1.0, 2.0, -0.5).normalize();
+        let q = Qua::from_axis_angle(axis, Rad(0.9));
+        let (back, angle) = q.to_axis_angle();
+        assert_vec3_eq(back, axis);
+        assert!((angle.0 - 0.9).abs() < 1e-5);
+        assert_eq!(Qua::identity().to_axis_angle().1, Rad(0.0));
+        assert!((Qua::identity().angle_between(&q) - 0.9).abs() < 1e-4);
+
+        // The axis does not need to be normalized.
+        assert_same_rotation(&Qua::from_axis_angle(axis * 3.0, Rad(0.9)), &q);
+        assert_same_rotation(&Qua::from_axis_angle(Vec3f::default(), Rad(0.9)), &Qua::identity());
+    }
+
+    #[test]
+    fn rotation_qua_agrees_with_rotation_axis() {
+        let axis = vec3(0.3, -1.0, 0.6).normalize();
+        for angle in [-2.5, -0.4, 0.0, 0.9, 3.0] {
+            let q = Qua::from_axis_angle(axis, Rad(angle));
+            assert_mat_eq(&Mat4f::rotation_qua(q), &Mat4f::rotation_axis(axis, Rad(angle)));
+            assert_mat_eq(&Mat3f::rotation_qua(q), &Mat3f::rotation_axis(axis, Rad(angle)));
+
+            let p = vec3(0.3, -1.0, 2.0);
+            let m = Mat4f::rotation_axis(axis, Rad(angle)) * p.extend(0.0);
+            assert_vec3_eq(q.rotate_vec3(p), m.truncate());
+        }
+    }
+
+    #[test]
+    fn euler_round_trip() {
+        let (x, y, z) = (Rad(0.3), Rad(-0.7), Deg(63.0));
+        for order in ORDERS {
+            let q = Qua::from_euler(x, y, z, order);
+            let radians = vec3(x.0, y.0, Rad::from(z).0);
+            let m = match order {
+                EulerOrder::Xyz => Mat4f::rotation_xyz(radians),
+                EulerOrder::Xzy => Mat4f::rotation_xzy(radians),
+                EulerOrder::Yxz => Mat4f::rotation_yxz(radians),
+                EulerOrder::Yzx => Mat4f::rotation_yzx(radians),
+                EulerOrder::Zyx => Mat4f::rotation_zyx(radians),
+                EulerOrder::Zxy => Mat4f::rotation_zxy(radians),
+            };
+            assert_mat_eq(&q.to_mat4(), &m);
+            assert_mat_eq(&Mat4f::rotation_euler(x, y, z, order), &m);
+            assert_mat_eq(&Mat3f::rotation_euler(x, y, z, order), &q.to_mat3());
+            let [bx, by, bz] = q.to_euler(order);
+            assert_vec3_eq(vec3(bx.0, by.0, bz.0), radians);
+        }
+    }
+
+    #[test]
+    fn euler_gimbal_lock() {
+        for order in ORDERS {
+            // The middle angle at a quarter turn locks the outer axes together.
+            let (i, j, k) = order.axes();
+            let mut angles = [Rad(0.0); 3];
+            angles[i] = Rad(0.3);
+            angles[j] = Rad(std::f32::consts::FRAC_PI_2);
+            angles[k] = Rad(0.2);
+            let [x, y, z] = angles;
+            let q = Qua::from_euler(x, y, z, order);
+            let [x, y, z] = q.to_euler(order);
+            let back = Qua::from_euler(x, y, z, order);
+            assert_mat_eq(&back.to_mat4(), &q.to_mat4());
+        }
+    }
+
+    fn assert_unit(q: &Qua) {
+        assert!(q.a.is_finite() && q.b.is_finite() && q.c.is_finite() && q.d.is_finite(), "{:?}", q);
+        assert!((q.abs() - 1.0).abs() < 1e-4, "{:?}", q);
+    }
+
+    #[test]
+    fn slerp_degenerate_inputs() {
+        let q = Qua::from_axis_angle(vec3(0.0, 1.0, 0.0), Rad(0.5));
+        let nearly = Qua::from_axis_angle(vec3(0.0, 1.0, 0.0), Rad(0.5 + 1e-4));
+        for t in [0.0, 0.3, 1.0] {
+            for (from, to) in [(q, q), (q, nearly), (q, -q), (-q, q)] {
+                let s = from.slerp(&to, t);
+                let n = from.nlerp(&to, t);
+                assert_unit(&s);
+                assert_unit(&n);
+                assert_same_rotation(&s, &q);
+                assert_same_rotation(&n, &q);
+            }
+        }
+    }
+
+    #[test]
+    fn slerp_takes_the_shortest_arc() {
+        let axis = vec3(0.0, 1.0, 0.0);
+        let from = Qua::from_axis_angle(axis, Rad(0.5));
+        // 2.5 - 0.5 = 2 radians apart, negated so the dot product is negative.
+        let to = -Qua::from_axis_angle(axis, Rad(2.5));
+        assert!(from.dot(&to) < 0.0);
+
+        assert_same_rotation(&from.slerp(&to, 0.0), &from);
+        assert_same_rotation(&from.slerp(&to, 1.0), &to);
+        for t in [0.25, 0.5, 0.75] {
+            let s = from.slerp(&to, t);
+            assert_unit(&s);
+            assert_same_rotation(&s, &Qua::from_axis_angle(axis, Rad(0.5 + 2.0 * t)));
+            assert!((from.angle_between(&s) - 2.0 * t).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn squad_degenerate_inputs() {
+        let q = Qua::from_axis_angle(vec3(1.0, 0.0, 0.0), Rad(0.8));
+        let nearly = Qua::from_axis_angle(vec3(1.0, 0.0, 0.0), Rad(0.8 + 1e-4));
+        for keys in [vec![q, q, q], vec![q, nearly, q], vec![q, -q, q, -q]] {
+            for i in 0..=20 {
+                let s = Qua::squad_path(&keys, i as f32 * 0.1);
+                assert_unit(&s);
+                assert_same_rotation(&s, &q);
+            }
+        }
+        assert_same_rotation(&Qua::squad_path(&[], 0.5), &Qua::identity());
+        assert_same_rotation(&Qua::squad_path(&[q], 0.5), &q);
+    }
+
+    #[test]
+    fn squad_passes_through_keys() {
+        let keys = [
+            Qua::identity(),
+            Qua::from_axis_angle(vec3(0.0, 1.0, 0.0), Rad(1.0)),
+            -Qua::from_axis_angle(vec3(1.0, 1.0, 0.0), Rad(2.0)),
+            Qua::from_axis_angle(vec3(0.0, 0.0, 1.0), Rad(-1.0)),
+        ];
+        for (i, key) in keys.iter().enumerate() {
+            assert_same_rotation(&Qua::squad_path(&keys, i as f32), key);
+        }
+        // Continuous, including across the flipped key.
+        let mut previous = keys[0];
+        for i in 1..=300 {
+            let s = Qua::squad_path(&keys, i as f32 * 0.01);
+            assert_unit(&s);
+            assert!(s.angle_between(&previous) < 0.1);
+            previous = s;
+        }
+    }
+
+    #[test]
+    fn look_rotation_points_forward() {
+        let q = Qua::look_rotation(vec3(1.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0));
+        assert_vec3_eq(q.rotate_vec3(vec3(0.0, 0.0, -1.0)), vec3(1.0, 0.0, -1.0).normalize());
+        assert_vec3_eq(q.rotate_vec3(vec3(0.0, 1.0, 0.0)), vec3(0.0, 1.0, 0.0));
+    }
+}
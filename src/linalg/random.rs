@@ -0,0 +1,291 @@
+/*
+    Implementation of random sampling.
+    Every function draws from the `rand::Rng` it is given,
+    so a seeded generator reproduces the same samples.
+*/
+
+use super::*;
+use geometry::Aabb;
+use rand::Rng;
+use std::f32::consts::TAU;
+
+// Directions & Shapes
+
+pub fn on_unit_circle<R: Rng + ?Sized>(rng: &mut R) -> Vec2f {
+    let angle = rng.gen::<f32>() * TAU;
+    Vec2f::from([[angle.cos(), angle.sin()]])
+}
+
+pub fn in_unit_disk<R: Rng + ?Sized>(rng: &mut R) -> Vec2f {
+    on_unit_circle(rng) * rng.gen::<f32>().sqrt()
+}
+
+pub fn on_unit_sphere<R: Rng + ?Sized>(rng: &mut R) -> Vec3f {
+    let z = 1.0 - 2.0 * rng.gen::<f32>();
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let angle = rng.gen::<f32>() * TAU;
+    Vec3f::from([[r * angle.cos(), r * angle.sin(), z]])
+}
+
+pub fn in_unit_ball<R: Rng + ?Sized>(rng: &mut R) -> Vec3f {
+    on_unit_sphere(rng) * rng.gen::<f32>().cbrt()
+}
+
+/// Unit direction within `half_angle` radians of `axis`, uniform over the spherical cap.
+pub fn in_cone<R: Rng + ?Sized>(rng: &mut R, axis: Vec3f, half_angle: f32) -> Vec3f {
+    let cos_theta = 1.0 - rng.gen::<f32>() * (1.0 - half_angle.cos());
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let angle = rng.gen::<f32>() * TAU;
+
+    let n = axis.normalize();
+    let (t, b) = orthonormal_basis(n);
+    t * (sin_theta * angle.cos()) + b * (sin_theta * angle.sin()) + n * cos_theta
+}
+
+/// Two unit vectors completing `n` to a right-handed orthonormal basis (Duff et al.).
+fn orthonormal_basis(n: Vec3f) -> (Vec3f, Vec3f) {
+    let sign = 1.0_f32.copysign(n.z());
+    let a = -1.0 / (sign + n.z());
+    let b = n.x() * n.y() * a;
+    (
+        Vec3f::from([[1.0 + sign * n.x() * n.x() * a, sign * b, -sign * n.x()]]),
+        Vec3f::from([[b, sign + n.y() * n.y() * a, -n.y()]]),
+    )
+}
+
+/// Uniform point inside the triangle `abc`, in any dimension.
+pub fn in_triangle<R: Rng + ?Sized, const L: usize>(
+    rng: &mut R,
+    a: TVec<f32, L>,
+    b: TVec<f32, L>,
+    c: TVec<f32, L>,
+) -> TVec<f32, L> {
+    let (mut u, mut v) = (rng.gen::<f32>(), rng.gen::<f32>());
+    if u + v > 1.0 {
+        (u, v) = (1.0 - u, 1.0 - v);
+    }
+    a + (b - a) * u + (c - a) * v
+}
+
+/// Uniform point in the box spanned by `min` and `max`, in any dimension.
+pub fn in_box<R: Rng + ?Sized, const L: usize>(rng: &mut R, min: TVec<f32, L>, max: TVec<f32, L>) -> TVec<f32, L> {
+    let mut p = min;
+    for (x, hi) in p.data[0].iter_mut().zip(max.data[0]) {
+        *x += (hi - *x) * rng.gen::<f32>();
+    }
+    p
+}
+
+pub fn in_aabb<R: Rng + ?Sized>(rng: &mut R, aabb: &Aabb<f32>) -> Vec3f {
+    in_box(rng, aabb.min, aabb.max)
+}
+
+/// Uniformly distributed rotation (Shoemake).
+pub fn rotation<R: Rng + ?Sized>(rng: &mut R) -> Qua {
+    let (u1, u2, u3) = (rng.gen::<f32>(), rng.gen::<f32>() * TAU, rng.gen::<f32>() * TAU);
+    let (s1, s2) = ((1.0 - u1).sqrt(), u1.sqrt());
+    Qua::new_from(s2 * u3.cos(), s1 * u2.sin(), s1 * u2.cos(), s2 * u3.sin())
+}
+
+// Poisson Disk
+
+/// Bridson's Poisson-disk sampling over `[0, size.x) × [0, size.y)`.\
+/// Points are at least `radius` apart and fill the area without large gaps.
+/// `attempts` is the number of candidates tried around each point before retiring it, 30 is typical.
+pub fn poisson_disk<R: Rng + ?Sized>(rng: &mut R, size: Vec2f, radius: f32, attempts: u32) -> Vec<Vec2f> {
+    if size.x() <= 0.0 || size.y() <= 0.0 || radius <= 0.0 {
+        return Vec::new();
+    }
+    let cell = radius / std::f32::consts::SQRT_2;
+    let columns = (size.x() / cell).ceil() as usize;
+    let rows = (size.y() / cell).ceil() as usize;
+    let mut grid: Vec<Option<usize>> = vec![None; columns * rows];
+    let cell_of = |p: Vec2f| {
+        (
+            ((p.x() / cell) as usize).min(columns - 1),
+            ((p.y() / cell) as usize).min(rows - 1),
+        )
+    };
+
+    let mut points = Vec::new();
+    let mut active = Vec::new();
+    let first = in_box(rng, Vec2f::default(), size);
+    let (cx, cy) = cell_of(first);
+    grid[cy * columns + cx] = Some(0);
+    points.push(first);
+    active.push(0);
+
+    while !active.is_empty() {
+        let slot = rng.gen_range(0..active.len());
+        let center = points[active[slot]];
+        let mut found = false;
+        for _ in 0..attempts {
+            // Uniform over the annulus between radius and 2 * radius.
+            let r = radius * (1.0 + 3.0 * rng.gen::<f32>()).sqrt();
+            let candidate = center + on_unit_circle(rng) * r;
+            if candidate.x() < 0.0 || candidate.y() < 0.0 || candidate.x() >= size.x() || candidate.y() >= size.y() {
+                continue;
+            }
+            let (cx, cy) = cell_of(candidate);
+            let near = (cy.saturating_sub(2)..(cy + 3).min(rows)).any(|y| {
+                (cx.saturating_sub(2)..(cx + 3).min(columns)).any(|x| {
+                    grid[y * columns + x]
+                        .is_some_and(|i| points[i].distance_to(&candidate) < radius)
+                })
+            });
+            if !near {
+                grid[cy * columns + cx] = Some(points.len());
+                active.push(points.len());
+                points.push(candidate);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            active.swap_remove(slot);
+        }
+    }
+    points
+}
+
+// Weighted Choice
+
+/// Index picked with probability proportional to its weight.\
+/// Returns `None` if there are no positive weights. Negative and NaN weights count as zero.
+pub fn weighted_index<R: Rng + ?Sized>(rng: &mut R, weights: &[f32]) -> Option<usize> {
+    let weight = |w: f32| if w > 0.0 { w } else { 0.0 };
+    let total: f32 = weights.iter().map(|&w| weight(w)).sum();
+    if total <= 0.0 || !total.is_finite() {
+        return None;
+    }
+    let mut target = rng.gen::<f32>() * total;
+    let mut last = None;
+    for (i, &w) in weights.iter().enumerate() {
+        if weight(w) > 0.0 {
+            if target < w {
+                return Some(i);
+            }
+            target -= w;
+            last = Some(i);
+        }
+    }
+    // Rounding can leave the target just past the final positive weight.
+    last
+}
+
+/// Item picked with probability proportional to its weight, see `weighted_index`.
+pub fn weighted_choice<'a, R: Rng + ?Sized, T>(rng: &mut R, items: &'a [(T, f32)]) -> Option<&'a T> {
+    let weights: Vec<f32> = items.iter().map(|(_, w)| *w).collect();
+    weighted_index(rng, &weights).map(|i| &items[i].0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+        Vec3f::from([[x, y, z]])
+    }
+
+    #[test]
+    fn poisson_disk_spacing() {
+        let mut rng = StdRng::seed_from_u64(15);
+        let size = Vec2f::from([[10.0, 6.0]]);
+        let radius = 0.5;
+        let points = poisson_disk(&mut rng, size, radius, 30);
+        // Well packed, a hexagonal packing would hold about 280.
+        assert!(points.len() > 150, "{}", points.len());
+        for (i, p) in points.iter().enumerate() {
+            assert!(p.x() >= 0.0 && p.y() >= 0.0 && p.x() < size.x() && p.y() < size.y(), "{p:?}");
+            for q in &points[i + 1..] {
+                assert!(p.distance_to(q) >= radius, "{p:?} {q:?}");
+            }
+        }
+
+        assert!(poisson_disk(&mut rng, size, 0.0, 30).is_empty());
+        assert!(poisson_disk(&mut rng, size, -1.0, 30).is_empty());
+        assert!(poisson_disk(&mut rng, Vec2f::from([[0.0, 5.0]]), radius, 30).is_empty());
+    }
+
+    #[test]
+    fn unit_lengths() {
+        let mut rng = StdRng::seed_from_u64(15);
+        for _ in 0..1000 {
+            assert!((on_unit_circle(&mut rng).length() - 1.0).abs() < 1e-5);
+            assert!((on_unit_sphere(&mut rng).length() - 1.0).abs() < 1e-5);
+            assert!((rotation(&mut rng).abs() - 1.0).abs() < 1e-5);
+            assert!(in_unit_disk(&mut rng).length() <= 1.0 + 1e-6);
+            assert!(in_unit_ball(&mut rng).length() <= 1.0 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn cone_stays_within_half_angle() {
+        let mut rng = StdRng::seed_from_u64(15);
+        let axes = [vec3(0.0, 0.0, 1.0), vec3(0.0, 0.0, -1.0), vec3(1.0, 2.0, -0.5), vec3(0.0, 3.0, 0.0)];
+        for axis in axes {
+            for half_angle in [0.0, 0.1, 1.0, 2.5] {
+                for _ in 0..200 {
+                    let d = in_cone(&mut rng, axis, half_angle);
+                    assert!((d.length() - 1.0).abs() < 1e-5);
+                    let angle = d.dot(&axis.normalize()).clamp(-1.0, 1.0).acos();
+                    assert!(angle <= half_angle + 1e-3, "{angle} > {half_angle}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn triangle_barycentrics() {
+        let mut rng = StdRng::seed_from_u64(15);
+        let (a, b, c) = (vec3(1.0, 0.0, 0.0), vec3(3.0, 1.0, 0.0), vec3(0.0, 2.0, 1.0));
+        // Solve p = a + (b - a) u + (c - a) v through the normal equations.
+        let (e1, e2) = (b - a, c - a);
+        let (d11, d12, d22) = (e1.dot(&e1), e1.dot(&e2), e2.dot(&e2));
+        let det = d11 * d22 - d12 * d12;
+        for _ in 0..1000 {
+            let p = in_triangle(&mut rng, a, b, c) - a;
+            let (p1, p2) = (p.dot(&e1), p.dot(&e2));
+            let u = (d22 * p1 - d12 * p2) / det;
+            let v = (d11 * p2 - d12 * p1) / det;
+            let w = 1.0 - u - v;
+            for coordinate in [u, v, w] {
+                assert!((-1e-5..=1.0 + 1e-5).contains(&coordinate), "{u} {v} {w}");
+            }
+        }
+    }
+
+    #[test]
+    fn weighted_index_skips_non_positive_weights() {
+        let mut rng = StdRng::seed_from_u64(15);
+        let weights = [0.0, 2.0, -1.0, f32::NAN, 1.0, 0.0];
+        let mut counts = [0; 6];
+        for _ in 0..3000 {
+            counts[weighted_index(&mut rng, &weights).unwrap()] += 1;
+        }
+        assert_eq!([counts[0], counts[2], counts[3], counts[5]], [0; 4]);
+        // About 2:1.
+        assert!((1700..2300).contains(&counts[1]), "{counts:?}");
+
+        assert_eq!(weighted_index(&mut rng, &[]), None);
+        assert_eq!(weighted_index(&mut rng, &[0.0, 0.0]), None);
+        assert_eq!(weighted_index(&mut rng, &[-1.0, f32::NAN]), None);
+        assert_eq!(weighted_choice(&mut rng, &[("a", 0.0), ("b", 1.0)]), Some(&"b"));
+    }
+
+    #[test]
+    fn same_seed_same_samples() {
+        let draw = |seed: u64| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let direction = on_unit_sphere(&mut rng);
+            let rotation = rotation(&mut rng);
+            let cone = in_cone(&mut rng, vec3(0.0, 1.0, 0.0), 0.5);
+            let index = weighted_index(&mut rng, &[1.0, 2.0, 3.0]);
+            let points = poisson_disk(&mut rng, Vec2f::from([[4.0, 4.0]]), 0.5, 30);
+            (direction, rotation.to_mat4(), cone, index, points)
+        };
+        assert_eq!(draw(15), draw(15));
+        assert_ne!(draw(15), draw(16));
+    }
+}
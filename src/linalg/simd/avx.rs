@@ -0,0 +1,39 @@
+/*
+    AVX kernels for x86 / x86_64, selected at runtime.
+*/
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+use super::super::Mat4f;
+
+/// Computes two result columns per iteration: each 128-bit half of the
+/// 256-bit registers holds one column.
+///
+/// # Safety
+/// The CPU must support AVX.
+#[target_feature(enable = "avx")]
+pub(super) unsafe fn mm(lhs: &Mat4f, rhs: &Mat4f) -> Mat4f {
+    let a = lhs.data.as_ptr() as *const f32;
+    let b = rhs.data.as_ptr() as *const f32;
+    let mut result = Mat4f::default();
+    let c = result.data.as_mut_ptr() as *mut f32;
+
+    let column = |i: usize| {
+        let v = _mm_loadu_ps(a.add(i * 4));
+        _mm256_set_m128(v, v)
+    };
+    let (a0, a1, a2, a3) = (column(0), column(1), column(2), column(3));
+
+    for j in [0, 2] {
+        let pair = _mm256_loadu_ps(b.add(j * 4));
+        let mut acc = _mm256_mul_ps(a0, _mm256_permute_ps::<0x00>(pair));
+        acc = _mm256_add_ps(acc, _mm256_mul_ps(a1, _mm256_permute_ps::<0x55>(pair)));
+        acc = _mm256_add_ps(acc, _mm256_mul_ps(a2, _mm256_permute_ps::<0xAA>(pair)));
+        acc = _mm256_add_ps(acc, _mm256_mul_ps(a3, _mm256_permute_ps::<0xFF>(pair)));
+        _mm256_storeu_ps(c.add(j * 4), acc);
+    }
+    result
+}
@@ -0,0 +1,368 @@
+/*
+    Batched vertex transforms.
+
+    Vertices are gathered four at a time into structure-of-arrays registers
+    (all x, all y, ...), transformed with splatted matrix elements,
+    then scattered back to their original layout.
+*/
+
+use super::*;
+
+/// Read access to a sequence of `N`-component vertices.
+trait Vertices<const N: usize> {
+    fn count(&self) -> usize;
+    fn get(&self, index: usize) -> [f32; N];
+}
+
+trait VerticesMut<const N: usize>: Vertices<N> {
+    fn set(&mut self, index: usize, value: [f32; N]);
+}
+
+impl Vertices<3> for [Vec3f] {
+    fn count(&self) -> usize {
+        self.len()
+    }
+    fn get(&self, index: usize) -> [f32; 3] {
+        self[index].data[0]
+    }
+}
+
+impl VerticesMut<3> for [Vec3f] {
+    fn set(&mut self, index: usize, value: [f32; 3]) {
+        self[index].data[0] = value;
+    }
+}
+
+impl Vertices<4> for [Vec4f] {
+    fn count(&self) -> usize {
+        self.len()
+    }
+    fn get(&self, index: usize) -> [f32; 4] {
+        self[index].data[0]
+    }
+}
+
+impl VerticesMut<4> for [Vec4f] {
+    fn set(&mut self, index: usize, value: [f32; 4]) {
+        self[index].data[0] = value;
+    }
+}
+
+/// A 3-component attribute inside an interleaved buffer, e.g. the output of `compose_data`.\
+/// `stride` and `offset` are counted in floats.
+struct Interleaved<S> {
+    data: S,
+    stride: usize,
+    offset: usize,
+}
+
+impl<S: AsRef<[f32]>> Interleaved<S> {
+    fn new(data: S, stride: usize, offset: usize) -> Self {
+        assert!(offset + 3 <= stride, "attribute does not fit in the vertex stride");
+        Self {
+            data,
+            stride,
+            offset,
+        }
+    }
+}
+
+impl<S: AsRef<[f32]>> Vertices<3> for Interleaved<S> {
+    fn count(&self) -> usize {
+        self.data.as_ref().len() / self.stride
+    }
+    fn get(&self, index: usize) -> [f32; 3] {
+        let base = index * self.stride + self.offset;
+        let data = self.data.as_ref();
+        [data[base], data[base + 1], data[base + 2]]
+    }
+}
+
+impl<S: AsRef<[f32]> + AsMut<[f32]>> VerticesMut<3> for Interleaved<S> {
+    fn set(&mut self, index: usize, value: [f32; 3]) {
+        let base = index * self.stride + self.offset;
+        self.data.as_mut()[base..base + 3].copy_from_slice(&value);
+    }
+}
+
+/// Matrix elements splatted across lanes, `columns[c][r]`.
+struct Splatted<V> {
+    columns: [[V; 4]; 4],
+}
+
+impl<V: F32x4> Splatted<V> {
+    fn new(m: &Mat4f) -> Self {
+        Self {
+            columns: m.data.map(|column| column.map(V::splat)),
+        }
+    }
+
+    /// Transforms four vertices given as `[x, y, z, w]` lanes.
+    fn apply(&self, lanes: [V; 4]) -> [V; 4] {
+        let c = &self.columns;
+        let mut out = [V::splat(0.0); 4];
+        for (r, row) in out.iter_mut().enumerate() {
+            *row = lanes[0]
+                .mul(c[0][r])
+                .add(lanes[1].mul(c[1][r]))
+                .add(lanes[2].mul(c[2][r]))
+                .add(lanes[3].mul(c[3][r]));
+        }
+        out
+    }
+}
+
+/// Gathers up to four vertices starting at `start` into SoA lanes.
+/// Missing components (`w` for 3-component vertices) are filled with `w`.
+fn gather<V: F32x4, const N: usize, B: Vertices<N> + ?Sized>(
+    buffer: &B,
+    start: usize,
+    w: f32,
+) -> [V; 4] {
+    let mut lanes = [[0_f32; 4]; 4];
+    lanes[3] = [w; 4];
+    let end = buffer.count().min(start + 4);
+    for (lane, vertex) in (start..end).map(|i| buffer.get(i)).enumerate() {
+        for (component, value) in lanes.iter_mut().zip(vertex) {
+            component[lane] = value;
+        }
+    }
+    lanes.map(|l| V::load(&l))
+}
+
+fn scatter<V: F32x4, const N: usize, B: VerticesMut<N> + ?Sized>(
+    buffer: &mut B,
+    start: usize,
+    lanes: [V; 4],
+) {
+    let lanes = lanes.map(|l| l.to_array());
+    let end = buffer.count().min(start + 4);
+    for (lane, index) in (start..end).enumerate() {
+        buffer.set(index, std::array::from_fn(|component| lanes[component][lane]));
+    }
+}
+
+fn transform_in_place<V: F32x4, const N: usize, B: VerticesMut<N> + ?Sized>(
+    m: &Mat4f,
+    w: f32,
+    buffer: &mut B,
+) {
+    let splatted = Splatted::<V>::new(m);
+    for start in (0..buffer.count()).step_by(4) {
+        let lanes = gather::<V, N, B>(buffer, start, w);
+        scatter::<V, N, B>(buffer, start, splatted.apply(lanes));
+    }
+}
+
+fn transform_into<V, const N: usize, I, O>(m: &Mat4f, w: f32, input: &I, output: &mut O)
+where
+    V: F32x4,
+    I: Vertices<N> + ?Sized,
+    O: VerticesMut<N> + ?Sized,
+{
+    assert!(
+        output.count() >= input.count(),
+        "output buffer is smaller than the input"
+    );
+    let splatted = Splatted::<V>::new(m);
+    for start in (0..input.count()).step_by(4) {
+        let lanes = gather::<V, N, I>(input, start, w);
+        scatter::<V, N, O>(output, start, splatted.apply(lanes));
+    }
+}
+
+/// Points are transformed with `w = 1`, directions with `w = 0`.
+/// The resulting `w` is dropped, so the matrix is expected to be affine.
+/// To transform normals, use the inverse transpose of the model matrix.
+impl Mat4f {
+    pub fn transform_points(&self, points: &mut [Vec3f]) {
+        transform_in_place::<Native, 3, _>(self, 1.0, points);
+    }
+
+    pub fn transform_points_into(&self, input: &[Vec3f], output: &mut [Vec3f]) {
+        transform_into::<Native, 3, _, _>(self, 1.0, input, output);
+    }
+
+    pub fn transform_directions(&self, directions: &mut [Vec3f]) {
+        transform_in_place::<Native, 3, _>(self, 0.0, directions);
+    }
+
+    pub fn transform_directions_into(&self, input: &[Vec3f], output: &mut [Vec3f]) {
+        transform_into::<Native, 3, _, _>(self, 0.0, input, output);
+    }
+
+    pub fn transform_vec4s(&self, vectors: &mut [Vec4f]) {
+        transform_in_place::<Native, 4, _>(self, 0.0, vectors);
+    }
+
+    pub fn transform_vec4s_into(&self, input: &[Vec4f], output: &mut [Vec4f]) {
+        transform_into::<Native, 4, _, _>(self, 0.0, input, output);
+    }
+
+    /// Transforms the 3-component attribute at `offset` of every vertex in `data`.
+    /// `stride` and `offset` are counted in floats, as in `compose_data` and `Program::bind_buffer`.
+    pub fn transform_interleaved_points(&self, data: &mut [f32], stride: usize, offset: usize) {
+        transform_in_place::<Native, 3, _>(self, 1.0, &mut Interleaved::new(data, stride, offset));
+    }
+
+    pub fn transform_interleaved_points_into(
+        &self,
+        input: &[f32],
+        output: &mut [f32],
+        stride: usize,
+        offset: usize,
+    ) {
+        transform_into::<Native, 3, _, _>(
+            self,
+            1.0,
+            &Interleaved::new(input, stride, offset),
+            &mut Interleaved::new(output, stride, offset),
+        );
+    }
+
+    pub fn transform_interleaved_directions(&self, data: &mut [f32], stride: usize, offset: usize) {
+        transform_in_place::<Native, 3, _>(self, 0.0, &mut Interleaved::new(data, stride, offset));
+    }
+
+    pub fn transform_interleaved_directions_into(
+        &self,
+        input: &[f32],
+        output: &mut [f32],
+        stride: usize,
+        offset: usize,
+    ) {
+        transform_into::<Native, 3, _, _>(
+            self,
+            0.0,
+            &Interleaved::new(input, stride, offset),
+            &mut Interleaved::new(output, stride, offset),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn model() -> Mat4f {
+        Mat4f::translation_xyz(1.0, 2.0, 3.0)
+            * Mat4f::rotation_axis(Vec3f::from([[1.0, 1.0, 1.0]]).normalize(), Rad(0.8))
+            * Mat4f::scale_xyz(2.0, 1.0, 0.5)
+    }
+
+    fn random_points(rng: &mut StdRng, count: usize) -> Vec<Vec3f> {
+        (0..count)
+            .map(|_| Vec3f::from([[0; 3].map(|_| rng.gen_range(-10.0..10.0))]))
+            .collect()
+    }
+
+    /// The scalar path, one matrix-vector product per vertex.
+    fn expected(m: &Mat4f, p: &Vec3f, w: f32) -> [f32; 3] {
+        (*m * p.extend(w)).truncate().data[0]
+    }
+
+    fn assert_close(a: [f32; 3], b: [f32; 3]) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    // Counts around multiples of four cover the partially filled last batch.
+    const COUNTS: [usize; 7] = [0, 1, 3, 4, 5, 17, 1000];
+
+    #[test]
+    fn points_and_directions_match_scalar() {
+        let m = model();
+        let mut rng = StdRng::seed_from_u64(5);
+        for count in COUNTS {
+            let input = random_points(&mut rng, count);
+
+            let mut points = input.clone();
+            m.transform_points(&mut points);
+            let mut directions = input.clone();
+            m.transform_directions(&mut directions);
+            let mut points_into = vec![Vec3f::default(); count];
+            m.transform_points_into(&input, &mut points_into);
+            let mut directions_into = vec![Vec3f::default(); count];
+            m.transform_directions_into(&input, &mut directions_into);
+            let mut scalar_backend = input.clone();
+            transform_in_place::<Scalar, 3, _>(&m, 1.0, scalar_backend.as_mut_slice());
+
+            for (i, p) in input.iter().enumerate() {
+                assert_close(points[i].data[0], expected(&m, p, 1.0));
+                assert_close(points_into[i].data[0], expected(&m, p, 1.0));
+                assert_close(scalar_backend[i].data[0], expected(&m, p, 1.0));
+                assert_close(directions[i].data[0], expected(&m, p, 0.0));
+                assert_close(directions_into[i].data[0], expected(&m, p, 0.0));
+            }
+        }
+    }
+
+    #[test]
+    fn vec4s_match_scalar() {
+        let m = model();
+        let mut rng = StdRng::seed_from_u64(6);
+        for count in COUNTS {
+            let input: Vec<Vec4f> = random_points(&mut rng, count)
+                .into_iter()
+                .map(|p| p.extend(rng.gen_range(-2.0..2.0)))
+                .collect();
+            let mut vectors = input.clone();
+            m.transform_vec4s(&mut vectors);
+            let mut vectors_into = vec![Vec4f::default(); count];
+            m.transform_vec4s_into(&input, &mut vectors_into);
+
+            for (i, v) in input.iter().enumerate() {
+                let expected = m * *v;
+                for c in 0..4 {
+                    assert!((vectors[i][0][c] - expected[0][c]).abs() < 1e-4);
+                    assert!((vectors_into[i][0][c] - expected[0][c]).abs() < 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn interleaved_matches_scalar() {
+        let m = model();
+        let (stride, offset) = (5, 1);
+        let mut rng = StdRng::seed_from_u64(8);
+        for count in COUNTS {
+            let input = random_points(&mut rng, count);
+            // Neighbouring attributes hold markers that must survive untouched.
+            let data: Vec<f32> = input.iter().flat_map(|p| [9.0, p.x(), p.y(), p.z(), 7.0]).collect();
+
+            let mut points = data.clone();
+            m.transform_interleaved_points(&mut points, stride, offset);
+            let mut directions = data.clone();
+            m.transform_interleaved_directions(&mut directions, stride, offset);
+            let mut points_into = data.clone();
+            m.transform_interleaved_points_into(&data, &mut points_into, stride, offset);
+            let mut directions_into = data.clone();
+            m.transform_interleaved_directions_into(&data, &mut directions_into, stride, offset);
+
+            let attribute = |buffer: &[f32], i: usize| {
+                let base = i * stride + offset;
+                [buffer[base], buffer[base + 1], buffer[base + 2]]
+            };
+            for (i, p) in input.iter().enumerate() {
+                assert_close(attribute(&points, i), expected(&m, p, 1.0));
+                assert_close(attribute(&points_into, i), expected(&m, p, 1.0));
+                assert_close(attribute(&directions, i), expected(&m, p, 0.0));
+                assert_close(attribute(&directions_into, i), expected(&m, p, 0.0));
+                for buffer in [&points, &directions, &points_into, &directions_into] {
+                    assert_eq!((buffer[i * stride], buffer[i * stride + 4]), (9.0, 7.0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "output buffer is smaller than the input")]
+    fn short_output_panics() {
+        let input = vec![Vec3f::default(); 5];
+        let mut output = vec![Vec3f::default(); 4];
+        Mat4f::identity().transform_points_into(&input, &mut output);
+    }
+}
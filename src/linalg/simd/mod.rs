@@ -0,0 +1,279 @@
+/*
+    Portable SIMD backend.
+
+    Matrix kernels are written once against `F32x4`, a minimal 4-lane vector
+    abstraction, and instantiated with the native backend selected by `cfg`:
+    - SSE on x86 / x86_64,
+    - NEON on aarch64,
+    - plain arrays everywhere else.
+    AVX is picked at runtime for kernels that benefit from 8 lanes.
+*/
+
+mod batch;
+mod scalar;
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse"))]
+mod sse;
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod avx;
+
+#[cfg(target_arch = "aarch64")]
+mod neon;
+
+use super::*;
+
+#[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse"))]
+pub(crate) type Native = sse::F32x4;
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) type Native = neon::F32x4;
+
+#[cfg(not(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse"),
+    target_arch = "aarch64"
+)))]
+pub(crate) type Native = scalar::F32x4;
+
+pub(crate) type Scalar = scalar::F32x4;
+
+/// Four packed `f32` lanes.
+pub(crate) trait F32x4: Copy {
+    fn load(values: &[f32; 4]) -> Self;
+    fn store(self, values: &mut [f32; 4]);
+    fn splat(value: f32) -> Self;
+    fn add(self, other: Self) -> Self;
+    fn sub(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    fn sum(self) -> f32;
+    /// Treats the four vectors as rows of a 4x4 matrix.
+    fn transpose(rows: [Self; 4]) -> [Self; 4];
+
+    fn to_array(self) -> [f32; 4] {
+        let mut values = [0_f32; 4];
+        self.store(&mut values);
+        values
+    }
+}
+
+fn load_mat<V: F32x4>(m: &Mat4f) -> [V; 4] {
+    [
+        V::load(&m.data[0]),
+        V::load(&m.data[1]),
+        V::load(&m.data[2]),
+        V::load(&m.data[3]),
+    ]
+}
+
+fn store_mat<V: F32x4>(columns: [V; 4]) -> Mat4f {
+    let mut m = Mat4f::default();
+    for (column, v) in m.data.iter_mut().zip(columns) {
+        v.store(column);
+    }
+    m
+}
+
+/// Linear combination of `columns` weighted by `weights`.
+fn combine<V: F32x4>(columns: &[V; 4], weights: &[f32; 4]) -> V {
+    columns[0]
+        .mul(V::splat(weights[0]))
+        .add(columns[1].mul(V::splat(weights[1])))
+        .add(columns[2].mul(V::splat(weights[2])))
+        .add(columns[3].mul(V::splat(weights[3])))
+}
+
+pub(crate) fn mm<V: F32x4>(lhs: &Mat4f, rhs: &Mat4f) -> Mat4f {
+    let a = load_mat::<V>(lhs);
+    store_mat([
+        combine(&a, &rhs.data[0]),
+        combine(&a, &rhs.data[1]),
+        combine(&a, &rhs.data[2]),
+        combine(&a, &rhs.data[3]),
+    ])
+}
+
+pub(crate) fn mul_vec4<V: F32x4>(lhs: &Mat4f, rhs: &Vec4f) -> Vec4f {
+    let mut result = Vec4f::default();
+    combine(&load_mat::<V>(lhs), &rhs.data[0]).store(&mut result.data[0]);
+    result
+}
+
+pub(crate) fn transpose<V: F32x4>(m: &Mat4f) -> Mat4f {
+    store_mat(V::transpose(load_mat::<V>(m)))
+}
+
+/// Gauss-Jordan elimination with partial pivoting.
+/// Pivot selection is scalar, row operations are vectorized.
+pub(crate) fn inverse<V: F32x4>(m: &Mat4f) -> Option<Mat4f> {
+    // Pivots are compared against their own original row, as in `TMat::lu`.
+    let mut scale = m.data.map(|row| row.iter().fold(0_f32, |max, e| max.max(e.abs())));
+
+    // Rows of the array `m.data`, augmented with the identity.
+    let mut left = load_mat::<V>(m);
+    let mut right = load_mat::<V>(&Mat4f::identity());
+
+    for k in 0..4 {
+        let mut pivot = k;
+        let mut pivot_abs = left[k].to_array()[k].abs();
+        for (i, row) in left.iter().enumerate().skip(k + 1) {
+            let candidate = row.to_array()[k].abs();
+            if candidate > pivot_abs {
+                pivot = i;
+                pivot_abs = candidate;
+            }
+        }
+        if pivot_abs <= f32::EPSILON * scale[pivot] {
+            return None;
+        }
+        left.swap(k, pivot);
+        right.swap(k, pivot);
+        scale.swap(k, pivot);
+
+        let scale = V::splat(1.0 / left[k].to_array()[k]);
+        left[k] = left[k].mul(scale);
+        right[k] = right[k].mul(scale);
+
+        for i in 0..4 {
+            if i == k {
+                continue;
+            }
+            let factor = V::splat(left[i].to_array()[k]);
+            left[i] = left[i].sub(left[k].mul(factor));
+            right[i] = right[i].sub(right[k].mul(factor));
+        }
+    }
+    // The inverse of the array is the array of the inverse's transpose,
+    // so storing the rows back as columns yields the inverse.
+    Some(store_mat(right))
+}
+
+impl Mat4f {
+    /// Uses AVX when the CPU supports it.
+    pub fn mm_simd(&self, other: &Self) -> Self {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if std::is_x86_feature_detected!("avx") {
+            return unsafe { avx::mm(self, other) };
+        }
+        mm::<Native>(self, other)
+    }
+
+    pub fn mul_vec4_simd(&self, v: &Vec4f) -> Vec4f {
+        mul_vec4::<Native>(self, v)
+    }
+
+    pub fn transpose_simd(&self) -> Self {
+        transpose::<Native>(self)
+    }
+
+    /// Returns `None` if the matrix is singular.
+    pub fn inverse_simd(&self) -> Option<Self> {
+        inverse::<Native>(self)
+    }
+}
+
+impl Vec4f {
+    pub fn add_simd(&self, other: &Self) -> Self {
+        let mut result = Self::default();
+        Native::load(&self.data[0])
+            .add(Native::load(&other.data[0]))
+            .store(&mut result.data[0]);
+        result
+    }
+
+    pub fn dot_simd(&self, other: &Self) -> f32 {
+        Native::load(&self.data[0])
+            .mul(Native::load(&other.data[0]))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn random_mat(rng: &mut StdRng) -> Mat4f {
+        let mut m = Mat4f::identity();
+        for column in m.data.iter_mut() {
+            for e in column.iter_mut() {
+                *e += rng.gen_range(-0.5..0.5);
+            }
+        }
+        m
+    }
+
+    fn assert_close<const R: usize, const C: usize>(a: &TMat<f32, R, C>, b: &TMat<f32, R, C>, eps: f32) {
+        for i in 0..C {
+            for j in 0..R {
+                assert!((a[i][j] - b[i][j]).abs() <= eps, "{:?} != {:?}", a, b);
+            }
+        }
+    }
+
+    /// Compares every kernel instantiated with `V` against the generic `TMat` operations.
+    fn check_backend<V: F32x4>() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..200 {
+            let (a, b) = (random_mat(&mut rng), random_mat(&mut rng));
+            let v = Vec4f::from([[rng.gen(), rng.gen(), rng.gen(), rng.gen()]]);
+            assert_close(&mm::<V>(&a, &b), &(a * b), 1e-5);
+            assert_close(&mul_vec4::<V>(&a, &v), &(a * v), 1e-5);
+            assert_close(&transpose::<V>(&a), &a.transpose(), 0.0);
+            assert_close(&inverse::<V>(&a).unwrap(), &a.inverse().unwrap(), 1e-4);
+        }
+        assert!(inverse::<V>(&Mat4f::default()).is_none());
+        assert!(inverse::<V>(&Mat4f::scale_xyz(1.0, 0.0, 1.0)).is_none());
+        let small = Mat4f::scale_xyz(1e-3, 1e-3, 1e-3);
+        assert_close(&inverse::<V>(&small).unwrap(), &Mat4f::scale_xyz(1e3, 1e3, 1e3), 1e-2);
+    }
+
+    #[test]
+    fn scalar_matches_generic() {
+        check_backend::<Scalar>();
+    }
+
+    #[test]
+    fn native_matches_generic() {
+        check_backend::<Native>();
+    }
+
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse"))]
+    #[test]
+    fn sse_matches_generic() {
+        check_backend::<sse::F32x4>();
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[test]
+    fn neon_matches_generic() {
+        check_backend::<neon::F32x4>();
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn avx_matches_generic() {
+        if !std::is_x86_feature_detected!("avx") {
+            return;
+        }
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..200 {
+            let (a, b) = (random_mat(&mut rng), random_mat(&mut rng));
+            assert_close(&unsafe { avx::mm(&a, &b) }, &(a * b), 1e-5);
+        }
+    }
+
+    #[test]
+    fn public_methods_match_generic() {
+        let mut rng = StdRng::seed_from_u64(13);
+        for _ in 0..50 {
+            let (a, b) = (random_mat(&mut rng), random_mat(&mut rng));
+            let (v, w) = (Vec4f::from([a[0]]), Vec4f::from([b[1]]));
+            assert_close(&a.mm_simd(&b), &(a * b), 1e-5);
+            assert_close(&a.mul_vec4_simd(&v), &(a * v), 1e-5);
+            assert_close(&a.transpose_simd(), &a.transpose(), 0.0);
+            assert_close(&a.inverse_simd().unwrap(), &a.inverse().unwrap(), 1e-4);
+            assert_close(&v.add_simd(&w), &(v + w), 0.0);
+            assert!((v.dot_simd(&w) - v.dot(&w)).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+/*
+    NEON backend for aarch64.
+*/
+
+use std::arch::aarch64::*;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct F32x4(float32x4_t);
+
+impl super::F32x4 for F32x4 {
+    fn load(values: &[f32; 4]) -> Self {
+        unsafe {
+            Self(vld1q_f32(values.as_ptr()))
+        }
+    }
+
+    fn store(self, values: &mut [f32; 4]) {
+        unsafe {
+            vst1q_f32(values.as_mut_ptr(), self.0)
+        }
+    }
+
+    fn splat(value: f32) -> Self {
+        unsafe {
+            Self(vdupq_n_f32(value))
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        unsafe {
+            Self(vaddq_f32(self.0, other.0))
+        }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        unsafe {
+            Self(vsubq_f32(self.0, other.0))
+        }
+    }
+
+    fn mul(self, other: Self) -> Self {
+        unsafe {
+            Self(vmulq_f32(self.0, other.0))
+        }
+    }
+
+    fn sum(self) -> f32 {
+        unsafe {
+            vaddvq_f32(self.0)
+        }
+    }
+
+    fn transpose(rows: [Self; 4]) -> [Self; 4] {
+        unsafe {
+            let [r0, r1, r2, r3] = rows.map(|r| r.0);
+            // Interleave 32-bit lanes, then 64-bit halves.
+            let t0 = vreinterpretq_f64_f32(vtrn1q_f32(r0, r1));
+            let t1 = vreinterpretq_f64_f32(vtrn2q_f32(r0, r1));
+            let t2 = vreinterpretq_f64_f32(vtrn1q_f32(r2, r3));
+            let t3 = vreinterpretq_f64_f32(vtrn2q_f32(r2, r3));
+            [
+                Self(vreinterpretq_f32_f64(vtrn1q_f64(t0, t2))),
+                Self(vreinterpretq_f32_f64(vtrn1q_f64(t1, t3))),
+                Self(vreinterpretq_f32_f64(vtrn2q_f64(t0, t2))),
+                Self(vreinterpretq_f32_f64(vtrn2q_f64(t1, t3))),
+            ]
+        }
+    }
+}
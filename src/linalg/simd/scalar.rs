@@ -0,0 +1,53 @@
+/*
+    Portable fallback for targets without a SIMD backend.
+*/
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct F32x4([f32; 4]);
+
+impl F32x4 {
+    fn zip(self, other: Self, f: impl Fn(f32, f32) -> f32) -> Self {
+        let (a, b) = (self.0, other.0);
+        Self([f(a[0], b[0]), f(a[1], b[1]), f(a[2], b[2]), f(a[3], b[3])])
+    }
+}
+
+impl super::F32x4 for F32x4 {
+    fn load(values: &[f32; 4]) -> Self {
+        Self(*values)
+    }
+
+    fn store(self, values: &mut [f32; 4]) {
+        *values = self.0;
+    }
+
+    fn splat(value: f32) -> Self {
+        Self([value; 4])
+    }
+
+    fn add(self, other: Self) -> Self {
+        self.zip(other, |a, b| a + b)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self.zip(other, |a, b| a - b)
+    }
+
+    fn mul(self, other: Self) -> Self {
+        self.zip(other, |a, b| a * b)
+    }
+
+    fn sum(self) -> f32 {
+        (self.0[0] + self.0[1]) + (self.0[2] + self.0[3])
+    }
+
+    fn transpose(rows: [Self; 4]) -> [Self; 4] {
+        let mut columns = [Self([0.0; 4]); 4];
+        for (i, row) in rows.iter().enumerate() {
+            for (j, column) in columns.iter_mut().enumerate() {
+                column.0[i] = row.0[j];
+            }
+        }
+        columns
+    }
+}
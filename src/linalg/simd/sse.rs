@@ -0,0 +1,75 @@
+/*
+    SSE backend for x86 / x86_64.
+*/
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct F32x4(__m128);
+
+impl super::F32x4 for F32x4 {
+    fn load(values: &[f32; 4]) -> Self {
+        unsafe {
+            Self(_mm_loadu_ps(values.as_ptr()))
+        }
+    }
+
+    fn store(self, values: &mut [f32; 4]) {
+        unsafe {
+            _mm_storeu_ps(values.as_mut_ptr(), self.0)
+        }
+    }
+
+    fn splat(value: f32) -> Self {
+        unsafe {
+            Self(_mm_set1_ps(value))
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        unsafe {
+            Self(_mm_add_ps(self.0, other.0))
+        }
+    }
+
+    fn sub(self, other: Self) -> Self {
+        unsafe {
+            Self(_mm_sub_ps(self.0, other.0))
+        }
+    }
+
+    fn mul(self, other: Self) -> Self {
+        unsafe {
+            Self(_mm_mul_ps(self.0, other.0))
+        }
+    }
+
+    fn sum(self) -> f32 {
+        unsafe {
+            // (a, b, c, d) + (b, a, d, c), then add the upper pair onto the lower one.
+            let swapped = _mm_shuffle_ps::<0b10_11_00_01>(self.0, self.0);
+            let pairs = _mm_add_ps(self.0, swapped);
+            let upper = _mm_movehl_ps(swapped, pairs);
+            _mm_cvtss_f32(_mm_add_ss(pairs, upper))
+        }
+    }
+
+    fn transpose(rows: [Self; 4]) -> [Self; 4] {
+        unsafe {
+            let [r0, r1, r2, r3] = rows.map(|r| r.0);
+            let t0 = _mm_unpacklo_ps(r0, r1);
+            let t1 = _mm_unpacklo_ps(r2, r3);
+            let t2 = _mm_unpackhi_ps(r0, r1);
+            let t3 = _mm_unpackhi_ps(r2, r3);
+            [
+                Self(_mm_movelh_ps(t0, t1)),
+                Self(_mm_movehl_ps(t1, t0)),
+                Self(_mm_movelh_ps(t2, t3)),
+                Self(_mm_movehl_ps(t3, t2)),
+            ]
+        }
+    }
+}
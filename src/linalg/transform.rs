@@ -0,0 +1,296 @@
+/*
+    Implementation of TRS transforms.
+    A point p is mapped to T + R * (S * p).
+*/
+
+use super::*;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    pub translation: Vec3f,
+    pub rotation: Qua,
+    pub scale: Vec3f,
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self {
+            translation: Vec3f::from([[0.0, 0.0, 0.0]]),
+            rotation: Qua::identity(),
+            scale: Vec3f::from([[1.0, 1.0, 1.0]]),
+        }
+    }
+
+    pub fn new(translation: Vec3f, rotation: Qua, scale: Vec3f) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn from_translation(translation: Vec3f) -> Self {
+        Self {
+            translation,
+            ..Self::identity()
+        }
+    }
+
+    pub fn from_rotation(rotation: Qua) -> Self {
+        Self {
+            rotation,
+            ..Self::identity()
+        }
+    }
+
+    pub fn from_scale(scale: Vec3f) -> Self {
+        Self {
+            scale,
+            ..Self::identity()
+        }
+    }
+
+    /// Applies `child` first, then `self`, as in `self.to_mat4() * child.to_mat4()`.\
+    /// Exact when `self` has uniform scale; otherwise the shear a non-uniform
+    /// parent scale would introduce on a rotated child is dropped.
+    pub fn compose(&self, child: &Self) -> Self {
+        Self {
+            translation: self.transform_point(child.translation),
+            rotation: (self.rotation * child.rotation).normalize(),
+            scale: self.scale.mul_elem(&child.scale),
+        }
+    }
+
+    /// Exact for uniform scale, see `compose`.
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.conjugate();
+        let scale = self.scale.map(f32::recip);
+        let translation = scale.mul_elem(&rotation.rotate_vec3(-self.translation));
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn to_mat4(self) -> Mat4f {
+        let mut m = Mat4f::rotation_qua(self.rotation);
+        for i in 0..3 {
+            let s = self.scale[0][i];
+            for j in 0..3 {
+                m[i][j] *= s;
+            }
+        }
+        m[3][0] = self.translation.x();
+        m[3][1] = self.translation.y();
+        m[3][2] = self.translation.z();
+        m
+    }
+
+    pub fn transform_point(&self, point: Vec3f) -> Vec3f {
+        self.rotation.rotate_vec3(self.scale.mul_elem(&point)) + self.translation
+    }
+
+    /// Ignores translation.
+    pub fn transform_vector(&self, vector: Vec3f) -> Vec3f {
+        self.rotation.rotate_vec3(self.scale.mul_elem(&vector))
+    }
+
+    /// Lerps translation and scale, slerps rotation.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(&other.translation, t),
+            rotation: self.rotation.slerp(&other.rotation, t),
+            scale: self.scale.lerp(&other.scale, t),
+        }
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl std::ops::Mul for Transform {
+    type Output = Transform;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.compose(&rhs)
+    }
+}
+
+impl std::ops::MulAssign for Transform {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.compose(&rhs);
+    }
+}
+
+impl From<Transform> for Mat4f {
+    fn from(value: Transform) -> Self {
+        value.to_mat4()
+    }
+}
+
+impl Mat4f {
+    /// Splits an affine matrix into translation, rotation and scale.\
+    /// Shear is not representable and gets folded into the rotation.
+    /// A negative determinant is expressed as a negative X scale.
+    /// Axes flattened by a zero scale are rebuilt orthogonal to the others.
+    pub fn decompose(&self) -> Transform {
+        let m = &self.data;
+        let column = |i: usize| Vec3f::from([[m[i][0], m[i][1], m[i][2]]]);
+        let (x, y, z) = (column(0), column(1), column(2));
+
+        let mut scale = Vec3f::from([[x.length(), y.length(), z.length()]]);
+        if x.cross(&y).dot(&z) < 0.0 {
+            *scale.x_mut() = -scale.x();
+        }
+
+        let mut axes = [x / scale.x(), y / scale.y(), z / scale.z()];
+        let flat: Vec<usize> = (0..3).filter(|&i| scale[0][i] == 0.0).collect();
+        let unit = |i: usize| {
+            let mut v = Vec3f::default();
+            v[0][i] = 1.0;
+            v
+        };
+        // Each axis is the cross product of the next two, keeping the basis right-handed.
+        match flat[..] {
+            [] => {}
+            [i] => axes[i] = axes[(i + 1) % 3].cross(&axes[(i + 2) % 3]).normalize(),
+            [i, j] => {
+                let k = 3 - i - j;
+                let axis = axes[k];
+                let least = (0..3).min_by(|&a, &b| axis[0][a].abs().total_cmp(&axis[0][b].abs())).unwrap();
+                axes[(k + 1) % 3] = axis.cross(&unit(least)).normalize();
+                axes[(k + 2) % 3] = axis.cross(&axes[(k + 1) % 3]);
+            }
+            _ => axes = [unit(0), unit(1), unit(2)],
+        }
+        let [x, y, z] = axes;
+        let rotation = Qua::from_mat3(&Mat3f::from([x.into(), y.into(), z.into()]));
+
+        Transform {
+            translation: column(3),
+            rotation,
+            scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+        Vec3f::from([[x, y, z]])
+    }
+
+    fn random_vec3(rng: &mut StdRng, range: std::ops::Range<f32>) -> Vec3f {
+        vec3(rng.gen_range(range.clone()), rng.gen_range(range.clone()), rng.gen_range(range))
+    }
+
+    fn assert_mat_eq(a: &Mat4f, b: &Mat4f) {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((a[i][j] - b[i][j]).abs() < 1e-4, "{a:?} != {b:?}");
+            }
+        }
+    }
+
+    fn assert_vec3_eq(a: Vec3f, b: Vec3f) {
+        assert!((a - b).length() < 1e-4, "{a:?} != {b:?}");
+    }
+
+    fn assert_orthonormal(q: &Qua) {
+        let m = Mat4f::rotation_qua(*q);
+        let columns = [0, 1, 2].map(|i| vec3(m[i][0], m[i][1], m[i][2]));
+        assert!((columns[0].cross(&columns[1]) - columns[2]).length() < 1e-5, "{q:?}");
+        assert!(columns.iter().all(|c| (c.length() - 1.0).abs() < 1e-5), "{q:?}");
+    }
+
+    /// Translation, rotation and a scale between 0.5 and 2, uniform if asked.
+    fn random_transform(rng: &mut StdRng, uniform: bool) -> Transform {
+        let scale = if uniform {
+            let s = rng.gen_range(0.5..2.0);
+            vec3(s, s, s)
+        } else {
+            random_vec3(rng, 0.5..2.0)
+        };
+        Transform::new(random_vec3(rng, -5.0..5.0), random::rotation(rng), scale)
+    }
+
+    #[test]
+    fn compose_matches_matrix_product() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let parent = random_transform(&mut rng, true);
+            let child = random_transform(&mut rng, false);
+            assert_mat_eq(&(parent * child).to_mat4(), &(parent.to_mat4() * child.to_mat4()));
+
+            let p = random_vec3(&mut rng, -3.0..3.0);
+            let expected = (child.to_mat4() * p.extend(1.0)).truncate();
+            assert_vec3_eq(child.transform_point(p), expected);
+            let expected = (child.to_mat4() * p.extend(0.0)).truncate();
+            assert_vec3_eq(child.transform_vector(p), expected);
+        }
+    }
+
+    #[test]
+    fn decompose_round_trip() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let t = random_transform(&mut rng, false);
+            let d = t.to_mat4().decompose();
+            assert_vec3_eq(d.translation, t.translation);
+            assert_vec3_eq(d.scale, t.scale);
+            assert!(d.rotation.dot(&t.rotation).abs() > 1.0 - 1e-5, "{:?} != {:?}", d.rotation, t.rotation);
+
+            // Mirrored on any axis, the flip moves to X and the matrix is preserved.
+            let axis = rng.gen_range(0..3);
+            let mut mirrored = t;
+            mirrored.scale[0][axis] = -mirrored.scale[0][axis];
+            let m = mirrored.to_mat4();
+            let d = m.decompose();
+            assert!(d.scale.x() < 0.0 && d.scale.y() > 0.0 && d.scale.z() > 0.0, "{:?}", d.scale);
+            assert_vec3_eq(d.scale.abs(), t.scale);
+            assert_orthonormal(&d.rotation);
+            assert_mat_eq(&d.to_mat4(), &m);
+        }
+    }
+
+    #[test]
+    fn decompose_zero_scale() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let scales = [
+            vec3(0.0, 2.0, 3.0),
+            vec3(2.0, 0.0, 3.0),
+            vec3(2.0, 3.0, 0.0),
+            vec3(0.0, 0.0, 3.0),
+            vec3(0.0, 2.0, 0.0),
+            vec3(2.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 0.0),
+        ];
+        for scale in scales {
+            let t = Transform::new(random_vec3(&mut rng, -5.0..5.0), random::rotation(&mut rng), scale);
+            let d = t.to_mat4().decompose();
+            assert_orthonormal(&d.rotation);
+            assert_vec3_eq(d.scale, scale);
+            assert_mat_eq(&d.to_mat4(), &t.to_mat4());
+        }
+    }
+
+    #[test]
+    fn inverse_round_trip() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let t = random_transform(&mut rng, true);
+            let inverse = t.inverse();
+            assert_mat_eq(&(t * inverse).to_mat4(), &Mat4f::identity());
+            assert_mat_eq(&(inverse * t).to_mat4(), &Mat4f::identity());
+            assert_mat_eq(&inverse.to_mat4(), &t.to_mat4().inverse().unwrap());
+            let p = random_vec3(&mut rng, -3.0..3.0);
+            assert_vec3_eq(inverse.transform_point(t.transform_point(p)), p);
+        }
+    }
+}
@@ -3,6 +3,7 @@
 */
 
 use super::aliases::*;
+use super::quaternion::SLERP_THRESHOLD;
 impl<T, const L: usize> TVec<T, L>
 where
     T: Default
@@ -11,9 +12,7 @@ where
         + std::ops::AddAssign
         + std::ops::DivAssign
         + std::ops::Mul<T, Output = T>
-        + std::ops::Div<T, Output = T>
-        + std::fmt::Display,
-    f32: From<T>,
+        + std::ops::Div<T, Output = T>,
 {
     pub fn dot(&self, other: &Self) -> T {
         let mut result = T::default();
@@ -63,13 +62,102 @@ where
         self.lerp(other, t).normalize()
     }
 
+    /// Spherical interpolation of unit vectors.\
+    /// Falls back to `nlerp` when the inputs are nearly parallel,
+    /// and rotates through an arbitrary perpendicular when they are opposite.
     pub fn slerp(&self, other : &Self, t : T) -> Self {
-        let theta = self.dot(&other).acos();
-        let alpha = ((T::one() - t) * theta).sin() / theta.sin();
-        let beta = (t * theta).sin() / theta.sin();
+        let threshold = T::from(SLERP_THRESHOLD).unwrap();
+        let cos_theta = self.dot(other).max(-T::one()).min(T::one());
+        if cos_theta > T::one() - threshold {
+            return self.nlerp(other, t);
+        }
+        if cos_theta < threshold - T::one() {
+            let half_turn = T::from(std::f64::consts::PI).unwrap() * t;
+            return *self * half_turn.cos() + self.any_perpendicular() * half_turn.sin();
+        }
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let alpha = ((T::one() - t) * theta).sin() / sin_theta;
+        let beta = (t * theta).sin() / sin_theta;
         *self * alpha + *other * beta
     }
 
+    /// Some unit vector orthogonal to `self`, found by projecting out the
+    /// basis axis along which `self` is smallest.
+    fn any_perpendicular(&self) -> Self {
+        let mut axis = 0;
+        for i in 1..L {
+            if self[0][i].abs() < self[0][axis].abs() {
+                axis = i;
+            }
+        }
+        let mut basis = Self::default();
+        basis[0][axis] = T::one();
+        let unit = self.normalize();
+        (basis - unit * unit.dot(&basis)).normalize()
+    }
+
+    pub fn mul_elem(&self, other: &Self) -> Self {
+        self.zip_map(other, |a, b| a * b)
+    }
+
+    pub fn div_elem(&self, other: &Self) -> Self {
+        self.zip_map(other, |a, b| a / b)
+    }
+
+    pub fn min(&self, other: &Self) -> Self {
+        self.zip_map(other, T::min)
+    }
+
+    pub fn max(&self, other: &Self) -> Self {
+        self.zip_map(other, T::max)
+    }
+
+    pub fn clamp(&self, min: &Self, max: &Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    pub fn abs(&self) -> Self {
+        self.map(T::abs)
+    }
+
+    pub fn floor(&self) -> Self {
+        self.map(T::floor)
+    }
+
+    pub fn ceil(&self) -> Self {
+        self.map(T::ceil)
+    }
+
+    pub fn round(&self) -> Self {
+        self.map(T::round)
+    }
+
+    pub fn signum(&self) -> Self {
+        self.map(T::signum)
+    }
+
+    /// Reflects `self` off a surface with unit `normal`.
+    pub fn reflect(&self, normal: &Self) -> Self {
+        *self - *normal * ((T::one() + T::one()) * self.dot(normal))
+    }
+
+    /// Refracts the unit vector `self` through a surface with unit `normal`,
+    /// `eta` being the ratio of refractive indices.\
+    /// Returns zero on total internal reflection, as GLSL `refract` does.
+    pub fn refract(&self, normal: &Self, eta: T) -> Self {
+        let cos_i = self.dot(normal);
+        let k = T::one() - eta * eta * (T::one() - cos_i * cos_i);
+        if k < T::zero() {
+            return Self::default();
+        }
+        *self * eta - *normal * (eta * cos_i + k.sqrt())
+    }
+
+    /// Component of `self` along `other`.
+    pub fn project_onto(&self, other: &Self) -> Self {
+        *other * (self.dot(other) / other.length_squared())
+    }
 
     // pub fn normalize(&mut self) {
     //     let length = self.length();
@@ -79,6 +167,19 @@ where
     // }
 }
 
+impl<T, const L: usize> TVec<T, L>
+where
+    T: Copy,
+{
+    pub fn map<U: Copy>(&self, f: impl Fn(T) -> U) -> TVec<U, L> {
+        TVec::from([self.data[0].map(f)])
+    }
+
+    pub fn zip_map<U: Copy, V: Copy>(&self, other: &TVec<U, L>, f: impl Fn(T, U) -> V) -> TVec<V, L> {
+        TVec::from([std::array::from_fn(|i| f(self[0][i], other[0][i]))])
+    }
+}
+
 impl<T, const U: usize> Into<[T; U]> for TVec<T, U>
 where
     T: Copy,
@@ -107,6 +208,10 @@ impl<T> Vec2<T>
 where
     T: Clone + Copy,
 {
+    pub fn extend(&self, z: T) -> Vec3<T> {
+        Vec3::from([[self.x(), self.y(), z]])
+    }
+
     pub fn x(&self) -> T {
         self[0][0]
     }
@@ -126,6 +231,14 @@ impl<T> Vec3<T>
 where
     T: Clone + Copy,
 {
+    pub fn extend(&self, w: T) -> Vec4<T> {
+        Vec4::from([[self.x(), self.y(), self.z(), w]])
+    }
+
+    pub fn truncate(&self) -> Vec2<T> {
+        Vec2::from([[self.x(), self.y()]])
+    }
+
     pub fn x(&self) -> T {
         self[0][0]
     }
@@ -151,6 +264,10 @@ impl<T> Vec4<T>
 where
     T: Clone + Copy,
 {
+    pub fn truncate(&self) -> Vec3<T> {
+        Vec3::from([[self.x(), self.y(), self.z()]])
+    }
+
     pub fn x(&self) -> T {
         self[0][0]
     }
@@ -184,4 +301,94 @@ impl Vec4f {
             [self.x() / self.w(), self.y() / self.w(), self.z() / self.w(), 1_f32]
         ])
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec3(x: f32, y: f32, z: f32) -> Vec3f {
+        Vec3f::from([[x, y, z]])
+    }
+
+    fn assert_vec3_eq(a: Vec3f, b: Vec3f) {
+        assert!((a - b).length() < 1e-6, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn elementwise() {
+        let (a, b) = (vec3(1.0, -2.0, 3.0), vec3(4.0, 0.5, -1.5));
+        assert_eq!(a.mul_elem(&b), vec3(4.0, -1.0, -4.5));
+        assert_eq!(a.div_elem(&b), vec3(0.25, -4.0, -2.0));
+        assert_eq!(a.min(&b), vec3(1.0, -2.0, -1.5));
+        assert_eq!(a.max(&b), vec3(4.0, 0.5, 3.0));
+        let (lo, hi) = (vec3(0.0, -1.0, 0.0), vec3(2.0, 1.0, 2.0));
+        assert_eq!(vec3(-1.0, 0.5, 5.0).clamp(&lo, &hi), vec3(0.0, 0.5, 2.0));
+        assert_eq!(a.clamp(&lo, &hi), vec3(1.0, -1.0, 2.0));
+        assert_eq!(a.abs(), vec3(1.0, 2.0, 3.0));
+        assert_eq!(vec3(1.5, -1.5, 2.5).floor(), vec3(1.0, -2.0, 2.0));
+        assert_eq!(vec3(1.5, -1.5, 2.5).ceil(), vec3(2.0, -1.0, 3.0));
+        assert_eq!(vec3(1.5, -1.5, 2.4).round(), vec3(2.0, -2.0, 2.0));
+        assert_eq!(vec3(3.0, -0.5, 0.0).signum(), vec3(1.0, -1.0, 1.0));
+    }
+
+    #[test]
+    fn map_and_zip_map() {
+        let v = vec3(1.0, -2.0, 3.0);
+        assert_eq!(v.map(|x| x * 2.0), vec3(2.0, -4.0, 6.0));
+        assert_eq!(v.map(|x| x > 0.0), TVec::from([[true, false, true]]));
+        let counts: TVec<i32, 3> = TVec::from([[2, 0, 1]]);
+        assert_eq!(v.zip_map(&counts, |x, n| x.powi(n)), vec3(1.0, 1.0, 3.0));
+        assert_eq!(v.zip_map(&v, |a, b| a == b), TVec::from([[true; 3]]));
+    }
+
+    #[test]
+    fn reflect_and_project() {
+        let up = vec3(0.0, 1.0, 0.0);
+        assert_eq!(vec3(1.0, -1.0, 0.0).reflect(&up), vec3(1.0, 1.0, 0.0));
+        assert_eq!(vec3(1.0, 0.0, 2.0).reflect(&up), vec3(1.0, 0.0, 2.0));
+        let normal = vec3(1.0, 2.0, -2.0) / 3.0;
+        let v = vec3(0.3, -1.2, 0.7);
+        let reflected = v.reflect(&normal);
+        assert!((reflected.length() - v.length()).abs() < 1e-6);
+        assert!((reflected.dot(&normal) + v.dot(&normal)).abs() < 1e-6);
+        assert_vec3_eq(reflected.reflect(&normal), v);
+
+        let onto = vec3(2.0, 0.0, 0.0);
+        assert_eq!(vec3(3.0, 4.0, 0.0).project_onto(&onto), vec3(3.0, 0.0, 0.0));
+        let projected = v.project_onto(&normal);
+        assert_vec3_eq(projected, normal * v.dot(&normal));
+        assert!((v - projected).dot(&normal).abs() < 1e-6);
+    }
+
+    #[test]
+    fn refract_follows_snell() {
+        let normal = vec3(0.0, 1.0, 0.0);
+        assert_vec3_eq(vec3(0.0, -1.0, 0.0).refract(&normal, 0.75), vec3(0.0, -1.0, 0.0));
+        assert_vec3_eq(vec3(0.6, -0.8, 0.0).refract(&normal, 1.0), vec3(0.6, -0.8, 0.0));
+
+        let incident = vec3(0.6, -0.8, 0.0);
+        let eta = 1.0 / 1.5;
+        let refracted = incident.refract(&normal, eta);
+        assert!((refracted.length() - 1.0).abs() < 1e-6);
+        assert!((refracted.x() - eta * incident.x()).abs() < 1e-6);
+        assert!(refracted.y() < 0.0 && refracted.z() == 0.0);
+
+        // sin = 0.8 from glass into air exceeds the critical angle.
+        let grazing = vec3(0.8, -0.6, 0.0);
+        assert_eq!(grazing.refract(&normal, 1.5), Vec3f::default());
+        assert_ne!(grazing.refract(&normal, 1.2), Vec3f::default());
+    }
+
+    #[test]
+    fn extend_and_truncate() {
+        let v = Vec2f::from([[1.0, 2.0]]);
+        let v3 = v.extend(3.0);
+        let v4 = v3.extend(4.0);
+        assert_eq!(v3, vec3(1.0, 2.0, 3.0));
+        assert_eq!(v4, Vec4f::from([[1.0, 2.0, 3.0, 4.0]]));
+        assert_eq!(v4.truncate(), v3);
+        assert_eq!(v3.truncate(), v);
+        assert_eq!(Vec4f::from([[2.0, 4.0, 6.0, 2.0]]).homogenize(), Vec4f::from([[1.0, 2.0, 3.0, 1.0]]));
+    }
+}
@@ -37,12 +37,12 @@ fn main() {
         0.5, 0.5, 0.0, 0.5, -0.5, 0.0, -0.5, -0.5, 0.0, -0.5, 0.5, 0.0,
     ];
 
-    let color: Vec<f32> = vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0];
+    let color = Color::flatten(&[Color::RED, Color::GREEN, Color::BLUE, Color::YELLOW]);
 
     let mut tex_coord: Vec<f32> = vec![1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
     tex_coord.reverse();
 
-    let data = compose_data(&vec![(&vertices, 3), (&color, 3), (&tex_coord, 2)]);
+    let data = compose_data(&vec![(&vertices, 3), (&color, 4), (&tex_coord, 2)]);
 
     println!("{:?}", data);
 
@@ -50,13 +50,14 @@ fn main() {
 
     let attrib = vec![
         (0, 3, gl::FLOAT, gl::FALSE, 0),
-        (1, 3, gl::FLOAT, gl::FALSE, 3),
-        (2, 2, gl::FLOAT, gl::FALSE, 6),
+        (1, 4, gl::FLOAT, gl::FALSE, 3),
+        (2, 2, gl::FLOAT, gl::FALSE, 7),
     ];
 
-    program.bind_pre_draw(Box::new(|| unsafe {
+    let clear_color = Color::new(0.4, 1.0, 0.0, 0.5);
+    program.bind_pre_draw(Box::new(move || unsafe {
         gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
-        gl::ClearColor(0.4, 1.0, 0.0, 0.5);
+        gl::ClearColor(clear_color.r, clear_color.g, clear_color.b, clear_color.a);
         gl::Enable(gl::BLEND);
         gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
     }));
@@ -64,9 +65,9 @@ fn main() {
 
     let vertex_src = r#"#version 330
 layout(location = 0) in vec3 aPos;
-layout(location = 1) in vec3 aCol;
+layout(location = 1) in vec4 aCol;
 layout(location = 2) in vec2 texcoord;
-out vec3 Color;
+out vec4 Color;
 out vec2 texCoords;
 uniform mat4 transform;
 void main() {
@@ -78,7 +79,7 @@ void main() {
     .to_string();
 
     let fragment_src = r#"#version 330
-in vec3 Color;
+in vec4 Color;
 in vec2 texCoords;
 out vec4 FragColor;
 uniform sampler2D tex;
@@ -96,16 +97,16 @@ void main() {
                 size.0 as f32 / size.1 as f32
             };
             let mut transform = Mat4f::identity();
-            // transform *= Mat4f::perspective(45_f32, aspect, -1.0, 10.0);
+            // transform *= Mat4f::perspective(Deg(45.0), aspect, -1.0, 10.0);
             transform *= Mat4f::ortho(-1.0, 1.0, -1.0, 1.0, -100.1, 100.0);
             transform *= Mat4f::rotation_axis(
                 Vec3f::from([[1.0, 1.0, 1.0]]).normalize(),
-                (glfw.get_time() as f32 * 100.0).to_radians(),
+                Deg(glfw.get_time() as f32 * 100.0),
             );
             //println!("{:?}", transform);
             program.set_uniform(
                 "transform",
-                transform, //Mat4f::rotation_x(glfw.get_time() as f32 * 24.0) * Mat4f::translation_xyz((glfw.get_time() * 20.0).sin() as f32, (glfw.get_time() * 13.0).sin() as f32, 0.0)
+                transform, //Mat4f::rotation_x(Deg(glfw.get_time() as f32 * 24.0)) * Mat4f::translation_xyz((glfw.get_time() * 20.0).sin() as f32, (glfw.get_time() * 13.0).sin() as f32, 0.0)
             );
             program.draw();
         },
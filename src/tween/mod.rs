@@ -14,6 +14,8 @@ mod tweener;
 
 pub use tweener::*;
 
+use crate::linalg::{curve::Curve, Interpolate, TVec};
+
 pub struct Tween<'a> {
     //pub value: f32,
     pub tween_function: &'a dyn Fn(f32) -> f32,
@@ -30,4 +32,17 @@ impl<'a> Tween<'a> {
     pub fn get(&self, value : f32) -> f32{
         (self.tween_function)(value)
     }
+
+    /// Eases `value` and evaluates `curve` at the resulting parameter.\
+    /// Wrap the curve in `curve::ArcLength` for motion at constant speed under `ease_linear`.
+    pub fn along<const L: usize>(&self, curve: &impl Curve<f32, L>, value: f32) -> TVec<f32, L> {
+        curve.point(self.get(value))
+    }
+
+    /// Eases `value` and blends `from` into `to` with their natural interpolation,\
+    /// so vectors, quaternions, colors or transforms can all be animated.
+    pub fn animate<V: Interpolate>(&self, from: &V, to: &V, value: f32) -> V {
+        let t = <V::Scalar as num_traits::NumCast>::from(self.get(value)).unwrap();
+        from.interpolate(to, t)
+    }
 }